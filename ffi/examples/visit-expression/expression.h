@@ -36,6 +36,7 @@ enum OpType {
   Distinct,
   In,
   NotIn,
+  StartsWith,
 };
 enum LitType {
   Integer,
@@ -56,11 +57,13 @@ enum LitType {
   Array,
   Map
 };
-enum ExpressionType { BinOp, Variadic, Literal, Unary, Column };
+enum ExpressionType { BinOp, Variadic, Literal, Unary, Column, Cast };
 enum VariadicType {
   And,
   Or,
   StructExpression,
+  Coalesce,
+  If,
 };
 enum UnaryType { Not, IsNull };
 typedef struct {
@@ -83,6 +86,10 @@ struct Unary {
   enum UnaryType type;
   ExpressionItemList sub_expr;
 };
+struct Cast {
+  char* data_type;
+  ExpressionItemList sub_expr;
+};
 struct BinaryData {
   uint8_t* buf;
   uintptr_t len;
@@ -175,6 +182,7 @@ DEFINE_BINOP(visit_expr_ne, NotEqual)
 DEFINE_BINOP(visit_expr_distinct, Distinct)
 DEFINE_BINOP(visit_expr_in, In)
 DEFINE_BINOP(visit_expr_not_in, NotIn)
+DEFINE_BINOP(visit_expr_starts_with, StartsWith)
 #undef DEFINE_BINOP
 
 /*************************************************************
@@ -271,6 +279,8 @@ void visit_expr_variadic(void* data,
 DEFINE_VARIADIC(visit_expr_and, And)
 DEFINE_VARIADIC(visit_expr_or, Or)
 DEFINE_VARIADIC(visit_expr_struct_expr, StructExpression)
+DEFINE_VARIADIC(visit_expr_coalesce, Coalesce)
+DEFINE_VARIADIC(visit_expr_if, If)
 #undef DEFINE_VARIADIC
 
 void visit_expr_array_literal(void* data, uintptr_t sibling_list_id, uintptr_t child_list_id) {
@@ -314,6 +324,19 @@ DEFINE_UNARY(visit_expr_is_null, IsNull)
 DEFINE_UNARY(visit_expr_not, Not)
 #undef DEFINE_UNARY
 
+/*************************************************************
+ * Cast Expression
+ ************************************************************/
+void visit_expr_cast(void* data,
+                     uintptr_t sibling_list_id,
+                     uintptr_t child_list_id,
+                     KernelStringSlice data_type) {
+  struct Cast* cast = malloc(sizeof(struct Cast));
+  cast->data_type = allocate_string(data_type);
+  cast->sub_expr = get_expr_list(data, child_list_id);
+  put_expr_item(data, sibling_list_id, cast, Cast);
+}
+
 /*************************************************************
  * Column Expression
  ************************************************************/
@@ -371,6 +394,10 @@ ExpressionItemList construct_expression(SharedExpression* expression) {
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
     .visit_not_in = visit_expr_not_in,
+    .visit_starts_with = visit_expr_starts_with,
+    .visit_cast = visit_expr_cast,
+    .visit_coalesce = visit_expr_coalesce,
+    .visit_if = visit_expr_if,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
@@ -418,6 +445,10 @@ ExpressionItemList construct_predicate(SharedPredicate* predicate) {
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
     .visit_not_in = visit_expr_not_in,
+    .visit_starts_with = visit_expr_starts_with,
+    .visit_cast = visit_expr_cast,
+    .visit_coalesce = visit_expr_coalesce,
+    .visit_if = visit_expr_if,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
@@ -502,6 +533,13 @@ void free_expression_item(ExpressionItem ref) {
       free(ref.ref);
       break;
     }
+    case Cast: {
+      struct Cast* cast = ref.ref;
+      free(cast->data_type);
+      free_expression_list(cast->sub_expr);
+      free(cast);
+      break;
+    }
   }
 }
 void free_expression_list(ExpressionItemList list) {
@@ -56,6 +56,7 @@ pub enum KernelError {
     LiteralExpressionTransformError,
     CheckpointWriteError,
     SchemaError,
+    LogCompactionWriteError,
 }
 
 impl From<Error> for KernelError {
@@ -65,6 +66,7 @@ impl From<Error> for KernelError {
             #[cfg(any(feature = "default-engine", feature = "sync-engine"))]
             Error::Arrow(_) => KernelError::ArrowError,
             Error::CheckpointWrite(_) => KernelError::CheckpointWriteError,
+            Error::LogCompactionWrite(_) => KernelError::LogCompactionWriteError,
             Error::EngineDataType(_) => KernelError::EngineDataTypeError,
             Error::Extract(..) => KernelError::ExtractError,
             Error::Generic(_) => KernelError::GenericError,
@@ -200,6 +200,15 @@ pub extern "C" fn visit_predicate_eq(
     visit_predicate_binary(state, BinaryPredicateOp::Equal, a, b)
 }
 
+#[no_mangle]
+pub extern "C" fn visit_predicate_starts_with(
+    state: &mut KernelExpressionVisitorState,
+    a: usize,
+    b: usize,
+) -> usize {
+    visit_predicate_binary(state, BinaryPredicateOp::StartsWith, a, b)
+}
+
 /// # Safety
 /// The string slice must be valid
 #[no_mangle]
@@ -6,8 +6,8 @@ use std::ffi::c_void;
 use crate::{handle::Handle, kernel_string_slice, KernelStringSlice};
 use delta_kernel::expressions::{
     ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
-    Expression, JunctionPredicate, JunctionPredicateOp, MapData, Predicate, Scalar, StructData,
-    UnaryPredicate, UnaryPredicateOp,
+    CastExpression, Expression, IfExpression, JunctionPredicate, JunctionPredicateOp, MapData,
+    Predicate, Scalar, StructData, UnaryPredicate, UnaryPredicateOp,
 };
 
 /// Free the memory the passed SharedExpression
@@ -54,6 +54,8 @@ type VisitJunctionFn =
 ///      - For an array literal, visit each of the elements.
 ///      - For a junction `and` or `or` expression, visit each sub-expression.
 ///      - For a binary operator expression, visit the left and right operands.
+///      - For a cast or coalesce expression, visit each sub-expression.
+///      - For an if expression, visit the condition, then the then and else operands.
 ///      - For a unary `is null` or `not` expression, visit the sub-expression.
 ///  3. When visiting a complex expression, the kernel also passes the "child list" containing
 ///     that element's (already-visited) children.
@@ -173,6 +175,9 @@ pub struct EngineExpressionVisitor {
     /// Visits the `NotIn` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_not_in: VisitBinaryFn,
+    /// Visits the `StartsWith` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands will be in a _two_ item list identified by `child_list_id`
+    pub visit_starts_with: VisitBinaryFn,
     /// Visits the `Add` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_add: VisitBinaryFn,
@@ -192,6 +197,23 @@ pub struct EngineExpressionVisitor {
     /// The sub-expressions of the `StructExpression` are in a list identified by `child_list_id`
     pub visit_struct_expr:
         extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize),
+    /// Visits a `Cast` expression belonging to the list identified by `sibling_list_id`.
+    /// The expression to cast is in a _one_ item list identified by `child_list_id`, and
+    /// `data_type` is the name of the type to cast to, e.g. `long` or `decimal(10,2)`.
+    pub visit_cast: extern "C" fn(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        child_list_id: usize,
+        data_type: KernelStringSlice,
+    ),
+    /// Visits a `Coalesce` expression belonging to the list identified by `sibling_list_id`.
+    /// The sub-expressions of the `Coalesce` are in a list identified by `child_list_id`
+    pub visit_coalesce:
+        extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize),
+    /// Visits an `If` expression belonging to the list identified by `sibling_list_id`.
+    /// The condition, then and else operands will be in a _three_ item list identified by
+    /// `child_list_id`
+    pub visit_if: extern "C" fn(data: *mut c_void, sibling_list_id: usize, child_list_id: usize),
 }
 
 /// Visit the expression of the passed [`SharedExpression`] Handle using the provided `visitor`.
@@ -436,6 +458,37 @@ fn visit_expression_impl(
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
+        Expression::Cast(CastExpression { expr, data_type }) => {
+            let child_list_id = call!(visitor, make_field_list, 1);
+            visit_expression_impl(visitor, expr, child_list_id);
+            let data_type = data_type.to_string();
+            let data_type = kernel_string_slice!(data_type);
+            call!(
+                visitor,
+                visit_cast,
+                sibling_list_id,
+                child_list_id,
+                data_type
+            );
+        }
+        Expression::Coalesce(exprs) => {
+            let child_list_id = call!(visitor, make_field_list, exprs.len());
+            for expr in exprs {
+                visit_expression_impl(visitor, expr, child_list_id);
+            }
+            call!(visitor, visit_coalesce, sibling_list_id, child_list_id);
+        }
+        Expression::If(IfExpression {
+            condition,
+            then_expr,
+            else_expr,
+        }) => {
+            let child_list_id = call!(visitor, make_field_list, 3);
+            visit_predicate_impl(visitor, condition, child_list_id);
+            visit_expression_impl(visitor, then_expr, child_list_id);
+            visit_expression_impl(visitor, else_expr, child_list_id);
+            call!(visitor, visit_if, sibling_list_id, child_list_id);
+        }
     }
 }
 
@@ -473,6 +526,7 @@ fn visit_predicate_impl(
                 BinaryPredicateOp::Distinct => visitor.visit_distinct,
                 BinaryPredicateOp::In => visitor.visit_in,
                 BinaryPredicateOp::NotIn => visitor.visit_not_in,
+                BinaryPredicateOp::StartsWith => visitor.visit_starts_with,
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
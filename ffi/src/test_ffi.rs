@@ -78,6 +78,13 @@ pub unsafe extern "C" fn get_testing_kernel_expression() -> Handle<SharedExpress
         Scalar::Array(array_data).into(),
         Scalar::Map(map_data).into(),
         Expr::struct_from([Expr::literal(5_i32), Expr::literal(20_i64)]),
+        Expr::cast(column_expr!("col"), DataType::LONG),
+        Expr::coalesce([column_expr!("col"), Expr::literal(0)]),
+        Expr::if_then_else(
+            Pred::is_null(column_expr!("col")),
+            Expr::literal(1),
+            Expr::literal(2),
+        ),
     ];
     sub_exprs.extend(
         [
@@ -127,6 +134,7 @@ pub unsafe extern "C" fn get_testing_kernel_predicate() -> Handle<SharedPredicat
             Pred::ne(Expr::literal(20), Expr::literal(10)),
         ]),
         Pred::is_not_null(column_expr!("col")),
+        Pred::starts_with(column_expr!("col"), Expr::literal("abc")),
     ];
     sub_exprs.extend(
         [
@@ -1,20 +1,39 @@
 //! This module includes support for reading DomainMetadata from the log. NB: it is similar to the
 //! set_transaction module which reads SetTransaction actions from the log.
 //!
-//! For now, this module only exposes the ability to read a single domain at once from the log. In
-//! the future this should allow for reading all domains from the log at once.
+//! This module exposes the ability to read either a single domain or all domains from the log.
 
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
+use serde::Deserialize;
+
 use crate::actions::get_log_domain_metadata_schema;
 use crate::actions::visitors::DomainMetadataVisitor;
 use crate::actions::{DomainMetadata, DOMAIN_METADATA_NAME};
+use crate::expressions::ColumnName;
 use crate::log_segment::LogSegment;
-use crate::{DeltaResult, Engine, EngineData, Expression as Expr, PredicateRef, RowVisitor as _};
+use crate::schema::{DataType, StructType};
+use crate::table_features::ColumnMappingMode;
+use crate::utils::require;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, Expression as Expr, PredicateRef, RowVisitor as _,
+};
 
 const DOMAIN_METADATA_DOMAIN_FIELD: &str = "domain";
 
+/// The system-controlled domain which holds the clustering columns of a table that uses liquid
+/// clustering.
+const CLUSTERING_DOMAIN_NAME: &str = "delta.clustering";
+
+/// The configuration of the [`CLUSTERING_DOMAIN_NAME`] domain.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusteringDomainMetadata {
+    /// The clustering columns, each given as the path of physical field names to the column.
+    clustering_columns: Vec<Vec<String>>,
+}
+
 pub(crate) type DomainMetadataMap = HashMap<String, DomainMetadata>;
 
 /// Read the latest domain metadata for a given domain and return its `configuration`. This
@@ -34,6 +53,89 @@ pub(crate) fn domain_metadata_configuration(
         .map(|domain_metadata| domain_metadata.configuration))
 }
 
+/// Read the latest domain metadata for every domain that has not been removed. This includes
+/// 'internal' (delta.*) domains.
+pub(crate) fn all_domain_metadatas(
+    log_segment: &LogSegment,
+    engine: &dyn Engine,
+) -> DeltaResult<DomainMetadataMap> {
+    scan_domain_metadatas(log_segment, None, engine)
+}
+
+/// Read the latest configuration of every user-controlled domain that has not been removed, as a
+/// map from domain to configuration. Unlike [`all_domain_metadatas`], this leaves out 'internal'
+/// (delta.*) domains.
+pub(crate) fn user_domain_metadata_configurations(
+    log_segment: &LogSegment,
+    engine: &dyn Engine,
+) -> DeltaResult<HashMap<String, String>> {
+    let domain_metadatas = scan_domain_metadatas(log_segment, None, engine)?;
+    Ok(domain_metadatas
+        .into_values()
+        .filter(|domain_metadata| !domain_metadata.is_internal())
+        .map(|domain_metadata| (domain_metadata.domain, domain_metadata.configuration))
+        .collect())
+}
+
+/// Read the clustering columns of a table from its `delta.clustering` domain, returning `None` if
+/// the table has no such domain. The domain stores physical column names, which are resolved to
+/// logical column names using the table `schema` and its `column_mapping_mode`.
+pub(crate) fn clustering_columns(
+    log_segment: &LogSegment,
+    schema: &StructType,
+    column_mapping_mode: ColumnMappingMode,
+    engine: &dyn Engine,
+) -> DeltaResult<Option<Vec<ColumnName>>> {
+    let Some(configuration) =
+        domain_metadata_configuration(log_segment, CLUSTERING_DOMAIN_NAME, engine)?
+    else {
+        return Ok(None);
+    };
+    let clustering: ClusteringDomainMetadata = serde_json::from_str(&configuration)?;
+    let columns = clustering
+        .clustering_columns
+        .iter()
+        .map(|physical_path| logical_column_name(schema, physical_path, column_mapping_mode))
+        .collect::<DeltaResult<_>>()?;
+    Ok(Some(columns))
+}
+
+/// Resolves the path of physical field names to a column in `schema` to its logical column name.
+fn logical_column_name(
+    schema: &StructType,
+    physical_path: &[String],
+    column_mapping_mode: ColumnMappingMode,
+) -> DeltaResult<ColumnName> {
+    let not_found = || {
+        Error::generic(format!(
+            "Clustering column {physical_path:?} not found in the table schema"
+        ))
+    };
+    let mut logical_path = Vec::with_capacity(physical_path.len());
+    let mut struct_type = Some(schema);
+    for physical_name in physical_path {
+        let field = struct_type
+            .and_then(|struct_type| {
+                struct_type
+                    .fields()
+                    .find(|field| match column_mapping_mode {
+                        ColumnMappingMode::None => field.name() == physical_name,
+                        ColumnMappingMode::Id | ColumnMappingMode::Name => {
+                            field.physical_name() == physical_name
+                        }
+                    })
+            })
+            .ok_or_else(not_found)?;
+        logical_path.push(field.name().clone());
+        struct_type = match field.data_type() {
+            DataType::Struct(struct_type) => Some(struct_type),
+            _ => None,
+        };
+    }
+    require!(!logical_path.is_empty(), not_found());
+    Ok(ColumnName::new(logical_path))
+}
+
 /// Scan the entire log for all domain metadata actions but terminate early if a specific domain
 /// is provided. Note that this returns the latest domain metadata for each domain, accounting for
 /// tombstones (removed=true) - that is, removed domain metadatas will _never_ be returned.
@@ -119,9 +119,9 @@ pub(crate) fn get_log_domain_metadata_schema() -> &'static SchemaRef {
     &LOG_DOMAIN_METADATA_SCHEMA
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Schema)]
+#[derive(Debug, Clone, PartialEq, Eq, Schema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
-#[cfg_attr(test, derive(Serialize), serde(rename_all = "camelCase"))]
 pub(crate) struct Format {
     /// Name of the encoding for files in this table
     pub(crate) provider: String,
@@ -138,8 +138,8 @@ impl Default for Format {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Schema)]
-#[cfg_attr(test, derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Schema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Metadata {
     /// Unique identifier for this table
@@ -168,7 +168,6 @@ impl Metadata {
     }
 
     #[internal_api]
-    #[allow(dead_code)]
     pub(crate) fn configuration(&self) -> &HashMap<String, String> {
         &self.configuration
     }
@@ -178,7 +177,6 @@ impl Metadata {
     }
 
     #[internal_api]
-    #[allow(dead_code)]
     pub(crate) fn partition_columns(&self) -> &Vec<String> {
         &self.partition_columns
     }
@@ -578,7 +576,8 @@ pub(crate) struct Cdc {
     pub tags: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Schema)]
+#[derive(Debug, Clone, PartialEq, Eq, Schema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct SetTransaction {
     /// A unique identifier for the application performing the transaction.
@@ -588,6 +587,7 @@ pub(crate) struct SetTransaction {
     pub(crate) version: i64,
 
     /// The time when this transaction action was created in milliseconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) last_updated: Option<i64>,
 }
 
@@ -681,7 +681,7 @@ pub(crate) struct CheckpointMetadata {
 /// Note that the `delta.*` domain is reserved for internal use.
 ///
 /// [DomainMetadata]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#domain-metadata
-#[derive(Debug, Clone, PartialEq, Eq, Schema)]
+#[derive(Debug, Clone, PartialEq, Eq, Schema, Serialize, Deserialize)]
 #[internal_api]
 pub(crate) struct DomainMetadata {
     domain: String,
@@ -692,7 +692,6 @@ pub(crate) struct DomainMetadata {
 impl DomainMetadata {
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
-    #[allow(unused)]
     fn is_internal(&self) -> bool {
         self.domain.starts_with(INTERNAL_DOMAIN_PREFIX)
     }
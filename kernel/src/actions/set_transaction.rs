@@ -1,17 +1,22 @@
 use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::actions::get_log_txn_schema;
 use crate::actions::visitors::SetTransactionVisitor;
 use crate::actions::{SetTransaction, SET_TRANSACTION_NAME};
 use crate::log_segment::LogSegment;
-use crate::{DeltaResult, Engine, EngineData, Expression as Expr, PredicateRef, RowVisitor as _};
+use crate::table_properties::TableProperties;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, Expression as Expr, PredicateRef, RowVisitor as _,
+};
 
 pub(crate) use crate::actions::visitors::SetTransactionMap;
 
 pub(crate) struct SetTransactionScanner {}
 
 impl SetTransactionScanner {
-    /// Scan the Delta Log for the latest `txn` action for an application id.
+    /// Scan the Delta Log for the latest `txn` action for an application id. The transaction is
+    /// ignored if it expired, i.e. if its `lastUpdated` is at or before `expiration_timestamp`.
     ///
     /// Note that each call to this function repeats log replay. Thus, if callers are interested
     /// in multiple app ids, use `get_all` (once) instead and probe the map returned.
@@ -19,25 +24,57 @@ impl SetTransactionScanner {
         log_segment: &LogSegment,
         application_id: &str,
         engine: &dyn Engine,
+        expiration_timestamp: Option<i64>,
     ) -> DeltaResult<Option<SetTransaction>> {
-        let mut transactions =
-            scan_application_transactions(log_segment, Some(application_id), engine)?;
+        let mut transactions = scan_application_transactions(
+            log_segment,
+            Some(application_id),
+            engine,
+            expiration_timestamp,
+        )?;
         Ok(transactions.remove(application_id))
     }
 
     /// Scan the Delta Log to obtain the all of the latest `txn` actions.
     ///
     /// This performs log replay and populates the `SetTransactionMap` with the latest `txn` action
-    /// found for each app_id.
-    #[allow(unused)]
+    /// found for each app_id, leaving out the transactions that expired at `expiration_timestamp`.
     pub(crate) fn get_all(
         log_segment: &LogSegment,
         engine: &dyn Engine,
+        expiration_timestamp: Option<i64>,
     ) -> DeltaResult<SetTransactionMap> {
-        scan_application_transactions(log_segment, None, engine)
+        scan_application_transactions(log_segment, None, engine, expiration_timestamp)
     }
 }
 
+/// Returns the timestamp (in milliseconds since the Unix epoch) at or before which `txn` actions
+/// are expired, based on the table's `delta.setTransactionRetentionDuration`. Returns `None` if
+/// the table does not set a retention duration, in which case `txn` actions never expire.
+pub(crate) fn txn_expiration_timestamp(
+    table_properties: &TableProperties,
+) -> DeltaResult<Option<i64>> {
+    let Some(retention_duration) = table_properties.set_transaction_retention_duration else {
+        return Ok(None);
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::generic(format!("Failed to calculate system time: {}", e)))?;
+    let now_ms = i64::try_from(now.as_millis())
+        .map_err(|_| Error::generic("Current timestamp exceeds i64 millisecond range"))?;
+    let retention_ms = i64::try_from(retention_duration.as_millis())
+        .map_err(|_| Error::generic("Retention duration exceeds i64 millisecond range"))?;
+    Ok(Some(now_ms - retention_ms))
+}
+
+/// Returns `true` if a `txn` action last updated at `last_updated` is expired at
+/// `expiration_timestamp`. Transactions without a `lastUpdated` timestamp never expire.
+pub(crate) fn is_txn_expired(last_updated: Option<i64>, expiration_timestamp: Option<i64>) -> bool {
+    last_updated
+        .zip(expiration_timestamp)
+        .is_some_and(|(last_updated, expiration_timestamp)| last_updated <= expiration_timestamp)
+}
+
 /// Scan the entire log for all application ids but terminate early if a specific application id
 /// is provided
 // TODO: we could have this track _multiple_ application ids instead of only up to one.
@@ -45,6 +82,7 @@ fn scan_application_transactions(
     log_segment: &LogSegment,
     application_id: Option<&str>,
     engine: &dyn Engine,
+    expiration_timestamp: Option<i64>,
 ) -> DeltaResult<SetTransactionMap> {
     let mut visitor = SetTransactionVisitor::new(application_id.map(|s| s.to_owned()));
     // If a specific id is requested then we can terminate log replay early as soon as it was
@@ -58,7 +96,11 @@ fn scan_application_transactions(
         }
     }
 
-    Ok(visitor.set_transactions)
+    // Expiration applies to the latest `txn` action of each application id: once that action has
+    // expired, the application id is unknown, regardless of any older actions.
+    let mut set_transactions = visitor.set_transactions;
+    set_transactions.retain(|_, txn| !is_txn_expired(txn.last_updated, expiration_timestamp));
+    Ok(set_transactions)
 }
 
 // Factored out to facilitate testing
@@ -107,8 +149,8 @@ mod tests {
         let log_segment = snapshot.log_segment();
 
         (
-            SetTransactionScanner::get_all(log_segment, &engine).unwrap(),
-            SetTransactionScanner::get_one(log_segment, app_id, &engine).unwrap(),
+            SetTransactionScanner::get_all(log_segment, &engine, None).unwrap(),
+            SetTransactionScanner::get_one(log_segment, app_id, &engine, None).unwrap(),
         )
     }
 
@@ -10,7 +10,7 @@
 //! 1. **Protocol and Metadata**: Retains exactly one of each - keeping only the latest protocol
 //!    and metadata actions.
 //! 2. **Txn Actions**: Keeps exactly one `txn` action for each unique app ID, always selecting
-//!    the latest one encountered.
+//!    the latest one encountered, unless it expired before `txn_expiration_timestamp`.
 //! 3. **File Actions**: Resolves file actions to produce the latest state of the table, keeping
 //!    the most recent valid add actions and unexpired remove actions (tombstones) that are newer
 //!    than `minimum_file_retention_timestamp`.
@@ -30,6 +30,7 @@
 //!      actions selected for the checkpoint file
 //!
 //! [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+use crate::actions::set_transaction::is_txn_expired;
 use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
 use crate::log_replay::{
     FileActionDeduplicator, FileActionKey, HasSelectionVector, LogReplayProcessor,
@@ -58,6 +59,8 @@ pub(crate) struct CheckpointLogReplayProcessor {
     seen_txns: HashSet<String>,
     /// Minimum timestamp for file retention, used for filtering expired tombstones.
     minimum_file_retention_timestamp: i64,
+    /// Timestamp at or before which txn actions are expired, if they can expire.
+    txn_expiration_timestamp: Option<i64>,
 }
 
 /// This struct is the output of the [`CheckpointLogReplayProcessor`].
@@ -103,6 +106,7 @@ impl LogReplayProcessor for CheckpointLogReplayProcessor {
             is_log_batch,
             selection_vector,
             self.minimum_file_retention_timestamp,
+            self.txn_expiration_timestamp,
             self.seen_protocol,
             self.seen_metadata,
             &mut self.seen_txns,
@@ -132,13 +136,17 @@ impl LogReplayProcessor for CheckpointLogReplayProcessor {
 }
 
 impl CheckpointLogReplayProcessor {
-    pub(crate) fn new(minimum_file_retention_timestamp: i64) -> Self {
+    pub(crate) fn new(
+        minimum_file_retention_timestamp: i64,
+        txn_expiration_timestamp: Option<i64>,
+    ) -> Self {
         Self {
             seen_file_keys: Default::default(),
             seen_protocol: false,
             seen_metadata: false,
             seen_txns: Default::default(),
             minimum_file_retention_timestamp,
+            txn_expiration_timestamp,
         }
     }
 }
@@ -165,7 +173,8 @@ impl CheckpointLogReplayProcessor {
 /// # Non-File Action Filtering:
 /// - Keeps only the first protocol action (newest version)
 /// - Keeps only the first metadata action (most recent table metadata)
-/// - Keeps only the first txn action for each unique app ID
+/// - Keeps only the first txn action for each unique app ID, unless its lastUpdated timestamp is
+///   at or before the txnExpirationTimestamp
 ///
 /// # Excluded Actions
 /// - CommitInfo, CDC, and CheckpointMetadata actions should not appear in the action
@@ -198,6 +207,8 @@ pub(crate) struct CheckpointVisitor<'seen> {
     add_actions_count: i64,
     // i64 for comparison with remove.deletionTimestamp
     minimum_file_retention_timestamp: i64,
+    // i64 for comparison with txn.lastUpdated. None if txn actions never expire
+    txn_expiration_timestamp: Option<i64>,
     // Flag to track if we've seen a protocol action so we can keep only the first protocol action
     seen_protocol: bool,
     // Flag to track if we've seen a metadata action so we can keep only the first metadata action
@@ -221,12 +232,15 @@ impl CheckpointVisitor<'_> {
     const REMOVE_DELETION_TIMESTAMP: &'static str = "remove.deletionTimestamp";
     const PROTOCOL_MIN_READER_VERSION: &'static str = "protocol.minReaderVersion";
     const METADATA_ID: &'static str = "metaData.id";
+    const TXN_LAST_UPDATED: &'static str = "txn.lastUpdated";
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new<'seen>(
         seen_file_keys: &'seen mut HashSet<FileActionKey>,
         is_log_batch: bool,
         selection_vector: Vec<bool>,
         minimum_file_retention_timestamp: i64,
+        txn_expiration_timestamp: Option<i64>,
         seen_protocol: bool,
         seen_metadata: bool,
         seen_txns: &'seen mut HashSet<String>,
@@ -244,6 +258,7 @@ impl CheckpointVisitor<'_> {
             actions_count: 0,
             add_actions_count: 0,
             minimum_file_retention_timestamp,
+            txn_expiration_timestamp,
             seen_protocol,
             seen_metadata,
             seen_txns,
@@ -359,20 +374,31 @@ impl CheckpointVisitor<'_> {
     /// Processes a potential txn action to determine if it should be included in the checkpoint.
     ///
     /// Returns Ok(true) if the row contains a valid txn action.
-    /// Returns Ok(false) if the row doesn't contain a txn action or is a duplicate.
+    /// Returns Ok(false) if the row doesn't contain a txn action, is a duplicate or has expired.
     /// Returns Err(...) if there was an error processing the action.
-    fn check_txn_action<'a>(&mut self, i: usize, getter: &'a dyn GetData<'a>) -> DeltaResult<bool> {
+    fn check_txn_action<'a>(
+        &mut self,
+        i: usize,
+        app_id_getter: &'a dyn GetData<'a>,
+        last_updated_getter: &'a dyn GetData<'a>,
+    ) -> DeltaResult<bool> {
         // Check for txn field
-        let Some(app_id) = getter.get_str(i, "txn.appId")? else {
+        let Some(app_id) = app_id_getter.get_str(i, "txn.appId")? else {
             return Ok(false); // Not a txn action
         };
 
         // If the app ID already exists in the set, the insertion will return false,
-        // indicating that this is a duplicate.
+        // indicating that this is a duplicate. Expired txn actions are still recorded as seen, so
+        // that older txn actions for the same app ID are not included instead.
         if !self.seen_txns.insert(app_id.to_string()) {
             return Ok(false);
         }
 
+        let last_updated = last_updated_getter.get_long(i, Self::TXN_LAST_UPDATED)?;
+        if is_txn_expired(last_updated, self.txn_expiration_timestamp) {
+            return Ok(false); // Skip expired txn actions
+        }
+
         // Valid, non-duplicate txn action to be included
         Ok(true)
     }
@@ -396,7 +422,7 @@ impl CheckpointVisitor<'_> {
         // The `||` operator short-circuits the evaluation, so if any of the checks return true,
         // the rest will not be evaluated.
         let is_valid = self.check_file_action(i, getters)?
-            || self.check_txn_action(i, getters[11])?
+            || self.check_txn_action(i, getters[11], getters[12])?
             || self.check_protocol_action(i, getters[10])?
             || self.check_metadata_action(i, getters[9])?;
 
@@ -435,6 +461,7 @@ impl RowVisitor for CheckpointVisitor<'_> {
                 (STRING, column_name!("metaData.id")),
                 (INTEGER, column_name!("protocol.minReaderVersion")),
                 (STRING, column_name!("txn.appId")),
+                (LONG, column_name!("txn.lastUpdated")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -444,7 +471,7 @@ impl RowVisitor for CheckpointVisitor<'_> {
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 12,
+            getters.len() == 13,
             Error::InternalError(format!(
                 "Wrong number of visitor getters: {}",
                 getters.len()
@@ -478,7 +505,7 @@ mod tests {
     fn run_checkpoint_test(
         input_batches: Vec<(Box<dyn EngineData>, bool)>,
     ) -> DeltaResult<(Vec<FilteredEngineData>, i64, i64)> {
-        let processed_batches: Vec<_> = CheckpointLogReplayProcessor::new(0)
+        let processed_batches: Vec<_> = CheckpointLogReplayProcessor::new(0, None)
             .process_actions_iter(input_batches.into_iter().map(Ok))
             .try_collect()?;
         let total_count: i64 = processed_batches.iter().map(|b| b.actions_count).sum();
@@ -499,7 +526,8 @@ mod tests {
             &mut seen_file_keys,
             true,
             vec![true; 9],
-            0, // minimum_file_retention_timestamp (no expired tombstones)
+            0,    // minimum_file_retention_timestamp (no expired tombstones)
+            None, // txn_expiration_timestamp
             false,
             false,
             &mut seen_txns,
@@ -553,7 +581,8 @@ mod tests {
             &mut seen_file_keys,
             true,
             vec![true; 4],
-            100, // minimum_file_retention_timestamp (threshold set to 100)
+            100,  // minimum_file_retention_timestamp (threshold set to 100)
+            None, // txn_expiration_timestamp
             false,
             false,
             &mut seen_txns,
@@ -584,6 +613,7 @@ mod tests {
             false, // is_log_batch = false (checkpoint batch)
             vec![true; 1],
             0,
+            None, // txn_expiration_timestamp
             false,
             false,
             &mut seen_txns,
@@ -622,6 +652,7 @@ mod tests {
             true,
             vec![true; 3],
             0,
+            None, // txn_expiration_timestamp
             false,
             false,
             &mut seen_txns,
@@ -656,6 +687,7 @@ mod tests {
             true,
             vec![true; 3],
             0,
+            None,           // txn_expiration_timestamp
             true,           // The visior has already seen a protocol action
             true,           // The visitor has already seen a metadata action
             &mut seen_txns, // Pre-populated transaction
@@ -671,6 +703,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_checkpoint_visitor_expired_txn_actions() -> DeltaResult<()> {
+        let json_strings: StringArray = vec![
+            r#"{"txn":{"appId":"app1","version":2,"lastUpdated":100}}"#, // Expired
+            r#"{"txn":{"appId":"app1","version":1,"lastUpdated":200}}"#, // Older txn of expired app
+            r#"{"txn":{"appId":"app2","version":1,"lastUpdated":101}}"#, // Not expired
+            r#"{"txn":{"appId":"app3","version":1}}"#, // No lastUpdated, never expires
+        ]
+        .into();
+        let batch = parse_json_batch(json_strings);
+
+        let mut seen_file_keys = HashSet::new();
+        let mut seen_txns = HashSet::new();
+        let mut visitor = CheckpointVisitor::new(
+            &mut seen_file_keys,
+            true,
+            vec![true; 4],
+            0,
+            Some(100), // txn_expiration_timestamp
+            false,
+            false,
+            &mut seen_txns,
+        );
+
+        visitor.visit_rows_of(batch.as_ref())?;
+
+        let expected = vec![false, false, true, true];
+        assert_eq!(visitor.selection_vector, expected);
+        assert_eq!(visitor.actions_count, 2);
+        assert_eq!(visitor.seen_txns.len(), 3);
+
+        Ok(())
+    }
+
     #[test]
     fn test_checkpoint_visitor_duplicate_non_file_actions() -> DeltaResult<()> {
         let json_strings: StringArray = vec![
@@ -692,7 +758,8 @@ mod tests {
             &mut seen_file_keys,
             true, // is_log_batch
             vec![true; 7],
-            0, // minimum_file_retention_timestamp
+            0,    // minimum_file_retention_timestamp
+            None, // txn_expiration_timestamp
             false,
             false,
             &mut seen_txns,
@@ -89,6 +89,7 @@
 use std::sync::{Arc, LazyLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::actions::set_transaction::txn_expiration_timestamp;
 use crate::actions::{
     schemas::GetStructField, Add, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME,
     CHECKPOINT_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME,
@@ -99,13 +100,13 @@ use crate::expressions::Scalar;
 use crate::log_replay::LogReplayProcessor;
 use crate::path::ParsedLogPath;
 use crate::schema::{DataType, SchemaRef, StructField, StructType};
-use crate::snapshot::{Snapshot, LAST_CHECKPOINT_FILE_NAME};
+use crate::snapshot::{LastCheckpointHint, Snapshot, LAST_CHECKPOINT_FILE_NAME};
 use crate::{DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension, FileMeta};
 use log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
 
 use url::Url;
 
-mod log_replay;
+pub(crate) mod log_replay;
 #[cfg(test)]
 mod tests;
 
@@ -126,6 +127,7 @@ static LAST_CHECKPOINT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         StructField::nullable("parts", DataType::LONG),
         StructField::nullable("sizeInBytes", DataType::LONG),
         StructField::nullable("numOfAddFiles", DataType::LONG),
+        StructField::nullable("checksum", DataType::STRING),
     ])
     .into()
 });
@@ -272,9 +274,11 @@ impl CheckpointWriter {
         )?;
 
         // Create iterator over actions for checkpoint data
-        let checkpoint_data =
-            CheckpointLogReplayProcessor::new(self.deleted_file_retention_timestamp()?)
-                .process_actions_iter(actions);
+        let checkpoint_data = CheckpointLogReplayProcessor::new(
+            self.deleted_file_retention_timestamp()?,
+            txn_expiration_timestamp(self.snapshot.table_properties())?,
+        )
+        .process_actions_iter(actions);
 
         let checkpoint_metadata =
             is_v2_checkpoints_supported.then(|| self.create_checkpoint_metadata_batch(engine));
@@ -338,7 +342,9 @@ impl CheckpointWriter {
             .log_root
             .join(LAST_CHECKPOINT_FILE_NAME)?;
 
-        // Write the `_last_checkpoint` file to `table/_delta_log/_last_checkpoint`
+        // Write the `_last_checkpoint` file to `table/_delta_log/_last_checkpoint`. The file is
+        // overwritten in a single put, and readers ignore it if it fails checksum validation (e.g.
+        // if the engine's object store does not make the overwrite atomic).
         engine.json_handler().write_json_file(
             &last_checkpoint_path,
             Box::new(std::iter::once(data)),
@@ -460,8 +466,9 @@ fn deleted_file_retention_timestamp_with_time(
 /// - `parts` (i64, optional): Always 1 for single-file checkpoints
 /// - `sizeInBytes` (i64, optional): Size of checkpoint file in bytes
 /// - `numOfAddFiles` (i64, optional): Number of Add actions
+/// - `checksum` (string, optional): Checksum of the other fields (see
+///   [`LastCheckpointHint::compute_checksum`])
 ///
-/// TODO(#838) Add `checksum` field to `_last_checkpoint` file
 /// TODO(#839) Add `checkpoint_schema` field to `_last_checkpoint` file
 pub(crate) fn create_last_checkpoint_data(
     engine: &dyn Engine,
@@ -470,14 +477,23 @@ pub(crate) fn create_last_checkpoint_data(
     add_actions_counter: i64,
     size_in_bytes: i64,
 ) -> DeltaResult<Box<dyn EngineData>> {
+    let parts = 1i64; // parts = 1 since we only support single-part checkpoint here
+    let checksum = LastCheckpointHint::compute_checksum(&serde_json::json!({
+        "version": version,
+        "size": actions_counter,
+        "parts": parts,
+        "sizeInBytes": size_in_bytes,
+        "numOfAddFiles": add_actions_counter,
+    }));
     engine.evaluation_handler().create_one(
         LAST_CHECKPOINT_SCHEMA.clone(),
         &[
             version.into(),
             actions_counter.into(),
-            1i64.into(), // parts = 1 since we only support single-part checkpoint here
+            parts.into(),
             size_in_bytes.into(),
             add_actions_counter.into(),
+            checksum.into(),
         ],
     )
 }
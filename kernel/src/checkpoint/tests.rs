@@ -1,14 +1,18 @@
 use std::{sync::Arc, time::Duration};
 
 use super::DEFAULT_RETENTION_SECS;
-use crate::actions::{Add, Metadata, Protocol, Remove};
+use crate::actions::Protocol;
 use crate::arrow::array::{ArrayRef, StructArray};
 use crate::arrow::datatypes::{DataType, Schema};
 use crate::checkpoint::{create_last_checkpoint_data, deleted_file_retention_timestamp_with_time};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
 use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
-use crate::utils::test_utils::Action;
+use crate::snapshot::LastCheckpointHint;
+use crate::utils::test_utils::{
+    create_add_action, create_basic_protocol_action, create_metadata_action, create_remove_action,
+    write_commit_to_store, Action,
+};
 use crate::Table;
 use crate::{DeltaResult, FileMeta};
 
@@ -18,7 +22,6 @@ use arrow_55::{
 };
 
 use serde_json::{from_slice, json, Value};
-use test_utils::delta_path_for_version;
 use url::Url;
 
 #[test]
@@ -138,7 +141,15 @@ fn test_create_last_checkpoint_data() -> DeltaResult<()> {
         Field::new("parts", DataType::Int64, true),
         Field::new("sizeInBytes", DataType::Int64, true),
         Field::new("numOfAddFiles", DataType::Int64, true),
+        Field::new("checksum", DataType::Utf8, true),
     ]));
+    let checksum = LastCheckpointHint::compute_checksum(&json!({
+        "version": version,
+        "size": total_actions_counter,
+        "parts": 1,
+        "sizeInBytes": size_in_bytes,
+        "numOfAddFiles": add_actions_counter,
+    }));
     let expected = RecordBatch::try_new(
         expected_schema,
         vec![
@@ -147,6 +158,7 @@ fn test_create_last_checkpoint_data() -> DeltaResult<()> {
             create_array!(Int64, [1]),
             create_array!(Int64, [size_in_bytes]),
             create_array!(Int64, [add_actions_counter]),
+            create_array!(Utf8, [checksum]),
         ],
     )
     .unwrap();
@@ -167,36 +179,6 @@ fn new_in_memory_store() -> (Arc<InMemory>, Url) {
     )
 }
 
-/// TODO(#855): Merge copies and move to `test_utils`
-/// Writes all actions to a _delta_log json commit file in the store.
-/// This function formats the provided filename into the _delta_log directory.
-fn write_commit_to_store(
-    store: &Arc<InMemory>,
-    actions: Vec<Action>,
-    version: u64,
-) -> DeltaResult<()> {
-    let json_lines: Vec<String> = actions
-        .into_iter()
-        .map(|action| serde_json::to_string(&action).expect("action to string"))
-        .collect();
-    let content = json_lines.join("\n");
-
-    let commit_path = format!("_delta_log/{}", delta_path_for_version(version, "json"));
-
-    tokio::runtime::Runtime::new()
-        .expect("create tokio runtime")
-        .block_on(async { store.put(&Path::from(commit_path), content.into()).await })?;
-
-    Ok(())
-}
-
-/// Create a Protocol action without v2Checkpoint feature support
-fn create_basic_protocol_action() -> Action {
-    Action::Protocol(
-        Protocol::try_new(3, 7, Some(Vec::<String>::new()), Some(Vec::<String>::new())).unwrap(),
-    )
-}
-
 /// Create a Protocol action with v2Checkpoint feature support
 fn create_v2_checkpoint_protocol_action() -> Action {
     Action::Protocol(
@@ -204,37 +186,6 @@ fn create_v2_checkpoint_protocol_action() -> Action {
     )
 }
 
-/// Create a Metadata action
-fn create_metadata_action() -> Action {
-    Action::Metadata(Metadata {
-        id: "test-table".into(),
-        schema_string: "{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}".to_string(),
-        ..Default::default()
-    })
-}
-
-/// Create an Add action with the specified path
-fn create_add_action(path: &str) -> Action {
-    Action::Add(Add {
-        path: path.into(),
-        data_change: true,
-        ..Default::default()
-    })
-}
-
-/// Create a Remove action with the specified path
-///
-/// The remove action has deletion_timestamp set to i64::MAX to ensure the
-/// remove action is not considered expired during testing.
-fn create_remove_action(path: &str) -> Action {
-    Action::Remove(Remove {
-        path: path.into(),
-        data_change: true,
-        deletion_timestamp: Some(i64::MAX), // Ensure the remove action is not expired
-        ..Default::default()
-    })
-}
-
 /// Helper to verify the contents of the `_last_checkpoint` file
 fn assert_last_checkpoint_contents(
     store: &Arc<InMemory>,
@@ -244,13 +195,14 @@ fn assert_last_checkpoint_contents(
     expected_size_in_bytes: u64,
 ) -> DeltaResult<()> {
     let last_checkpoint_data = read_last_checkpoint_file(store)?;
-    let expected_data = json!({
+    let mut expected_data = json!({
         "version": expected_version,
         "size": expected_size,
         "parts": 1,
         "sizeInBytes": expected_size_in_bytes,
         "numOfAddFiles": expected_num_add_files,
     });
+    expected_data["checksum"] = LastCheckpointHint::compute_checksum(&expected_data).into();
     assert_eq!(last_checkpoint_data, expected_data);
     Ok(())
 }
@@ -0,0 +1,243 @@
+//! This module implements reading and writing version checksum (`<version>.crc`) files.
+//!
+//! A version checksum file is an optional file written alongside a commit which summarizes the
+//! state of the table as of that version: the number and total size of the active files, the
+//! protocol and metadata, and (optionally) the set transactions and domain metadata. Readers may
+//! use it to validate the table state, or to load a snapshot without log replay.
+//!
+//! The checksum for a new version is computed incrementally from the checksum of the previous
+//! version and the actions of the commit whenever the previous checksum is available. Otherwise,
+//! it is computed from the full state of the read snapshot (which requires log replay).
+//!
+//! [More info]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#version-checksum-file
+use std::collections::HashMap;
+use std::iter;
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::set_transaction::{txn_expiration_timestamp, SetTransactionScanner};
+use crate::actions::{
+    domain_metadata::all_domain_metadatas, schemas::ToSchema as _, DomainMetadata, Metadata,
+    Protocol, SetTransaction,
+};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::Scalar;
+use crate::path::ParsedLogPath;
+use crate::scan::state::{DvInfo, Stats};
+use crate::schema::{
+    column_name, ColumnName, ColumnNamesAndTypes, DataType, SchemaRef, StructField, StructType,
+};
+use crate::snapshot::Snapshot;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension as _, ExpressionRef,
+    StorageHandler, Version,
+};
+
+use delta_kernel_derive::Schema;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use url::Url;
+
+#[cfg(test)]
+mod tests;
+
+/// Schema of the single-column batch holding the serialized checksum, which is parsed into
+/// [`VersionChecksum`]'s schema before being written out.
+static CRC_JSON_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::not_null(
+        "json",
+        DataType::STRING,
+    )]))
+});
+
+static CRC_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| Arc::new(VersionChecksum::to_schema()));
+
+/// The contents of a version checksum file. Fields that the kernel does not track (such as the
+/// file size histogram) are ignored when reading and never written.
+#[derive(Debug, Clone, PartialEq, Eq, Schema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VersionChecksum {
+    /// Total size of the table in bytes, i.e. the sum of the sizes of all active files
+    pub(crate) table_size_bytes: i64,
+    /// Number of active files in the table
+    pub(crate) num_files: i64,
+    /// Number of metadata actions in the table state. Always 1.
+    pub(crate) num_metadata: i64,
+    /// Number of protocol actions in the table state. Always 1.
+    pub(crate) num_protocol: i64,
+    /// The in-commit timestamp of this version, if in-commit timestamps are enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) in_commit_timestamp_opt: Option<i64>,
+    /// The latest `txn` action of every application, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) set_transactions: Option<Vec<SetTransaction>>,
+    /// All active (not removed) domain metadata, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) domain_metadata: Option<Vec<DomainMetadata>>,
+    /// The table metadata
+    pub(crate) metadata: Metadata,
+    /// The table protocol
+    pub(crate) protocol: Protocol,
+}
+
+impl VersionChecksum {
+    /// Compute the checksum of the given snapshot from its full state. This requires log replay.
+    pub(crate) fn try_new_from_snapshot(
+        snapshot: Arc<Snapshot>,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Self> {
+        fn visit_file(
+            totals: &mut (i64, i64),
+            _: &str,
+            size: i64,
+            _: Option<Stats>,
+            _: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            totals.0 += 1;
+            totals.1 += size;
+        }
+
+        let log_segment = snapshot.log_segment();
+        let expiration_timestamp = txn_expiration_timestamp(snapshot.table_properties())?;
+        let set_transactions =
+            SetTransactionScanner::get_all(log_segment, engine, expiration_timestamp)?;
+        let domain_metadata = all_domain_metadatas(log_segment, engine)?;
+        let mut totals = (0, 0);
+        for scan_metadata in snapshot
+            .clone()
+            .scan_builder()
+            .build()?
+            .scan_metadata(engine)?
+        {
+            totals = scan_metadata?.visit_scan_files(totals, visit_file)?;
+        }
+        let (num_files, table_size_bytes) = totals;
+        Ok(Self {
+            table_size_bytes,
+            num_files,
+            num_metadata: 1,
+            num_protocol: 1,
+            in_commit_timestamp_opt: None,
+            set_transactions: Some(set_transactions.into_values().collect()),
+            domain_metadata: Some(domain_metadata.into_values().collect()),
+            metadata: snapshot.metadata().clone(),
+            protocol: snapshot.protocol().clone(),
+        })
+    }
+
+    /// Compute the checksum of the version following this one, given the actions of its commit:
+    /// the added files and the set transactions. Commits written by the kernel never remove files
+    /// or change the protocol, metadata or domain metadata.
+    pub(crate) fn apply_commit(
+        mut self,
+        added_files: AddedFiles,
+        set_transactions: &[SetTransaction],
+    ) -> Self {
+        self.num_files += added_files.num_files;
+        self.table_size_bytes += added_files.size_bytes;
+        // The checksum has no in-commit timestamp unless the commit has one
+        self.in_commit_timestamp_opt = None;
+        // If the previous checksum omitted the set transactions then so must this one, since we
+        // only know about the transactions of this commit.
+        if let Some(txns) = self.set_transactions.as_mut() {
+            txns.retain(|txn| !set_transactions.iter().any(|t| t.app_id == txn.app_id));
+            txns.extend(set_transactions.iter().cloned());
+        }
+        self
+    }
+
+    /// Write this checksum as the `<version>.crc` file of the table, without overwriting any
+    /// existing file.
+    pub(crate) fn write(
+        &self,
+        engine: &dyn Engine,
+        table_root: &Url,
+        version: Version,
+    ) -> DeltaResult<()> {
+        let path = ParsedLogPath::new_crc(table_root, version)?;
+        // Engines can only write JSON from engine data, so we round-trip the serialized checksum
+        // through the JSON parser to get engine data with the checksum schema.
+        let json = serde_json::to_string(self)?;
+        let json_data = engine
+            .evaluation_handler()
+            .create_one(CRC_JSON_SCHEMA.clone(), &[Scalar::from(json)])?;
+        let data = engine
+            .json_handler()
+            .parse_json(json_data, CRC_SCHEMA.clone())?;
+        engine
+            .json_handler()
+            .write_json_file(&path.location, Box::new(iter::once(Ok(data))), false)
+    }
+}
+
+/// Read the `<version>.crc` file of the table. Returns `None` if the file does not exist or is
+/// not valid.
+pub(crate) fn read_version_checksum(
+    storage: &dyn StorageHandler,
+    table_root: &Url,
+    version: Version,
+) -> DeltaResult<Option<VersionChecksum>> {
+    let path = ParsedLogPath::new_crc(table_root, version)?;
+    read_version_checksum_file(storage, &path.location)
+}
+
+/// Read the version checksum file at `location`. Returns `None` if the file does not exist or is
+/// not valid.
+pub(crate) fn read_version_checksum_file(
+    storage: &dyn StorageHandler,
+    location: &Url,
+) -> DeltaResult<Option<VersionChecksum>> {
+    match storage
+        .read_files(vec![(location.clone(), None)])
+        .and_then(|mut data| {
+            data.next().unwrap_or_else(|| {
+                Err(Error::internal_error(format!(
+                    "read_files returned no data for {location}"
+                )))
+            })
+        }) {
+        Ok(data) => Ok(serde_json::from_slice(&data)
+            .inspect_err(|e| warn!("invalid checksum JSON in {location}: {e}"))
+            .ok()),
+        Err(Error::FileNotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// The number and total size of the files added by a commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AddedFiles {
+    num_files: i64,
+    size_bytes: i64,
+}
+
+impl AddedFiles {
+    /// Count the files (and their total size) in the write metadata of a transaction.
+    pub(crate) fn try_from_write_metadata<'a>(
+        write_metadata: impl IntoIterator<Item = &'a dyn EngineData>,
+    ) -> DeltaResult<Self> {
+        let mut added_files = Self::default();
+        for data in write_metadata {
+            added_files.visit_rows_of(data)?;
+        }
+        Ok(added_files)
+    }
+}
+
+impl RowVisitor for AddedFiles {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("size")], vec![DataType::LONG]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        for i in 0..row_count {
+            let size: i64 = getters[0].get(i, "size")?;
+            self.num_files += 1;
+            self.size_bytes += size;
+        }
+        Ok(())
+    }
+}
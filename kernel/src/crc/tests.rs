@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use crate::actions::{Metadata, Protocol, SetTransaction};
+use crate::arrow::array::{Int64Array, RecordBatch, StringArray};
+use crate::arrow::datatypes::{DataType, Field, Schema};
+use crate::engine::arrow_data::ArrowEngineData;
+use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
+use crate::{DeltaResult, Engine, EngineData, FileMeta, FileSlice, StorageHandler};
+
+use super::{read_version_checksum, AddedFiles, VersionChecksum};
+
+use bytes::Bytes;
+use url::Url;
+
+fn put(store: &InMemory, path: &str, content: &str) {
+    futures::executor::block_on(store.put(&Path::from(path), content.to_string().into())).unwrap();
+}
+
+fn checksum(set_transactions: Option<Vec<SetTransaction>>) -> VersionChecksum {
+    VersionChecksum {
+        table_size_bytes: 100,
+        num_files: 2,
+        num_metadata: 1,
+        num_protocol: 1,
+        in_commit_timestamp_opt: None,
+        set_transactions,
+        domain_metadata: Some(vec![]),
+        metadata: Metadata {
+            id: "test".into(),
+            ..Default::default()
+        },
+        protocol: Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap(),
+    }
+}
+
+fn write_metadata(sizes: Vec<i64>) -> Box<dyn EngineData> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("path", DataType::Utf8, false),
+        Field::new("size", DataType::Int64, false),
+    ]));
+    let paths = StringArray::from_iter_values(sizes.iter().map(|size| format!("{size}.parquet")));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(paths), Arc::new(Int64Array::from(sizes))],
+    )
+    .unwrap();
+    Box::new(ArrowEngineData::new(batch))
+}
+
+#[test]
+fn test_read_version_checksum() -> DeltaResult<()> {
+    let store = Arc::new(InMemory::new());
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let storage = engine.storage_handler();
+    let table_root = Url::parse("memory:///")?;
+
+    // fields the kernel does not track are ignored
+    put(
+        &store,
+        "_delta_log/00000000000000000001.crc",
+        r#"{"txnId":"abc","tableSizeBytes":100,"numFiles":2,"numMetadata":1,"numProtocol":1,
+            "setTransactions":[{"appId":"app","version":3}],"domainMetadata":[],
+            "metadata":{"id":"test","format":{"provider":"parquet","options":{}},
+            "schemaString":"","partitionColumns":[],"configuration":{}},
+            "protocol":{"minReaderVersion":1,"minWriterVersion":2},
+            "histogramOpt":{"sortedBinBoundaries":[0],"fileCounts":[2],"totalBytes":[100]}}"#,
+    );
+    let expected = checksum(Some(vec![SetTransaction::new("app".into(), 3, None)]));
+    assert_eq!(
+        read_version_checksum(storage.as_ref(), &table_root, 1)?,
+        Some(expected)
+    );
+
+    // missing and invalid checksums are ignored
+    put(&store, "_delta_log/00000000000000000002.crc", "not json");
+    assert_eq!(
+        read_version_checksum(storage.as_ref(), &table_root, 2)?,
+        None
+    );
+    assert_eq!(
+        read_version_checksum(storage.as_ref(), &table_root, 3)?,
+        None
+    );
+    Ok(())
+}
+
+/// A storage handler whose reads return no data at all
+struct EmptyReadStorageHandler;
+
+impl StorageHandler for EmptyReadStorageHandler {
+    fn list_from(&self, _: &Url) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+        Ok(Box::new(std::iter::empty()))
+    }
+
+    fn read_files(
+        &self,
+        _: Vec<FileSlice>,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+        Ok(Box::new(std::iter::empty()))
+    }
+}
+
+#[test]
+fn test_read_version_checksum_without_data() -> DeltaResult<()> {
+    let table_root = Url::parse("memory:///")?;
+    let err = read_version_checksum(&EmptyReadStorageHandler, &table_root, 1).unwrap_err();
+    assert!(
+        err.to_string().contains("read_files returned no data"),
+        "{err}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_write_and_read_version_checksum() -> DeltaResult<()> {
+    let store = Arc::new(InMemory::new());
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let table_root = Url::parse("memory:///")?;
+
+    let expected = checksum(Some(vec![SetTransaction::new("app".into(), 3, Some(7))]));
+    expected.write(&engine, &table_root, 5)?;
+    let actual = read_version_checksum(engine.storage_handler().as_ref(), &table_root, 5)?;
+    assert_eq!(actual, Some(expected.clone()));
+
+    // existing checksum files are never overwritten
+    assert!(expected.write(&engine, &table_root, 5).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_apply_commit() -> DeltaResult<()> {
+    let previous = checksum(Some(vec![
+        SetTransaction::new("a".into(), 1, None),
+        SetTransaction::new("b".into(), 1, None),
+    ]));
+    let write_metadata = [write_metadata(vec![10, 20]), write_metadata(vec![30])];
+    let set_transactions = [SetTransaction::new("b".into(), 2, Some(5))];
+
+    let added_files =
+        AddedFiles::try_from_write_metadata(write_metadata.iter().map(|d| d.as_ref()))?;
+    let next = previous.apply_commit(added_files, &set_transactions);
+    assert_eq!(next.num_files, 5);
+    assert_eq!(next.table_size_bytes, 160);
+    assert_eq!(
+        next.set_transactions,
+        Some(vec![
+            SetTransaction::new("a".into(), 1, None),
+            SetTransaction::new("b".into(), 2, Some(5)),
+        ])
+    );
+
+    // set transactions stay unknown if the previous checksum omitted them
+    let next = checksum(None).apply_commit(AddedFiles::default(), &set_transactions);
+    assert_eq!(next.set_transactions, None);
+    assert_eq!(next.num_files, 2);
+    Ok(())
+}
@@ -1,12 +1,16 @@
 //! Expression handling based on arrow-rs compute kernels.
 use crate::arrow::array::types::*;
 use crate::arrow::array::{
-    Array, ArrayRef, AsArray, BooleanArray, Datum, RecordBatch, StructArray,
+    make_comparator, Array, ArrayRef, AsArray, BooleanArray, Datum, ListArray, RecordBatch,
+    StructArray, UInt32Array,
 };
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq};
-use crate::arrow::compute::kernels::comparison::in_list_utf8;
+use crate::arrow::compute::kernels::comparison::{in_list_utf8, starts_with};
 use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
-use crate::arrow::compute::{and_kleene, is_null, not, or_kleene};
+use crate::arrow::compute::kernels::zip::zip;
+use crate::arrow::compute::{
+    and_kleene, cast, is_not_null, is_null, not, or_kleene, sort_to_indices, take, SortOptions,
+};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, IntervalUnit, TimeUnit,
 };
@@ -14,10 +18,12 @@ use crate::arrow::error::ArrowError;
 use crate::engine::arrow_utils::prim_array_cmp;
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{
-    BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
-    JunctionPredicate, JunctionPredicateOp, Predicate, Scalar, UnaryPredicate, UnaryPredicateOp,
+    BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, CastExpression,
+    Expression, IfExpression, JunctionPredicate, JunctionPredicateOp, Predicate, Scalar,
+    UnaryPredicate, UnaryPredicateOp,
 };
 use crate::schema::DataType;
+use crate::utils::require;
 use itertools::Itertools;
 use std::sync::Arc;
 
@@ -126,6 +132,37 @@ pub(crate) fn evaluate_expression(
 
             Ok(eval(&left_arr, &right_arr)?)
         }
+        (Cast(CastExpression { expr, data_type }), _) => {
+            let arr = evaluate_expression(expr, batch, None)?;
+            let arrow_type = ArrowDataType::try_from(data_type)?;
+            Ok(cast(&arr, &arrow_type)?)
+        }
+        (Coalesce(exprs), _) => {
+            // Fill the NULL rows of the result so far with values of each successive expression
+            let mut arrays = exprs
+                .iter()
+                .map(|expr| evaluate_expression(expr, batch, result_type));
+            let Some(first) = arrays.next() else {
+                return Err(Error::generic("COALESCE requires at least one input"));
+            };
+            arrays.try_fold(first?, |result, arr| {
+                Ok(zip(&is_not_null(&result)?, &result, &arr?)?)
+            })
+        }
+        (
+            If(IfExpression {
+                condition,
+                then_expr,
+                else_expr,
+            }),
+            _,
+        ) => {
+            // NOTE: zip takes the else value for rows whose condition is NULL
+            let condition = evaluate_predicate(condition, batch)?;
+            let then_arr = evaluate_expression(then_expr, batch, result_type)?;
+            let else_arr = evaluate_expression(else_expr, batch, result_type)?;
+            Ok(zip(&condition, &then_arr, &else_arr)?)
+        }
     }
 }
 
@@ -223,6 +260,9 @@ pub(crate) fn evaluate_predicate(
         Binary(BinaryPredicate { op, left, right }) => {
             let left_arr = evaluate_expression(left.as_ref(), batch, None)?;
             let right_arr = evaluate_expression(right.as_ref(), batch, None)?;
+            if left_arr.data_type().is_nested() || right_arr.data_type().is_nested() {
+                return compare_nested(*op, left_arr, right_arr);
+            }
 
             type Operation = fn(&dyn Datum, &dyn Datum) -> Result<BooleanArray, ArrowError>;
             let eval: Operation = match op {
@@ -233,6 +273,7 @@ pub(crate) fn evaluate_predicate(
                 Equal => |l, r| eq(l, r),
                 NotEqual => |l, r| neq(l, r),
                 Distinct => |l, r| distinct(l, r),
+                StartsWith => |l, r| starts_with(l, r),
                 // NOTE: [Not]In was already covered above
                 In | NotIn => return Err(Error::generic("Invalid expression given")),
             };
@@ -253,3 +294,75 @@ pub(crate) fn evaluate_predicate(
         }
     }
 }
+
+/// Compares two nested (struct, list or map) arrays row by row, because arrow's comparison kernels
+/// don't support nested types. Rows where either side is NULL compare as NULL (except for
+/// `DISTINCT`), and nested values follow the same ordering as [`Scalar::partial_cmp`]: NULLs
+/// nested inside a value order first, and maps compare as lists of key/value entries sorted by key.
+fn compare_nested(
+    op: BinaryPredicateOp,
+    left: ArrayRef,
+    right: ArrayRef,
+) -> DeltaResult<BooleanArray> {
+    use std::cmp::Ordering;
+    use BinaryPredicateOp::*;
+    let test: fn(Ordering) -> bool = match op {
+        LessThan => Ordering::is_lt,
+        LessThanOrEqual => Ordering::is_le,
+        GreaterThan => Ordering::is_gt,
+        GreaterThanOrEqual => Ordering::is_ge,
+        Equal => Ordering::is_eq,
+        NotEqual | Distinct => Ordering::is_ne,
+        StartsWith | In | NotIn => {
+            return Err(Error::invalid_expression(format!(
+                "Unsupported comparison of nested types: {op}"
+            )))
+        }
+    };
+    let (left, right) = (map_as_list(left)?, map_as_list(right)?);
+    require!(
+        left.len() == right.len(),
+        Error::generic("Nested comparison requires arrays of equal length")
+    );
+    let options = SortOptions {
+        descending: false,
+        nulls_first: true,
+    };
+    let cmp = make_comparator(left.as_ref(), right.as_ref(), options)?;
+    let result = (0..left.len())
+        .map(|i| match (left.is_null(i), right.is_null(i)) {
+            (false, false) => Some(test(cmp(i, i))),
+            _ if op == Distinct => Some(left.is_null(i) != right.is_null(i)),
+            _ => None,
+        })
+        .collect();
+    Ok(result)
+}
+
+/// Reinterprets a map array as the list of key/value entry structs it is physically stored as, with
+/// the entries of each map sorted by key, so that it can be compared with [`make_comparator`].
+/// Other arrays are returned unchanged.
+fn map_as_list(arr: ArrayRef) -> DeltaResult<ArrayRef> {
+    let Some(map) = arr.as_map_opt() else {
+        return Ok(arr);
+    };
+    let ArrowDataType::Map(entries_field, _) = map.data_type() else {
+        return Err(Error::internal_error("Map array without a map data type"));
+    };
+    let mut indices: Vec<u32> = (0..map.entries().len() as u32).collect();
+    for (&start, &end) in map.offsets().iter().tuple_windows() {
+        let (start, len) = (start as usize, (end - start) as usize);
+        let sorted = sort_to_indices(&map.keys().slice(start, len), None, None)?;
+        for (index, offset) in indices[start..start + len].iter_mut().zip(sorted.values()) {
+            *index = start as u32 + offset;
+        }
+    }
+    let entries = take(map.entries(), &UInt32Array::from(indices), None)?;
+    let list = ListArray::try_new(
+        entries_field.clone(),
+        map.offsets().clone(),
+        entries,
+        map.nulls().cloned(),
+    )?;
+    Ok(Arc::new(list))
+}
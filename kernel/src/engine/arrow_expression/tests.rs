@@ -410,6 +410,286 @@ fn test_binary_cmp() {
     assert_eq!(results, expected);
 }
 
+#[test]
+fn test_starts_with() {
+    let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+    let values = GenericStringArray::<i32>::from(vec![Some("abc"), Some("ab"), None, Some("xabc")]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+
+    let predicate = column_expr!("a").starts_with(Expr::literal("ab"));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![Some(true), Some(true), None, Some(false)]);
+    assert_eq!(results, expected);
+
+    let predicate = Pred::not(column_expr!("a").starts_with(Expr::literal("abc")));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![Some(false), Some(true), None, Some(true)]);
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn test_nested_cmp() {
+    let array_type = ArrayType::new(DeltaDataTypes::INTEGER, true);
+    let array = |values: &[Option<i32>]| {
+        Scalar::Array(ArrayData::try_new(array_type.clone(), values.iter().copied()).unwrap())
+    };
+    let list_field = Arc::new(Field::new("element", DataType::Int32, true));
+    let lists = ListArray::new(
+        list_field,
+        OffsetBuffer::new(ScalarBuffer::from(vec![0, 2, 3, 5, 5])),
+        Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(2),
+            Some(1),
+            Some(1),
+            None,
+        ])),
+        Some(vec![true, true, true, false].into()),
+    );
+    let struct_fields = Fields::from(vec![Field::new("x", DataType::Int32, true)]);
+    let structs = StructArray::new(
+        struct_fields.clone(),
+        vec![Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            Some(4),
+        ]))],
+        None,
+    );
+    let schema = Schema::new(vec![
+        Field::new("l", lists.data_type().clone(), true),
+        Field::new("s", DataType::Struct(struct_fields), false),
+    ]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(lists), Arc::new(structs)]).unwrap();
+
+    // rows: [1, 2], [1], [1, NULL], NULL
+    let literal = Expr::literal(array(&[Some(1), Some(2)]));
+    let predicate = column_expr!("l").eq(literal.clone());
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![Some(true), Some(false), Some(false), None]);
+    assert_eq!(results, expected);
+
+    let predicate = column_expr!("l").lt(literal.clone());
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![Some(false), Some(true), Some(true), None]);
+    assert_eq!(results, expected);
+
+    let predicate = column_expr!("l").distinct(literal);
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![false, true, true, true]);
+    assert_eq!(results, expected);
+
+    let predicate = column_expr!("l").eq(Expr::literal(array(&[Some(1), None])));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![Some(false), Some(false), Some(true), None]);
+    assert_eq!(results, expected);
+
+    // rows: {x: 1}, {x: NULL}, {x: 3}, {x: 4}
+    let field = StructField::nullable("x", DeltaDataTypes::INTEGER);
+    let literal = Scalar::Struct(StructData::try_new(vec![field], vec![3.into()]).unwrap());
+    let predicate = column_expr!("s").ge(Expr::literal(literal));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    let expected = BooleanArray::from(vec![false, false, true, true]);
+    assert_eq!(results, expected);
+
+    // nested comparisons agree with scalar comparisons
+    let scalars = [
+        array(&[Some(1), Some(2)]),
+        array(&[Some(1)]),
+        array(&[Some(1), None]),
+    ];
+    let literal = array(&[Some(1), Some(1)]);
+    let predicate = column_expr!("l").gt(Expr::literal(literal.clone()));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    for (i, scalar) in scalars.iter().enumerate() {
+        assert_eq!(results.value(i), scalar > &literal, "{scalar} > {literal}");
+    }
+}
+
+#[test]
+fn test_nested_map_cmp() {
+    let map_type = MapType::new(DeltaDataTypes::STRING, DeltaDataTypes::INTEGER, true);
+    let map = |pairs: &[(&str, i32)]| {
+        let pairs = pairs.iter().map(|(k, v)| (k.to_string(), *v));
+        Scalar::Map(MapData::try_new(map_type.clone(), pairs).unwrap())
+    };
+    let column = map(&[("a", 1), ("b", 2)]).to_array(1).unwrap();
+    let schema = Schema::new(vec![Field::new("m", column.data_type().clone(), true)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![column]).unwrap();
+
+    let predicate = column_expr!("m").eq(Expr::literal(map(&[("a", 1), ("b", 2)])));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true]));
+
+    let predicate = column_expr!("m").gt(Expr::literal(map(&[("a", 1), ("b", 1)])));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true]));
+
+    // entries are compared sorted by key, like scalar maps
+    let literal = map(&[("b", 2), ("a", 1)]);
+    let predicate = column_expr!("m").eq(Expr::literal(literal.clone()));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true]));
+    assert_eq!(map(&[("a", 1), ("b", 2)]), literal);
+
+    let literal = map(&[("c", 0), ("a", 1)]);
+    let predicate = column_expr!("m").lt(Expr::literal(literal.clone()));
+    let results = evaluate_predicate(&predicate, &batch).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true]));
+    assert!(map(&[("a", 1), ("b", 2)]) < literal);
+
+    let predicate = column_expr!("m").starts_with(Expr::literal(map(&[("a", 1)])));
+    assert!(evaluate_predicate(&predicate, &batch).is_err());
+}
+
+#[test]
+fn test_cast() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            create_array!(Int32, [Some(1), None, Some(3)]),
+            create_array!(Utf8, [Some("12"), Some("x"), None]),
+        ],
+    )
+    .unwrap();
+
+    let expr = Expr::cast(column_expr!("a"), DeltaDataTypes::LONG);
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Int64, [Some(1), None, Some(3)]);
+    assert_eq!(&results, &expected);
+
+    // Values that cannot be converted become NULL
+    let expr = Expr::cast(column_expr!("b"), DeltaDataTypes::INTEGER);
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Int32, [Some(12), None, None]);
+    assert_eq!(&results, &expected);
+}
+
+#[test]
+fn test_coalesce() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            create_array!(Int32, [Some(1), None, None]),
+            create_array!(Int32, [Some(10), Some(20), None]),
+        ],
+    )
+    .unwrap();
+
+    let expr = Expr::coalesce([column_expr!("a"), column_expr!("b")]);
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Int32, [Some(1), Some(20), None]);
+    assert_eq!(&results, &expected);
+
+    let expr = Expr::coalesce([column_expr!("a"), column_expr!("b"), Expr::literal(0)]);
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Int32, [1, 20, 0]);
+    assert_eq!(&results, &expected);
+
+    let expr = Expr::coalesce([]);
+    assert!(evaluate_expression(&expr, &batch, None).is_err());
+}
+
+#[test]
+fn test_if() {
+    let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![create_array!(Int32, [Some(-1), Some(0), Some(1), None])],
+    )
+    .unwrap();
+
+    // NULL conditions take the else branch
+    let expr = Expr::if_then_else(
+        column_expr!("a").gt(Expr::literal(0)),
+        Expr::literal("pos"),
+        Expr::literal("other"),
+    );
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Utf8, ["other", "other", "pos", "other"]);
+    assert_eq!(&results, &expected);
+
+    let expr = Expr::case_when(
+        [
+            (column_expr!("a").lt(Expr::literal(0)), Expr::literal("neg")),
+            (column_expr!("a").gt(Expr::literal(0)), Expr::literal("pos")),
+            (
+                column_expr!("a").is_null(),
+                Expr::null_literal(DeltaDataTypes::STRING),
+            ),
+        ],
+        Expr::literal("zero"),
+    );
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected: ArrayRef = create_array!(Utf8, [Some("neg"), Some("zero"), Some("pos"), None]);
+    assert_eq!(&results, &expected);
+}
+
+// Transforms (e.g. physical-to-logical) evaluate a struct of per-field expressions against an
+// output schema, which the new expression kinds must also support.
+#[test]
+fn test_transform_with_cast_coalesce_and_if() {
+    let input_schema = Arc::new(StructType::new([
+        StructField::nullable("a", DeltaDataTypes::INTEGER),
+        StructField::nullable("b", DeltaDataTypes::STRING),
+    ]));
+    let output_schema = Arc::new(StructType::new([
+        StructField::nullable("a", DeltaDataTypes::LONG),
+        StructField::nullable("b", DeltaDataTypes::STRING),
+        StructField::nullable("c", DeltaDataTypes::BOOLEAN),
+    ]));
+    let batch = RecordBatch::try_new(
+        Arc::new(input_schema.as_ref().try_into().unwrap()),
+        vec![
+            create_array!(Int32, [Some(1), None]),
+            create_array!(Utf8, [None, Some("x")]),
+        ],
+    )
+    .unwrap();
+    let transform = Expr::struct_from([
+        Expr::cast(column_expr!("a"), DeltaDataTypes::LONG),
+        Expr::coalesce([column_expr!("b"), Expr::literal("default")]),
+        Expr::if_then_else(
+            column_expr!("a").is_null(),
+            Expr::literal(true),
+            Expr::literal(false),
+        ),
+    ]);
+
+    let evaluator = ArrowEvaluationHandler.new_expression_evaluator(
+        input_schema,
+        transform,
+        output_schema.clone().into(),
+    );
+    let result: RecordBatch = evaluator
+        .evaluate(&ArrowEngineData::new(batch))
+        .unwrap()
+        .into_any()
+        .downcast::<ArrowEngineData>()
+        .unwrap()
+        .into();
+    let expected = RecordBatch::try_new(
+        Arc::new(output_schema.as_ref().try_into().unwrap()),
+        vec![
+            create_array!(Int64, [Some(1), None]),
+            create_array!(Utf8, ["default", "x"]),
+            create_array!(Boolean, [false, true]),
+        ],
+    )
+    .unwrap();
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn test_logical() {
     let schema = Schema::new(vec![
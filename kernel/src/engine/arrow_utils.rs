@@ -2,18 +2,19 @@
 
 use std::collections::HashSet;
 use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use std::sync::Arc;
 
 use crate::engine::ensure_data_types::DataTypeCompat;
 use crate::{
     engine::arrow_data::ArrowEngineData,
-    schema::{DataType, Schema, SchemaRef, StructField, StructType},
+    schema::{DataType, MetadataColumnSpec, Schema, SchemaRef, StructField, StructType},
     utils::require,
     DeltaResult, EngineData, Error,
 };
 
 use crate::arrow::array::{
-    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray,
+    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, Int64Array,
     OffsetSizeTrait, RecordBatch, StringArray, StructArray,
 };
 use crate::arrow::buffer::NullBuffer;
@@ -23,6 +24,7 @@ use crate::arrow::datatypes::{
     SchemaRef as ArrowSchemaRef,
 };
 use crate::arrow::json::{LineDelimitedWriter, ReaderBuilder};
+use crate::parquet::file::metadata::RowGroupMetaData;
 use crate::parquet::{arrow::ProjectionMask, schema::types::SchemaDescriptor};
 use itertools::Itertools;
 use tracing::debug;
@@ -68,15 +70,17 @@ pub(crate) fn make_arrow_error(s: impl Into<String>) -> Error {
 
 /// Applies post-processing to data read from parquet files. This includes `reorder_struct_array` to
 /// ensure schema compatibility, as well as `fix_nested_null_masks` to ensure that leaf columns have
-/// accurate null masks that row visitors rely on for correctness.
+/// accurate null masks that row visitors rely on for correctness. If the requested schema contains a
+/// row index metadata column, `row_indexes` must yield the row index of each row that was read.
 pub(crate) fn fixup_parquet_read<T>(
     batch: RecordBatch,
     requested_ordering: &[ReorderIndex],
+    row_indexes: Option<&mut RowIndexes>,
 ) -> DeltaResult<T>
 where
     StructArray: Into<T>,
 {
-    let data = reorder_struct_array(batch.into(), requested_ordering)?;
+    let data = reorder_struct_array(batch.into(), requested_ordering, row_indexes)?;
     let data = fix_nested_null_masks(data);
     Ok(data.into())
 }
@@ -201,6 +205,8 @@ pub(crate) enum ReorderIndexTransform {
     Identity,
     /// Data is missing, fill in with a null column
     Missing(ArrowFieldRef),
+    /// Row index metadata column, fill in with the physical position of each row in the file
+    RowIndex(ArrowFieldRef),
 }
 
 impl ReorderIndex {
@@ -224,12 +230,18 @@ impl ReorderIndex {
         ReorderIndex::new(index, ReorderIndexTransform::Missing(field))
     }
 
+    fn row_index(index: usize, field: ArrowFieldRef) -> Self {
+        ReorderIndex::new(index, ReorderIndexTransform::RowIndex(field))
+    }
+
     /// Check if this reordering requires a transformation anywhere. See comment below on
     /// [`ordering_needs_transform`] to understand why this is needed.
     fn needs_transform(&self) -> bool {
         match self.transform {
-            // if we're casting or inserting null, we need to transform
-            ReorderIndexTransform::Cast(_) | ReorderIndexTransform::Missing(_) => true,
+            // if we're casting or inserting a column, we need to transform
+            ReorderIndexTransform::Cast(_)
+            | ReorderIndexTransform::Missing(_)
+            | ReorderIndexTransform::RowIndex(_) => true,
             // if our nested ordering needs a transform, we need a transform
             ReorderIndexTransform::Nested(ref children) => ordering_needs_transform(children),
             // no transform needed
@@ -271,9 +283,12 @@ fn get_indices(
     let mut parquet_offset = start_parquet_offset;
     // for each field, get its position in the parquet (via enumerate), a reference to the arrow
     // field, and info about where it appears in the requested_schema, or None if the field is not
-    // requested
+    // requested. Metadata columns are never read from the file, even if it has a matching column.
     let all_field_info = fields.iter().enumerate().map(|(parquet_index, field)| {
-        let field_info = requested_schema.fields.get_full(field.name());
+        let field_info = requested_schema
+            .fields
+            .get_full(field.name())
+            .filter(|(_, _, requested_field)| !requested_field.is_metadata_column());
         (parquet_index, field, field_info)
     });
     for (parquet_index, field, field_info) in all_field_info {
@@ -413,7 +428,13 @@ fn get_indices(
         // some fields are missing, but they might be nullable, need to insert them into the reorder_indices
         for (requested_position, field) in requested_schema.fields().enumerate() {
             if !found_fields.contains(field.name()) {
-                if field.nullable {
+                if let Some(MetadataColumnSpec::RowIndex) = field.get_metadata_column_spec() {
+                    debug!("Inserting row index field: {}", field.name());
+                    reorder_indices.push(ReorderIndex::row_index(
+                        requested_position,
+                        Arc::new(field.try_into()?),
+                    ));
+                } else if field.nullable {
                     debug!("Inserting missing and nullable field: {}", field.name());
                     reorder_indices.push(ReorderIndex::missing(
                         requested_position,
@@ -491,15 +512,59 @@ fn ordering_needs_transform(requested_ordering: &[ReorderIndex]) -> bool {
         .any(|ri| (ri[0].index >= ri[1].index) || ri[1].needs_transform())
 }
 
+/// Iterator over the physical row indexes of the rows read from a parquet file, in read order.
+pub(crate) type RowIndexes = std::iter::Flatten<std::vec::IntoIter<Range<i64>>>;
+
+/// Computes the row indexes of the rows a parquet reader will produce, accounting for any row
+/// groups that were skipped. Row indexes are 0-based positions of the rows in the file.
+pub(crate) struct RowIndexBuilder {
+    row_group_row_index_ranges: Vec<Range<i64>>,
+    row_group_ordinals: Option<Vec<usize>>,
+}
+
+impl RowIndexBuilder {
+    pub(crate) fn new(row_groups: &[RowGroupMetaData]) -> Self {
+        let mut row_group_row_index_ranges = Vec::with_capacity(row_groups.len());
+        let mut offset = 0;
+        for row_group in row_groups {
+            let num_rows = row_group.num_rows();
+            row_group_row_index_ranges.push(offset..offset + num_rows);
+            offset += num_rows;
+        }
+        Self {
+            row_group_row_index_ranges,
+            row_group_ordinals: None,
+        }
+    }
+
+    /// Only produce row indexes for the row groups with the given ordinals, in the given order.
+    pub(crate) fn select_row_groups(&mut self, ordinals: &[usize]) {
+        self.row_group_ordinals = Some(ordinals.to_vec());
+    }
+
+    pub(crate) fn build(self) -> RowIndexes {
+        let ranges = match self.row_group_ordinals {
+            Some(ordinals) => ordinals
+                .into_iter()
+                .map(|i| self.row_group_row_index_ranges[i].clone())
+                .collect(),
+            None => self.row_group_row_index_ranges,
+        };
+        ranges.into_iter().flatten()
+    }
+}
+
 // we use this as a placeholder for an array and its associated field. We can fill in a Vec of None
 // of this type and then set elements of the Vec to Some(FieldArrayOpt) for each column
 type FieldArrayOpt = Option<(Arc<ArrowField>, Arc<dyn ArrowArray>)>;
 
 /// Reorder a RecordBatch to match `requested_ordering`. For each non-zero value in
-/// `requested_ordering`, the column at that index will be added in order to returned batch
+/// `requested_ordering`, the column at that index will be added in order to returned batch. Row
+/// index columns are filled from `row_indexes`, which is only supported at the top level.
 pub(crate) fn reorder_struct_array(
     input_data: StructArray,
     requested_ordering: &[ReorderIndex],
+    mut row_indexes: Option<&mut RowIndexes>,
 ) -> DeltaResult<StructArray> {
     debug!("Reordering {input_data:?} with ordering: {requested_ordering:?}");
     if !ordering_needs_transform(requested_ordering) {
@@ -533,7 +598,7 @@ pub(crate) fn reorder_struct_array(
                         ArrowDataType::Struct(_) => {
                             let struct_array = input_cols[parquet_position].as_struct().clone();
                             let result_array =
-                                Arc::new(reorder_struct_array(struct_array, children)?);
+                                Arc::new(reorder_struct_array(struct_array, children, None)?);
                             // create the new field specifying the correct order for the struct
                             let new_field = Arc::new(ArrowField::new_struct(
                                 input_fields[parquet_position].name(),
@@ -578,6 +643,23 @@ pub(crate) fn reorder_struct_array(
                     let field = field.clone(); // cheap Arc clone
                     final_fields_cols[reorder_index.index] = Some((field, null_array));
                 }
+                ReorderIndexTransform::RowIndex(field) => {
+                    let Some(row_indexes) = row_indexes.take() else {
+                        return Err(Error::internal_error(
+                            "Row index column requested but no row indexes were provided",
+                        ));
+                    };
+                    let row_index_array = Int64Array::from_iter_values(row_indexes.take(num_rows));
+                    require!(
+                        row_index_array.len() == num_rows,
+                        Error::internal_error(
+                            "Row indexes exhausted before the end of the parquet data"
+                        )
+                    );
+                    let field = field.clone(); // cheap Arc clone
+                    final_fields_cols[reorder_index.index] =
+                        Some((field, Arc::new(row_index_array)));
+                }
             }
         }
         let num_cols = final_fields_cols.len();
@@ -603,7 +685,7 @@ fn reorder_list<O: OffsetSizeTrait>(
     let (list_field, offset_buffer, maybe_sa, null_buf) = list_array.into_parts();
     if let Some(struct_array) = maybe_sa.as_struct_opt() {
         let struct_array = struct_array.clone();
-        let result_array = Arc::new(reorder_struct_array(struct_array, children)?);
+        let result_array = Arc::new(reorder_struct_array(struct_array, children, None)?);
         let new_list_field = Arc::new(ArrowField::new_struct(
             list_field.name(),
             result_array.fields().clone(),
@@ -1336,7 +1418,7 @@ mod tests {
     fn simple_reorder_struct() {
         let arry = make_struct_array();
         let reorder = vec![ReorderIndex::identity(1), ReorderIndex::identity(0)];
-        let ordered = reorder_struct_array(arry, &reorder).unwrap();
+        let ordered = reorder_struct_array(arry, &reorder, None).unwrap();
         assert_eq!(ordered.column_names(), vec!["c", "b"]);
     }
 
@@ -1384,7 +1466,7 @@ mod tests {
                 ],
             ),
         ];
-        let ordered = reorder_struct_array(nested, &reorder).unwrap();
+        let ordered = reorder_struct_array(nested, &reorder, None).unwrap();
         assert_eq!(ordered.column_names(), vec!["struct2", "struct1"]);
         let ordered_s2 = ordered.column(0).as_struct();
         assert_eq!(ordered_s2.column_names(), vec!["b", "c", "s"]);
@@ -1431,7 +1513,7 @@ mod tests {
             0,
             vec![ReorderIndex::identity(1), ReorderIndex::identity(0)],
         )];
-        let ordered = reorder_struct_array(struct_array, &reorder).unwrap();
+        let ordered = reorder_struct_array(struct_array, &reorder, None).unwrap();
         let ordered_list_col = ordered.column(0).as_list::<i32>();
         for i in 0..ordered_list_col.len() {
             let array_item = ordered_list_col.value(i);
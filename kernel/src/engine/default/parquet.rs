@@ -19,7 +19,9 @@ use uuid::Uuid;
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
 use super::UrlExt;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
+use crate::engine::arrow_utils::{
+    fixup_parquet_read, generate_mask, get_requested_indices, RowIndexBuilder,
+};
 use crate::engine::default::executor::TaskExecutor;
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
 use crate::schema::SchemaRef;
@@ -291,8 +293,12 @@ impl FileOpener for ParquetOpener {
                 builder = builder.with_projection(mask)
             }
 
+            let mut row_indexes = table_schema
+                .fields()
+                .any(|field| field.is_metadata_column())
+                .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
+                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
             }
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
@@ -300,7 +306,10 @@ impl FileOpener for ParquetOpener {
 
             let stream = builder.with_batch_size(batch_size).build()?;
 
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let mut row_indexes = row_indexes.map(RowIndexBuilder::build);
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
+            });
             Ok(stream.boxed())
         }))
     }
@@ -359,8 +368,12 @@ impl FileOpener for PresignedUrlOpener {
                 builder = builder.with_projection(mask)
             }
 
+            let mut row_indexes = table_schema
+                .fields()
+                .any(|field| field.is_metadata_column())
+                .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
+                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
             }
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
@@ -368,8 +381,11 @@ impl FileOpener for PresignedUrlOpener {
 
             let reader = builder.with_batch_size(batch_size).build()?;
 
+            let mut row_indexes = row_indexes.map(RowIndexBuilder::build);
             let stream = futures::stream::iter(reader);
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
+            });
             Ok(stream.boxed())
         }))
     }
@@ -438,6 +454,67 @@ mod tests {
         assert_eq!(data[0].num_rows(), 10);
     }
 
+    #[tokio::test]
+    async fn test_read_row_index_with_row_group_skipping() {
+        use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+        use crate::parquet::file::properties::WriterProperties;
+        use crate::schema::{DataType, MetadataColumnSpec, StructField, StructType};
+
+        let store = Arc::new(InMemory::new());
+        let batch = RecordBatch::try_from_iter(vec![(
+            "id",
+            Arc::new(Int64Array::from(vec![0, 1, 2, 3, 4, 5])) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        // three row groups of two rows each
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut buffer = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = buffer.len();
+        store
+            .put(&Path::from("data.parquet"), buffer.into())
+            .await
+            .unwrap();
+
+        let files = &[FileMeta {
+            location: Url::parse("memory:///data.parquet").unwrap(),
+            last_modified: 0,
+            size: size as u64,
+        }];
+        let schema = Arc::new(StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::create_metadata_column("row_index", MetadataColumnSpec::RowIndex),
+        ]));
+        // skips the middle row group
+        let predicate = Pred::or(
+            column_expr!("id").lt(Expr::literal(2i64)),
+            column_expr!("id").gt(Expr::literal(3i64)),
+        );
+
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let data: Vec<RecordBatch> = handler
+            .read_parquet_files(files, schema, Some(Arc::new(predicate)))
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        let ids = data.iter().flat_map(|batch| {
+            let ids = batch.column(0).as_any().downcast_ref::<Int64Array>();
+            ids.unwrap().values().to_vec()
+        });
+        assert_eq!(ids.collect_vec(), vec![0, 1, 4, 5]);
+        let row_indexes = data.iter().flat_map(|batch| {
+            let row_indexes = batch.column(1).as_any().downcast_ref::<Int64Array>();
+            row_indexes.unwrap().values().to_vec()
+        });
+        assert_eq!(row_indexes.collect_vec(), vec![0, 1, 4, 5]);
+    }
+
     #[test]
     fn test_as_record_batch() {
         let location = Url::parse("file:///test_url").unwrap();
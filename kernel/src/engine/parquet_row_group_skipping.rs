@@ -1,4 +1,5 @@
 //! An implementation of parquet row group skipping using data skipping predicates over footer stats.
+use crate::engine::arrow_utils::RowIndexBuilder;
 use crate::expressions::{ColumnName, DecimalData, Predicate, Scalar};
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::parquet::arrow::arrow_reader::ArrowReaderBuilder;
@@ -7,6 +8,7 @@ use crate::parquet::file::statistics::Statistics;
 use crate::parquet::schema::types::ColumnDescPtr;
 use crate::schema::{DataType, DecimalType, PrimitiveType};
 use chrono::{DateTime, Days};
+use itertools::Itertools;
 use std::collections::HashMap;
 use tracing::debug;
 
@@ -16,11 +18,20 @@ mod tests;
 /// An extension trait for [`ArrowReaderBuilder`] that injects row group skipping capability.
 pub(crate) trait ParquetRowGroupSkipping {
     /// Instructs the parquet reader to perform row group skipping, eliminating any row group whose
-    /// stats prove that none of the group's rows can satisfy the given `predicate`.
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self;
+    /// stats prove that none of the group's rows can satisfy the given `predicate`. If a
+    /// `row_indexes` builder is given, it is informed of the row groups that will be read.
+    fn with_row_group_filter(
+        self,
+        predicate: &Predicate,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self;
 }
 impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self {
+    fn with_row_group_filter(
+        self,
+        predicate: &Predicate,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self {
         let indices = self
             .metadata()
             .row_groups()
@@ -30,8 +41,11 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
                 // If the group survives the filter, return Some(index) so filter_map keeps it.
                 RowGroupFilter::apply(row_group, predicate).then_some(index)
             })
-            .collect();
+            .collect_vec();
         debug!("with_row_group_filter({predicate:#?}) = {indices:?})");
+        if let Some(row_indexes) = row_indexes {
+            row_indexes.select_row_groups(&indices);
+        }
         self.with_row_groups(indices)
     }
 }
@@ -40,6 +40,9 @@ pub enum Error {
     #[error("Error writing checkpoint: {0}")]
     CheckpointWrite(String),
 
+    #[error("Error writing log compaction: {0}")]
+    LogCompactionWrite(String),
+
     /// User tried to convert engine data to the wrong type
     #[error("Invalid engine data type. Could not convert to {0}")]
     EngineDataType(String),
@@ -222,6 +225,10 @@ impl Error {
         Self::CheckpointWrite(msg.to_string())
     }
 
+    pub(crate) fn log_compaction_write(msg: impl ToString) -> Self {
+        Self::LogCompactionWrite(msg.to_string())
+    }
+
     pub fn generic_err(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
         Self::GenericError {
             source: source.into(),
@@ -1,13 +1,16 @@
 use crate::{DeltaResult, Error};
 
+use serde::{Deserialize, Serialize};
+
 use std::borrow::Borrow;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::iter::Peekable;
 use std::ops::Deref;
 
-/// A (possibly nested) column name.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+/// A (possibly nested) column name. Serializes as its path of field names, e.g. `["a", "b.c"]`.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct ColumnName {
     path: Vec<String>,
 }
@@ -216,7 +219,7 @@ impl Display for ColumnName {
 }
 
 // Simple column names contain only simple chars, and do not need to be wrapped in backticks.
-fn is_simple_char(c: char) -> bool {
+pub(super) fn is_simple_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_'
 }
 
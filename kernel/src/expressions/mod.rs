@@ -4,18 +4,24 @@ use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 pub use self::column_names::{
     column_expr, column_name, column_pred, joined_column_expr, joined_column_name, ColumnName,
 };
+pub(crate) use self::parser::pad_scale;
 pub use self::scalars::{ArrayData, DecimalData, MapData, Scalar, StructData};
 use self::transforms::{ExpressionTransform as _, GetColumnReferences};
-use crate::DataType;
+pub use self::versioned::{Versioned, EXPRESSION_FORMAT_VERSION};
+use crate::schema::Schema;
+use crate::{DataType, DeltaResult};
 
 mod column_names;
 pub(crate) mod literal_expression_transform;
+mod parser;
 mod scalars;
 pub mod transforms;
+mod versioned;
 
 pub type ExpressionRef = std::sync::Arc<Expression>;
 pub type PredicateRef = std::sync::Arc<Predicate>;
@@ -25,14 +31,16 @@ pub type PredicateRef = std::sync::Arc<Predicate>;
 ////////////////////////////////////////////////////////////////////////
 
 /// A unary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum UnaryPredicateOp {
     /// Unary Is Null
     IsNull,
 }
 
 /// A binary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BinaryPredicateOp {
     /// Comparison Less Than
     LessThan,
@@ -52,10 +60,13 @@ pub enum BinaryPredicateOp {
     In,
     /// NOT IN
     NotIn,
+    /// String prefix match, e.g. `LIKE 'abc%'`
+    StartsWith,
 }
 
 /// A binary expression operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BinaryExpressionOp {
     /// Arithmetic Plus
     Plus,
@@ -68,7 +79,8 @@ pub enum BinaryExpressionOp {
 }
 
 /// A junction (AND/OR) predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum JunctionPredicateOp {
     /// Conjunction
     And,
@@ -80,7 +92,8 @@ pub enum JunctionPredicateOp {
 // Expressions and predicates
 ////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct UnaryPredicate {
     /// The operator.
     pub op: UnaryPredicateOp,
@@ -88,7 +101,8 @@ pub struct UnaryPredicate {
     pub expr: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BinaryPredicate {
     /// The operator.
     pub op: BinaryPredicateOp,
@@ -98,7 +112,8 @@ pub struct BinaryPredicate {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BinaryExpression {
     /// The operator.
     pub op: BinaryExpressionOp,
@@ -108,7 +123,28 @@ pub struct BinaryExpression {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CastExpression {
+    /// The input expression.
+    pub expr: Box<Expression>,
+    /// The data type to cast the input to.
+    pub data_type: DataType,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IfExpression {
+    /// The condition.
+    pub condition: Box<Predicate>,
+    /// The result for rows where the condition is TRUE.
+    pub then_expr: Box<Expression>,
+    /// The result for rows where the condition is FALSE or NULL.
+    pub else_expr: Box<Expression>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct JunctionPredicate {
     /// The operator.
     pub op: JunctionPredicateOp,
@@ -121,7 +157,12 @@ pub struct JunctionPredicate {
 /// These expressions do not track or validate data types, other than the type
 /// of literals. It is up to the expression evaluator to validate the
 /// expression against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serializes as a single-entry map from the (camelCase) variant name to its contents, e.g.
+/// `{"column": ["a", "b"]}`. See [`Versioned`] for exchanging expressions between processes that
+/// may run different kernel versions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Expression {
     /// A literal value.
     Literal(Scalar),
@@ -133,6 +174,13 @@ pub enum Expression {
     Struct(Vec<Expression>),
     /// An expression that takes two expressions as input.
     Binary(BinaryExpression),
+    /// Converts an expression to a different data type, e.g. `CAST(<expr> AS <type>)`.
+    Cast(CastExpression),
+    /// The first non-NULL value of a Vec of expressions, or NULL if all of them are NULL.
+    Coalesce(Vec<Expression>),
+    /// A conditional expression, e.g. `IF(<pred>, <expr>, <expr>)`. A SQL `CASE WHEN` expression is
+    /// a chain of nested conditionals, see [`Expression::case_when`].
+    If(IfExpression),
 }
 
 /// A SQL predicate.
@@ -140,7 +188,10 @@ pub enum Expression {
 /// These predicates do not track or validate data types, other than the type
 /// of literals. It is up to the predicate evaluator to validate the
 /// predicate against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serializes like [`Expression`], e.g. `{"not": {"booleanExpression": {"column": ["a"]}}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Predicate {
     /// A boolean-valued expression, useful for e.g. `AND(<boolean_col1>, <boolean_col2>)`.
     BooleanExpression(Expression),
@@ -169,7 +220,7 @@ impl BinaryPredicateOp {
         use BinaryPredicateOp::*;
         match self {
             LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual => true,
-            Equal | NotEqual => true,
+            Equal | NotEqual | StartsWith => true,
             Distinct | In | NotIn => false, // tolerates NULL input
         }
     }
@@ -183,7 +234,7 @@ impl BinaryPredicateOp {
             LessThan => Some(GreaterThan),
             LessThanOrEqual => Some(GreaterThanOrEqual),
             Equal | NotEqual | Distinct => Some(*self),
-            In | NotIn => None, // not commutative
+            In | NotIn | StartsWith => None, // not commutative
         }
     }
 }
@@ -217,6 +268,30 @@ impl BinaryExpression {
     }
 }
 
+impl CastExpression {
+    fn new(expr: impl Into<Expression>, data_type: DataType) -> Self {
+        let expr = Box::new(expr.into());
+        Self { expr, data_type }
+    }
+}
+
+impl IfExpression {
+    fn new(
+        condition: impl Into<Predicate>,
+        then_expr: impl Into<Expression>,
+        else_expr: impl Into<Expression>,
+    ) -> Self {
+        let condition = Box::new(condition.into());
+        let then_expr = Box::new(then_expr.into());
+        let else_expr = Box::new(else_expr.into());
+        Self {
+            condition,
+            then_expr,
+            else_expr,
+        }
+    }
+}
+
 impl BinaryPredicate {
     fn new(
         op: BinaryPredicateOp,
@@ -236,6 +311,12 @@ impl JunctionPredicate {
 }
 
 impl Expression {
+    /// Parses a SQL-like expression string, e.g. `price * 2 + 1`, resolving its columns and
+    /// literal types against `schema`. See [`Predicate::parse`] for details.
+    pub fn parse(input: &str, schema: &Schema) -> DeltaResult<Self> {
+        parser::parse_expression(input, schema)
+    }
+
     /// Returns a set of columns referenced by this expression.
     pub fn references(&self) -> HashSet<&ColumnName> {
         let mut references = GetColumnReferences::default();
@@ -319,6 +400,11 @@ impl Expression {
         Predicate::distinct(self, other)
     }
 
+    /// Create a new predicate `self STARTS_WITH prefix`
+    pub fn starts_with(self, prefix: impl Into<Self>) -> Predicate {
+        Predicate::starts_with(self, prefix)
+    }
+
     /// Creates a new binary expression lhs OP rhs
     pub fn binary(
         op: BinaryExpressionOp,
@@ -331,9 +417,70 @@ impl Expression {
             right: Box::new(rhs.into()),
         })
     }
+
+    /// Creates a new expression `CAST(expr AS data_type)`
+    pub fn cast(expr: impl Into<Expression>, data_type: impl Into<DataType>) -> Self {
+        Self::Cast(CastExpression::new(expr, data_type.into()))
+    }
+
+    /// Creates a new expression `COALESCE(exprs...)`
+    pub fn coalesce(exprs: impl IntoIterator<Item = Self>) -> Self {
+        Self::Coalesce(exprs.into_iter().collect())
+    }
+
+    /// Creates a new expression `IF(condition, then_expr, else_expr)`
+    pub fn if_then_else(
+        condition: impl Into<Predicate>,
+        then_expr: impl Into<Expression>,
+        else_expr: impl Into<Expression>,
+    ) -> Self {
+        Self::If(IfExpression::new(condition, then_expr, else_expr))
+    }
+
+    /// Creates a new expression `CASE WHEN cond1 THEN expr1 WHEN cond2 THEN expr2 ... ELSE
+    /// else_expr END`, as a chain of nested [`Expression::If`].
+    pub fn case_when(
+        branches: impl IntoIterator<Item = (Predicate, Expression)>,
+        else_expr: impl Into<Expression>,
+    ) -> Self {
+        let branches: Vec<_> = branches.into_iter().collect();
+        branches
+            .into_iter()
+            .rev()
+            .fold(else_expr.into(), |else_expr, (condition, then_expr)| {
+                Self::if_then_else(condition, then_expr, else_expr)
+            })
+    }
 }
 
 impl Predicate {
+    /// Parses a SQL-like predicate string, resolving its columns and literal types against
+    /// `schema`. Literals take the type of the column (or other expression) they are compared
+    /// with, so e.g. `'2026-01-01'` is a date when compared with a date column:
+    ///
+    /// ```
+    /// # use delta_kernel::expressions::{column_expr, Predicate, Scalar};
+    /// # use delta_kernel::schema::{DataType, StructField, StructType};
+    /// let schema = StructType::new([
+    ///     StructField::nullable("date", DataType::DATE),
+    ///     StructField::nullable("region", DataType::STRING),
+    /// ]);
+    /// let pred = Predicate::parse("date >= '1970-01-02' AND region = 'eu'", &schema).unwrap();
+    /// assert_eq!(
+    ///     pred,
+    ///     Predicate::and(
+    ///         column_expr!("date").ge(Scalar::Date(1)),
+    ///         column_expr!("region").eq(Scalar::from("eu")),
+    ///     )
+    /// );
+    /// ```
+    ///
+    /// Nested columns use [`ColumnName`] syntax, e.g. ``a.`b.c` ``. The parser also accepts the
+    /// output of this type's `Display` impl, so a displayed predicate parses back to the original.
+    pub fn parse(input: &str, schema: &Schema) -> DeltaResult<Self> {
+        parser::parse_predicate(input, schema)
+    }
+
     /// Returns a set of columns referenced by this predicate.
     pub fn references(&self) -> HashSet<&ColumnName> {
         let mut references = GetColumnReferences::default();
@@ -417,6 +564,11 @@ impl Predicate {
         Self::binary(BinaryPredicateOp::Distinct, a, b)
     }
 
+    /// Create a new predicate `self STARTS_WITH prefix`, equivalent to SQL `self LIKE 'prefix%'`
+    pub fn starts_with(a: impl Into<Expression>, prefix: impl Into<Expression>) -> Self {
+        Self::binary(BinaryPredicateOp::StartsWith, a, prefix)
+    }
+
     /// Create a new predicate `self AND other`
     pub fn and(a: impl Into<Self>, b: impl Into<Self>) -> Self {
         Self::and_from([a.into(), b.into()])
@@ -467,6 +619,17 @@ impl Predicate {
 // Trait impls
 ////////////////////////////////////////////////////////////////////////
 
+impl BinaryExpressionOp {
+    /// How tightly this operator binds its operands, relative to the other operators.
+    fn precedence(&self) -> u8 {
+        use BinaryExpressionOp::*;
+        match self {
+            Plus | Minus => 1,
+            Multiply | Divide => 2,
+        }
+    }
+}
+
 impl Display for BinaryExpressionOp {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         use BinaryExpressionOp::*;
@@ -495,6 +658,7 @@ impl Display for BinaryPredicateOp {
             Distinct => write!(f, "DISTINCT"),
             In => write!(f, "IN"),
             NotIn => write!(f, "NOT IN"),
+            StartsWith => write!(f, "STARTS_WITH"),
         }
     }
 }
@@ -511,7 +675,33 @@ impl Display for Expression {
                 "Struct({})",
                 &exprs.iter().map(|e| format!("{e}")).join(", ")
             ),
-            Binary(BinaryExpression { op, left, right }) => write!(f, "{left} {op} {right}"),
+            Binary(BinaryExpression { op, left, right }) => {
+                // Parenthesize operands that would otherwise bind differently when read back, e.g.
+                // `(a + b) * c` and `a - (b - c)`
+                let precedence = |expr: &Expression| match expr {
+                    Binary(BinaryExpression { op, .. }) => op.precedence(),
+                    _ => u8::MAX,
+                };
+                match precedence(left) < op.precedence() {
+                    true => write!(f, "({left}) {op} ")?,
+                    false => write!(f, "{left} {op} ")?,
+                }
+                match precedence(right) <= op.precedence() {
+                    true => write!(f, "({right})"),
+                    false => write!(f, "{right}"),
+                }
+            }
+            Cast(CastExpression { expr, data_type }) => write!(f, "CAST({expr} AS {data_type})"),
+            Coalesce(exprs) => write!(
+                f,
+                "COALESCE({})",
+                &exprs.iter().map(|e| format!("{e}")).join(", ")
+            ),
+            If(IfExpression {
+                condition,
+                then_expr,
+                else_expr,
+            }) => write!(f, "IF({condition}, {then_expr}, {else_expr})"),
         }
     }
 }
@@ -602,6 +792,7 @@ impl<R: Into<Expression>> std::ops::Div<R> for Expression {
 #[cfg(test)]
 mod tests {
     use super::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::DataType;
 
     #[test]
     fn test_expression_format() {
@@ -609,12 +800,34 @@ mod tests {
             (column_expr!("x"), "Column(x)"),
             (
                 (column_expr!("x") + Expr::literal(4)) / Expr::literal(10) * Expr::literal(42),
-                "Column(x) + 4 / 10 * 42",
+                "(Column(x) + 4) / 10 * 42",
+            ),
+            (
+                column_expr!("x") - (Expr::literal(4) - column_expr!("y") * Expr::literal(2)),
+                "Column(x) - (4 - Column(y) * 2)",
             ),
             (
                 Expr::struct_from([column_expr!("x"), Expr::literal(2), Expr::literal(10)]),
                 "Struct(Column(x), 2, 10)",
             ),
+            (
+                Expr::cast(column_expr!("x"), DataType::LONG),
+                "CAST(Column(x) AS long)",
+            ),
+            (
+                Expr::coalesce([column_expr!("x"), Expr::literal(0)]),
+                "COALESCE(Column(x), 0)",
+            ),
+            (
+                Expr::case_when(
+                    [
+                        (column_expr!("x").lt(Expr::literal(0)), Expr::literal("neg")),
+                        (column_expr!("x").gt(Expr::literal(0)), Expr::literal("pos")),
+                    ],
+                    Expr::literal("zero"),
+                ),
+                "IF(Column(x) < 0, 'neg', IF(Column(x) > 0, 'pos', 'zero'))",
+            ),
         ];
 
         for (expr, expected) in cases {
@@ -658,6 +871,14 @@ mod tests {
                 column_expr!("x").eq(Expr::literal("foo")),
                 "Column(x) = 'foo'",
             ),
+            (
+                column_expr!("x").eq(Expr::literal("it's")),
+                "Column(x) = 'it''s'",
+            ),
+            (
+                column_expr!("x").starts_with(Expr::literal("foo")),
+                "Column(x) STARTS_WITH 'foo'",
+            ),
         ];
 
         for (pred, expected) in cases {
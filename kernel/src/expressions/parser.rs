@@ -0,0 +1,1246 @@
+//! A parser for SQL-like predicate and expression strings, such as
+//! `date >= '2026-01-01' AND region IN ('eu', 'us')`.
+//!
+//! Besides SQL syntax, the parser accepts everything the [`Display`] impls of [`Predicate`] and
+//! [`Expression`] produce (`Column(a.b)`, `AND(p, q)`, `DISTINCT(a, b)`, ...), so that displaying
+//! a predicate and parsing it back against the same schema reproduces the original predicate.
+//!
+//! Parsing happens in two passes. The first pass builds an untyped syntax tree, whose literals are
+//! just the text that spelled them. The second pass resolves column references against the table
+//! schema, and gives each literal the type of whatever it is compared or combined with. For
+//! example, `'2026-01-01'` becomes a [`Scalar::Date`] when compared with a date column, `10`
+//! becomes a [`Scalar::Long`] when added to a long column, and `1.5` becomes a decimal when
+//! compared with a decimal column. Literals with nothing to take a type from are inferred from
+//! their text: integers are `integer` (or `long`, if too large), other numbers are `double`.
+//!
+//! [`Display`]: std::fmt::Display
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use super::column_names::is_simple_char;
+use super::{
+    ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicateOp, CastExpression, ColumnName,
+    Expression, IfExpression, JunctionPredicateOp, MapData, Predicate, Scalar, StructData,
+};
+use crate::schema::{ArrayType, DataType, PrimitiveType, Schema};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// Parses a predicate string, resolving its columns and literals against `schema`.
+pub(super) fn parse_predicate(input: &str, schema: &Schema) -> DeltaResult<Predicate> {
+    let ast = Parser::new(input)?.parse()?;
+    Resolver { schema }.predicate(ast)
+}
+
+/// Parses an expression string, resolving its columns and literals against `schema`.
+pub(super) fn parse_expression(input: &str, schema: &Schema) -> DeltaResult<Expression> {
+    let ast = Parser::new(input)?.parse()?;
+    Resolver { schema }.expression(ast, None)
+}
+
+////////////////////////////////////////////////////////////////////////
+// Tokenizer
+////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A bare identifier, which may be a keyword, a function name or a column name
+    Word(String),
+    /// A nested or escaped column name, which is never a keyword, e.g. `a.b` or `` `a b` ``
+    Column(ColumnName),
+    /// The text of a numeric literal, e.g. `1`, `1.5` or `1e10`
+    Number(String),
+    /// The (unescaped) contents of a single-quoted string literal
+    String(String),
+    /// An operator or punctuation
+    Symbol(&'static str),
+}
+
+// NOTE: Longer symbols come first, so that e.g. `<=` is not tokenized as `<` followed by `=`
+const SYMBOLS: &[&str] = &[
+    "<=", ">=", "!=", "<>", "=", "<", ">", "+", "-", "*", "/", "(", ")", ",", "{", "}", ":",
+];
+
+const STRING_QUOTE: char = '\'';
+const FIELD_ESCAPE_CHAR: char = '`';
+
+fn tokenize(input: &str) -> DeltaResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = input.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let (token, len) = if c == STRING_QUOTE {
+            tokenize_string(rest)?
+        } else if c.is_ascii_digit() {
+            tokenize_number(rest)
+        } else if c == FIELD_ESCAPE_CHAR || is_simple_char(c) {
+            tokenize_column(rest)?
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            (Token::Symbol(symbol), symbol.len())
+        } else {
+            return Err(Error::generic(format!(
+                "Unexpected character {c:?} at position {}",
+                input.len() - rest.len()
+            )));
+        };
+        tokens.push(token);
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Tokenizes a string literal, in which a doubled quote (`''`) stands for a single quote.
+fn tokenize_string(input: &str) -> DeltaResult<(Token, usize)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != STRING_QUOTE {
+            value.push(c);
+        } else if chars.next_if(|(_, c)| *c == STRING_QUOTE).is_some() {
+            value.push(STRING_QUOTE);
+        } else {
+            return Ok((Token::String(value), i + 1));
+        }
+    }
+    Err(Error::generic(format!(
+        "No closing {STRING_QUOTE:?} after string {value:?}"
+    )))
+}
+
+/// Tokenizes a number, e.g. `42`, `4.2` or `4.2e-1`. Signs are handled by the parser.
+fn tokenize_number(input: &str) -> (Token, usize) {
+    let mut len = 0;
+    let mut prev = None;
+    for c in input.chars() {
+        let is_exponent_sign = matches!(prev, Some('e' | 'E')) && matches!(c, '+' | '-');
+        if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || is_exponent_sign) {
+            break;
+        }
+        len += c.len_utf8();
+        prev = Some(c);
+    }
+    (Token::Number(input[..len].to_string()), len)
+}
+
+/// Tokenizes a (possibly nested and escaped) column name, e.g. `a`, `a.b` or `` a.`b.c` ``.
+fn tokenize_column(input: &str) -> DeltaResult<(Token, usize)> {
+    let mut len = 0;
+    loop {
+        let field = &input[len..];
+        len += match field.strip_prefix(FIELD_ESCAPE_CHAR) {
+            Some(escaped) => {
+                escaped_field_len(escaped).ok_or_else(|| {
+                    Error::generic(format!("No closing {FIELD_ESCAPE_CHAR:?} in {input:?}"))
+                })? + 1
+            }
+            None => field.find(|c| !is_simple_char(c)).unwrap_or(field.len()),
+        };
+        let next_field = input[len..].strip_prefix('.');
+        match next_field.and_then(|next| next.chars().next()) {
+            Some(c) if c == FIELD_ESCAPE_CHAR || is_simple_char(c) => len += 1,
+            _ => break,
+        }
+    }
+    let name = &input[..len];
+    let token = match name.contains(['.', FIELD_ESCAPE_CHAR]) {
+        true => Token::Column(name.parse()?),
+        false => Token::Word(name.to_string()),
+    };
+    Ok((token, len))
+}
+
+/// Returns the length of an escaped field name, including its closing backtick, whose opening
+/// backtick was already consumed.
+fn escaped_field_len(input: &str) -> Option<usize> {
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == FIELD_ESCAPE_CHAR && chars.next_if(|(_, c)| *c == FIELD_ESCAPE_CHAR).is_none() {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+////////////////////////////////////////////////////////////////////////
+// Parser
+////////////////////////////////////////////////////////////////////////
+
+/// An untyped literal value
+#[derive(Debug, Clone)]
+enum Literal {
+    Null,
+    Boolean(bool),
+    Number(String),
+    String(String),
+}
+
+/// An untyped syntax tree, which may represent either a predicate or an expression
+#[derive(Debug, Clone)]
+enum Ast {
+    Literal(Literal),
+    /// A parenthesized list of values, e.g. `(1, 2, 3)`
+    List(Vec<Ast>),
+    /// A braced list of key/value pairs, e.g. `{a: 1, b: 2}`
+    Record(Vec<(Ast, Ast)>),
+    Column(ColumnName),
+    Arithmetic(BinaryExpressionOp, Box<Ast>, Box<Ast>),
+    Struct(Vec<Ast>),
+    Cast(Box<Ast>, DataType),
+    Coalesce(Vec<Ast>),
+    If(Box<Ast>, Box<Ast>, Box<Ast>),
+    Comparison(BinaryPredicateOp, Box<Ast>, Box<Ast>),
+    IsNull(Box<Ast>),
+    Not(Box<Ast>),
+    Junction(JunctionPredicateOp, Vec<Ast>),
+}
+
+impl Ast {
+    fn is_literal(&self) -> bool {
+        matches!(self, Ast::Literal(_) | Ast::List(_) | Ast::Record(_))
+    }
+
+    fn comparison(op: BinaryPredicateOp, left: Ast, right: Ast) -> Self {
+        Self::Comparison(op, Box::new(left), Box::new(right))
+    }
+}
+
+// Keywords can't be used as bare column names (but can be escaped, e.g. `` `and` ``)
+const KEYWORDS: &[&str] = &[
+    "AND",
+    "OR",
+    "NOT",
+    "IN",
+    "IS",
+    "NULL",
+    "TRUE",
+    "FALSE",
+    "AS",
+    "STARTS_WITH",
+];
+
+/// A recursive descent parser. Each `parse_xxx` method parses one precedence level, from the
+/// loosest binding (`OR`) to the tightest binding (literals, columns and function calls).
+/// How deeply the parser lets predicates and expressions nest, so that deeply nested input fails
+/// to parse instead of overflowing the stack. Parsing and resolving input nested this deeply fits
+/// in the 2 MiB stack of a spawned thread even in debug builds.
+const MAX_NESTING_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// The nesting depth of the predicate or expression being parsed
+    depth: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> DeltaResult<Self> {
+        let tokens = tokenize(input)?;
+        Ok(Self {
+            tokens,
+            pos: 0,
+            depth: 0,
+        })
+    }
+
+    /// Fails if nesting `levels` deeper than the current depth exceeds [`MAX_NESTING_DEPTH`].
+    fn check_depth(&self, levels: usize) -> DeltaResult<()> {
+        require!(
+            self.depth + levels <= MAX_NESTING_DEPTH,
+            Error::generic("expression nested too deeply")
+        );
+        Ok(())
+    }
+
+    /// Parses a nested predicate or expression with `parse`, one level deeper than the current one.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> DeltaResult<T>) -> DeltaResult<T> {
+        self.check_depth(1)?;
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse(mut self) -> DeltaResult<Ast> {
+        let ast = self.parse_or()?;
+        match self.tokens.get(self.pos) {
+            Some(token) => Err(Error::generic(format!("Unexpected trailing {token:?}"))),
+            None => Ok(ast),
+        }
+    }
+
+    fn next(&mut self) -> DeltaResult<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token.ok_or_else(|| Error::generic("Unexpected end of input"))
+    }
+
+    fn peek_symbol(&self, symbol: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn next_if_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.peek_symbol(symbol);
+        self.pos += found as usize;
+        found
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(
+            self.tokens.get(self.pos),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword)
+        );
+        self.pos += found as usize;
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> DeltaResult<()> {
+        match self.next()? {
+            Token::Symbol(s) if s == symbol => Ok(()),
+            token => Err(Error::generic(format!(
+                "Expected {symbol:?}, got {token:?}"
+            ))),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> DeltaResult<()> {
+        match self.next()? {
+            Token::Word(word) if word.eq_ignore_ascii_case(keyword) => Ok(()),
+            token => Err(Error::generic(format!("Expected {keyword}, got {token:?}"))),
+        }
+    }
+
+    /// Parses a comma-separated list of predicates or expressions, up to a closing `)` or `}`.
+    fn parse_list<T>(
+        &mut self,
+        close: &str,
+        mut parse_item: impl FnMut(&mut Self) -> DeltaResult<T>,
+    ) -> DeltaResult<Vec<T>> {
+        let mut items = vec![];
+        if self.next_if_symbol(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(parse_item(self)?);
+            if self.next_if_symbol(close) {
+                return Ok(items);
+            }
+            self.expect_symbol(",")?;
+        }
+    }
+
+    fn parse_args(&mut self) -> DeltaResult<Vec<Ast>> {
+        self.parse_list(")", Self::parse_or)
+    }
+
+    fn parse_or(&mut self) -> DeltaResult<Ast> {
+        let mut preds = vec![self.parse_and()?];
+        while self.next_if_keyword("OR") {
+            preds.push(self.parse_and()?);
+        }
+        Ok(match preds.len() {
+            1 => preds.remove(0),
+            _ => Ast::Junction(JunctionPredicateOp::Or, preds),
+        })
+    }
+
+    fn parse_and(&mut self) -> DeltaResult<Ast> {
+        let mut preds = vec![self.parse_not()?];
+        while self.next_if_keyword("AND") {
+            preds.push(self.parse_not()?);
+        }
+        Ok(match preds.len() {
+            1 => preds.remove(0),
+            _ => Ast::Junction(JunctionPredicateOp::And, preds),
+        })
+    }
+
+    fn parse_not(&mut self) -> DeltaResult<Ast> {
+        match self.next_if_keyword("NOT") {
+            true => Ok(Ast::Not(Box::new(self.nested(Self::parse_not)?))),
+            false => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> DeltaResult<Ast> {
+        use BinaryPredicateOp::*;
+        let left = self.parse_additive()?;
+        let op = match self.tokens.get(self.pos) {
+            Some(Token::Symbol("=")) => Some(Equal),
+            Some(Token::Symbol("!=" | "<>")) => Some(NotEqual),
+            Some(Token::Symbol("<")) => Some(LessThan),
+            Some(Token::Symbol("<=")) => Some(LessThanOrEqual),
+            Some(Token::Symbol(">")) => Some(GreaterThan),
+            Some(Token::Symbol(">=")) => Some(GreaterThanOrEqual),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.pos += 1;
+            Ok(Ast::comparison(op, left, self.parse_additive()?))
+        } else if self.next_if_keyword("IS") {
+            let negated = self.next_if_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            let is_null = Ast::IsNull(Box::new(left));
+            Ok(match negated {
+                true => Ast::Not(Box::new(is_null)),
+                false => is_null,
+            })
+        } else if self.next_if_keyword("IN") {
+            Ok(Ast::comparison(In, left, self.parse_in_list()?))
+        } else if self.next_if_keyword("NOT") {
+            self.expect_keyword("IN")?;
+            Ok(Ast::comparison(NotIn, left, self.parse_in_list()?))
+        } else if self.next_if_keyword("STARTS_WITH") {
+            Ok(Ast::comparison(StartsWith, left, self.parse_additive()?))
+        } else {
+            Ok(left)
+        }
+    }
+
+    /// Parses the right side of `[NOT] IN`, which is always a list if parenthesized, even if it
+    /// has only one element (or none).
+    fn parse_in_list(&mut self) -> DeltaResult<Ast> {
+        match self.next_if_symbol("(") {
+            true => Ok(Ast::List(self.parse_args()?)),
+            false => self.parse_additive(),
+        }
+    }
+
+    fn parse_additive(&mut self) -> DeltaResult<Ast> {
+        let mut left = self.parse_multiplicative()?;
+        // Each operator nests the operations to its left one level deeper
+        let mut operators = 0;
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(Token::Symbol("+")) => BinaryExpressionOp::Plus,
+                Some(Token::Symbol("-")) => BinaryExpressionOp::Minus,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            operators += 1;
+            self.check_depth(operators)?;
+            let right = self.parse_multiplicative()?;
+            left = Ast::Arithmetic(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> DeltaResult<Ast> {
+        let mut left = self.parse_primary()?;
+        // Each operator nests the operations to its left one level deeper
+        let mut operators = 0;
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(Token::Symbol("*")) => BinaryExpressionOp::Multiply,
+                Some(Token::Symbol("/")) => BinaryExpressionOp::Divide,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            operators += 1;
+            self.check_depth(operators)?;
+            let right = self.parse_primary()?;
+            left = Ast::Arithmetic(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_primary(&mut self) -> DeltaResult<Ast> {
+        match self.next()? {
+            Token::Number(number) => Ok(Ast::Literal(Literal::Number(number))),
+            Token::String(string) => Ok(Ast::Literal(Literal::String(string))),
+            Token::Column(name) => Ok(Ast::Column(name)),
+            Token::Symbol("-") => match self.next()? {
+                Token::Number(number) => Ok(Ast::Literal(Literal::Number(format!("-{number}")))),
+                token => Err(Error::generic(format!(
+                    "Expected a number after '-', got {token:?}"
+                ))),
+            },
+            Token::Symbol("(") => {
+                let mut items = self.nested(Self::parse_args)?;
+                match items.len() {
+                    1 => Ok(items.remove(0)),
+                    _ => Ok(Ast::List(items)),
+                }
+            }
+            Token::Symbol("{") => {
+                let entries = self.nested(|parser| {
+                    parser.parse_list("}", |parser| {
+                        let key = parser.parse_additive()?;
+                        parser.expect_symbol(":")?;
+                        Ok((key, parser.parse_or()?))
+                    })
+                })?;
+                Ok(Ast::Record(entries))
+            }
+            Token::Word(word) if self.next_if_symbol("(") => {
+                self.nested(|parser| parser.parse_function(word))
+            }
+            Token::Word(word) => {
+                let keyword = KEYWORDS.iter().find(|k| word.eq_ignore_ascii_case(k));
+                match keyword.copied() {
+                    Some("NULL") => Ok(Ast::Literal(Literal::Null)),
+                    Some("TRUE") => Ok(Ast::Literal(Literal::Boolean(true))),
+                    Some("FALSE") => Ok(Ast::Literal(Literal::Boolean(false))),
+                    Some(keyword) => Err(Error::generic(format!("Unexpected keyword {keyword}"))),
+                    None => Ok(Ast::Column(ColumnName::new([word]))),
+                }
+            }
+            Token::Symbol(symbol) => Err(Error::generic(format!("Unexpected {symbol:?}"))),
+        }
+    }
+
+    /// Parses the arguments of a function call, whose opening parenthesis was already consumed.
+    fn parse_function(&mut self, name: String) -> DeltaResult<Ast> {
+        match name.to_ascii_uppercase().as_str() {
+            "AND" => Ok(Ast::Junction(JunctionPredicateOp::And, self.parse_args()?)),
+            "OR" => Ok(Ast::Junction(JunctionPredicateOp::Or, self.parse_args()?)),
+            "COLUMN" => {
+                let name = match self.next()? {
+                    Token::Symbol(")") => return Ok(Ast::Column(ColumnName::new::<&str>([]))),
+                    Token::Word(word) => ColumnName::new([word]),
+                    Token::Column(name) => name,
+                    token => {
+                        return Err(Error::generic(format!(
+                            "Expected a column name, got {token:?}"
+                        )))
+                    }
+                };
+                self.expect_symbol(")")?;
+                Ok(Ast::Column(name))
+            }
+            "STRUCT" => Ok(Ast::Struct(self.parse_args()?)),
+            "COALESCE" => Ok(Ast::Coalesce(self.parse_args()?)),
+            "CAST" => {
+                let expr = self.parse_or()?;
+                self.expect_keyword("AS")?;
+                let data_type = self.parse_data_type()?;
+                self.expect_symbol(")")?;
+                Ok(Ast::Cast(Box::new(expr), data_type))
+            }
+            "DISTINCT" => {
+                let [left, right] = self.parse_exact_args(&name)?;
+                Ok(Ast::comparison(BinaryPredicateOp::Distinct, left, right))
+            }
+            "IF" => {
+                let [condition, then_ast, else_ast] = self.parse_exact_args(&name)?;
+                Ok(Ast::If(
+                    Box::new(condition),
+                    Box::new(then_ast),
+                    Box::new(else_ast),
+                ))
+            }
+            _ => Err(Error::generic(format!("Unknown function {name}"))),
+        }
+    }
+
+    fn parse_exact_args<const N: usize>(&mut self, function: &str) -> DeltaResult<[Ast; N]> {
+        <[_; N]>::try_from(self.parse_args()?).map_err(|args| {
+            Error::generic(format!(
+                "{function} expects {N} arguments, got {}",
+                args.len()
+            ))
+        })
+    }
+
+    /// Parses the name of a primitive type, e.g. `long` or `decimal(10,2)`. Common SQL aliases
+    /// such as `int` and `bigint` are also accepted.
+    fn parse_data_type(&mut self) -> DeltaResult<DataType> {
+        let name = match self.next()? {
+            Token::Word(name) => name,
+            token => return Err(Error::generic(format!("Expected a type, got {token:?}"))),
+        };
+        let data_type = match name.to_ascii_lowercase().as_str() {
+            "string" => DataType::STRING,
+            "long" | "bigint" => DataType::LONG,
+            "integer" | "int" => DataType::INTEGER,
+            "short" | "smallint" => DataType::SHORT,
+            "byte" | "tinyint" => DataType::BYTE,
+            "float" => DataType::FLOAT,
+            "double" => DataType::DOUBLE,
+            "boolean" => DataType::BOOLEAN,
+            "binary" => DataType::BINARY,
+            "date" => DataType::DATE,
+            "timestamp" => DataType::TIMESTAMP,
+            "timestamp_ntz" => DataType::TIMESTAMP_NTZ,
+            "decimal" => {
+                self.expect_symbol("(")?;
+                let precision = self.parse_type_parameter()?;
+                self.expect_symbol(",")?;
+                let scale = self.parse_type_parameter()?;
+                self.expect_symbol(")")?;
+                DataType::decimal(precision, scale)?
+            }
+            _ => return Err(Error::unsupported(format!("Unsupported type {name}"))),
+        };
+        Ok(data_type)
+    }
+
+    fn parse_type_parameter(&mut self) -> DeltaResult<u8> {
+        match self.next()? {
+            Token::Number(number) => number
+                .parse()
+                .map_err(|_| Error::generic(format!("Invalid type parameter {number}"))),
+            token => Err(Error::generic(format!(
+                "Expected a type parameter, got {token:?}"
+            ))),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////
+// Resolver
+////////////////////////////////////////////////////////////////////////
+
+/// Converts an untyped syntax tree into a predicate or expression, typing its literals.
+struct Resolver<'a> {
+    schema: &'a Schema,
+}
+
+impl Resolver<'_> {
+    fn predicate(&self, ast: Ast) -> DeltaResult<Predicate> {
+        match ast {
+            Ast::Comparison(op, left, right) => {
+                let (left, right) = self.operands(*left, *right, Some(op), None)?;
+                Ok(Predicate::binary(op, left, right))
+            }
+            Ast::IsNull(expr) => Ok(Predicate::is_null(self.expression(*expr, None)?)),
+            Ast::Not(pred) => Ok(Predicate::not(self.predicate(*pred)?)),
+            Ast::Junction(op, preds) => {
+                let preds: Vec<_> = preds
+                    .into_iter()
+                    .map(|pred| self.predicate(pred))
+                    .try_collect()?;
+                Ok(Predicate::junction(op, preds))
+            }
+            ast => Ok(Predicate::from_expr(
+                self.expression(ast, Some(&DataType::BOOLEAN))?,
+            )),
+        }
+    }
+
+    /// Resolves an expression. Literals take the `expected` type, if any.
+    fn expression(&self, ast: Ast, expected: Option<&DataType>) -> DeltaResult<Expression> {
+        match ast {
+            Ast::Literal(_) | Ast::List(_) | Ast::Record(_) => {
+                Ok(Expression::literal(self.scalar(ast, expected)?))
+            }
+            Ast::Column(name) => {
+                self.column_type(&name)?;
+                Ok(Expression::Column(name))
+            }
+            Ast::Arithmetic(op, left, right) => {
+                let (left, right) = self.operands(*left, *right, None, expected)?;
+                Ok(Expression::binary(op, left, right))
+            }
+            Ast::Struct(children) => {
+                let children: Vec<_> = match expected {
+                    Some(DataType::Struct(fields)) if fields.fields_len() == children.len() => {
+                        children
+                            .into_iter()
+                            .zip(fields.fields())
+                            .map(|(child, field)| self.expression(child, Some(field.data_type())))
+                            .try_collect()?
+                    }
+                    _ => children
+                        .into_iter()
+                        .map(|child| self.expression(child, None))
+                        .try_collect()?,
+                };
+                Ok(Expression::struct_from(children))
+            }
+            Ast::Cast(expr, data_type) => {
+                Ok(Expression::cast(self.expression(*expr, None)?, data_type))
+            }
+            Ast::Coalesce(children) => {
+                // Children all have the same type, so literals take the type of any other child
+                let expected = match expected {
+                    Some(expected) => Some(expected.clone()),
+                    None => self.common_type(&children)?,
+                };
+                let children: Vec<_> = children
+                    .into_iter()
+                    .map(|child| self.expression(child, expected.as_ref()))
+                    .try_collect()?;
+                Ok(Expression::coalesce(children))
+            }
+            Ast::If(condition, then_ast, else_ast) => {
+                let condition = self.predicate(*condition)?;
+                let (then_expr, else_expr) = self.operands(*then_ast, *else_ast, None, expected)?;
+                Ok(Expression::if_then_else(condition, then_expr, else_expr))
+            }
+            ast @ (Ast::Comparison(..) | Ast::IsNull(_) | Ast::Not(_) | Ast::Junction(..)) => {
+                Ok(Expression::predicate(self.predicate(ast)?))
+            }
+        }
+    }
+
+    /// Resolves the operands of a comparison or arithmetic operation. A literal operand takes the
+    /// type of the other operand, or the `expected` type if both operands are literals.
+    fn operands(
+        &self,
+        left: Ast,
+        right: Ast,
+        op: Option<BinaryPredicateOp>,
+        expected: Option<&DataType>,
+    ) -> DeltaResult<(Expression, Expression)> {
+        // The right side of [NOT] IN is a list of values of the left side's type
+        let is_in_list = matches!(op, Some(BinaryPredicateOp::In | BinaryPredicateOp::NotIn));
+        let left_type = |right_type: DataType| match right_type {
+            DataType::Array(array_type) if is_in_list => Some(array_type.element_type().clone()),
+            _ if is_in_list => None,
+            right_type => Some(right_type),
+        };
+        let right_type = |left_type: DataType| match is_in_list {
+            true => Some(ArrayType::new(left_type, true).into()),
+            false => Some(left_type),
+        };
+        match (left.is_literal(), right.is_literal()) {
+            (true, true) => Ok((
+                self.expression(left, expected)?,
+                self.expression(right, expected)?,
+            )),
+            (true, false) => {
+                let right = self.expression(right, None)?;
+                let expected = self.data_type(&right).and_then(left_type);
+                Ok((self.expression(left, expected.as_ref())?, right))
+            }
+            (false, _) => {
+                let left = self.expression(left, expected)?;
+                let expected = self.data_type(&left).and_then(right_type);
+                Ok((left, self.expression(right, expected.as_ref())?))
+            }
+        }
+    }
+
+    /// Returns the type of the first non-literal in `asts` whose type is known, if any.
+    fn common_type(&self, asts: &[Ast]) -> DeltaResult<Option<DataType>> {
+        for ast in asts.iter().filter(|ast| !ast.is_literal()) {
+            let expr = self.expression(ast.clone(), None)?;
+            if let Some(data_type) = self.data_type(&expr) {
+                return Ok(Some(data_type));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the type of a resolved expression, if it can be determined.
+    fn data_type(&self, expr: &Expression) -> Option<DataType> {
+        match expr {
+            Expression::Literal(scalar) => Some(scalar.data_type()),
+            Expression::Column(name) => self.column_type(name).ok().cloned(),
+            Expression::Predicate(_) => Some(DataType::BOOLEAN),
+            Expression::Struct(_) => None,
+            Expression::Binary(BinaryExpression { left, right, .. }) => {
+                self.data_type(left).or_else(|| self.data_type(right))
+            }
+            Expression::Cast(CastExpression { data_type, .. }) => Some(data_type.clone()),
+            Expression::Coalesce(exprs) => exprs.iter().find_map(|expr| self.data_type(expr)),
+            Expression::If(IfExpression {
+                then_expr,
+                else_expr,
+                ..
+            }) => self
+                .data_type(then_expr)
+                .or_else(|| self.data_type(else_expr)),
+        }
+    }
+
+    fn column_type(&self, name: &ColumnName) -> DeltaResult<&DataType> {
+        let mut path = name.iter();
+        let mut field = path.next().and_then(|first| self.schema.field(first));
+        for field_name in path {
+            field = match field.map(|field| field.data_type()) {
+                Some(DataType::Struct(fields)) => fields.field(field_name),
+                _ => None,
+            };
+        }
+        field
+            .map(|field| field.data_type())
+            .ok_or_else(|| Error::missing_column(format!("Column {name} not found in schema")))
+    }
+
+    /// Resolves a literal value, which takes the `expected` type if there is one.
+    fn scalar(&self, ast: Ast, expected: Option<&DataType>) -> DeltaResult<Scalar> {
+        match ast {
+            Ast::Literal(literal) => literal_scalar(literal, expected),
+            Ast::List(items) => {
+                let element_type = match expected {
+                    Some(DataType::Array(array_type)) => Some(array_type.element_type().clone()),
+                    Some(other) => {
+                        return Err(Error::generic(format!("Expected a {other}, got a list")))
+                    }
+                    None => None,
+                };
+                let mut elements = Vec::with_capacity(items.len());
+                for item in items {
+                    // Without an expected type, the first element decides the type of the rest
+                    let element_type = element_type
+                        .clone()
+                        .or_else(|| elements.first().map(Scalar::data_type));
+                    elements.push(self.scalar(item, element_type.as_ref())?);
+                }
+                let element_type = element_type
+                    .or_else(|| elements.first().map(Scalar::data_type))
+                    .ok_or_else(|| Error::generic("Cannot infer the type of an empty list"))?;
+                let contains_null = elements.iter().any(Scalar::is_null);
+                let array_type = ArrayType::new(element_type, contains_null);
+                Ok(Scalar::Array(ArrayData::try_new(array_type, elements)?))
+            }
+            // Struct values are matched with fields by name, so every field needs exactly one key
+            Ast::Record(entries) => match expected {
+                Some(DataType::Struct(struct_type)) => {
+                    let mut entries_by_name = HashMap::with_capacity(entries.len());
+                    for (key, value) in entries {
+                        let name = match key {
+                            Ast::Column(name) if name.path().len() == 1 => {
+                                name.into_inner().remove(0)
+                            }
+                            Ast::Literal(Literal::String(name)) => name,
+                            key => {
+                                return Err(Error::invalid_struct_data(format!(
+                                    "Expected a field name as struct key, got {key:?}"
+                                )))
+                            }
+                        };
+                        require!(
+                            struct_type.field(&name).is_some(),
+                            Error::invalid_struct_data(format!("Unknown struct field {name:?}"))
+                        );
+                        if entries_by_name.insert(name.clone(), value).is_some() {
+                            return Err(Error::invalid_struct_data(format!(
+                                "Duplicate struct field {name:?}"
+                            )));
+                        }
+                    }
+                    let values: Vec<_> = struct_type
+                        .fields()
+                        .map(|field| {
+                            let value = entries_by_name.remove(field.name()).ok_or_else(|| {
+                                Error::invalid_struct_data(format!(
+                                    "Missing struct field {:?}",
+                                    field.name()
+                                ))
+                            })?;
+                            self.scalar(value, Some(field.data_type()))
+                        })
+                        .try_collect()?;
+                    let fields = struct_type.fields().cloned().collect();
+                    Ok(Scalar::Struct(StructData::try_new(fields, values)?))
+                }
+                Some(DataType::Map(map_type)) => {
+                    let pairs: Vec<_> = entries
+                        .into_iter()
+                        .map(|(key, value)| -> DeltaResult<_> {
+                            let key = self.scalar(key, Some(map_type.key_type()))?;
+                            let value = self.scalar(value, Some(map_type.value_type()))?;
+                            Ok((key, value))
+                        })
+                        .try_collect()?;
+                    Ok(Scalar::Map(MapData::try_new(*map_type.clone(), pairs)?))
+                }
+                _ => Err(Error::generic(
+                    "Cannot infer the type of a struct or map literal",
+                )),
+            },
+            ast => Err(Error::generic(format!("Expected a literal, got {ast:?}"))),
+        }
+    }
+}
+
+/// Gives a literal the `expected` type, or infers its type from its text if there is none.
+fn literal_scalar(literal: Literal, expected: Option<&DataType>) -> DeltaResult<Scalar> {
+    use PrimitiveType::*;
+    let Some(data_type) = expected else {
+        return infer_scalar(literal);
+    };
+    let parse_error = |raw: &str| Error::ParseError(raw.to_string(), data_type.clone());
+    let DataType::Primitive(primitive) = data_type else {
+        return match literal {
+            Literal::Null => Ok(Scalar::Null(data_type.clone())),
+            literal => Err(parse_error(&format!("{literal:?}"))),
+        };
+    };
+    match (literal, primitive) {
+        (Literal::Null, _) => Ok(Scalar::Null(data_type.clone())),
+        (Literal::Boolean(b), Boolean) => Ok(Scalar::Boolean(b)),
+        (Literal::Boolean(b), _) => Err(parse_error(&b.to_string())),
+        // Strings parse like partition values, e.g. '2026-01-01' for a date
+        (Literal::String(s), String) => Ok(Scalar::String(s)),
+        (Literal::String(s), _) if s.is_empty() => Err(parse_error(&s)),
+        (Literal::String(s), primitive) => primitive.parse_scalar(&s),
+        // Dates and timestamps display as their number of days or microseconds since the epoch
+        (Literal::Number(n), Date) => n.parse().map(Scalar::Date).map_err(|_| parse_error(&n)),
+        (Literal::Number(n), Timestamp) => n
+            .parse()
+            .map(Scalar::Timestamp)
+            .map_err(|_| parse_error(&n)),
+        (Literal::Number(n), TimestampNtz) => n
+            .parse()
+            .map(Scalar::TimestampNtz)
+            .map_err(|_| parse_error(&n)),
+        (Literal::Number(n), Decimal(dtype)) => {
+            primitive.parse_scalar(&pad_scale(&n, dtype.scale()))
+        }
+        (Literal::Number(n), Byte | Short | Integer | Long | Float | Double) => {
+            primitive.parse_scalar(&n)
+        }
+        (Literal::Number(n), String | Boolean | Binary) => Err(parse_error(&n)),
+    }
+}
+
+fn infer_scalar(literal: Literal) -> DeltaResult<Scalar> {
+    match literal {
+        Literal::Boolean(b) => Ok(Scalar::Boolean(b)),
+        Literal::String(s) => Ok(Scalar::String(s)),
+        Literal::Number(n) => {
+            if let Ok(i) = n.parse() {
+                Ok(Scalar::Integer(i))
+            } else if let Ok(i) = n.parse() {
+                Ok(Scalar::Long(i))
+            } else {
+                // NOTE: The tokenizer accepts malformed numbers such as `1.2.3`
+                let parsed = n.parse().map(Scalar::Double);
+                parsed.map_err(|_| Error::ParseError(n, DataType::DOUBLE))
+            }
+        }
+        Literal::Null => Err(Error::generic("Cannot infer the type of NULL")),
+    }
+}
+
+/// Appends zeros to the fractional part of a plain decimal number, so that e.g. `1.5` parses as a
+/// `decimal(p, 2)` (which otherwise requires exactly two fractional digits, i.e. `1.50`).
+pub(crate) fn pad_scale(number: &str, scale: u8) -> std::string::String {
+    if number.contains(['e', 'E']) {
+        return number.to_string();
+    }
+    let digits = number.find('.').map_or(0, |pos| number.len() - pos - 1);
+    let padding = (scale as usize).saturating_sub(digits);
+    match (digits, padding) {
+        (_, 0) => number.to_string(),
+        (0, _) => format!("{}.{}", number.trim_end_matches('.'), "0".repeat(padding)),
+        (_, _) => format!("{number}{}", "0".repeat(padding)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, column_pred};
+    use crate::schema::{MapType, StructField, StructType};
+    use crate::{Expression as Expr, Predicate as Pred};
+
+    fn schema() -> Schema {
+        StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("date", DataType::DATE),
+            StructField::nullable("ts", DataType::TIMESTAMP),
+            StructField::nullable("region", DataType::STRING),
+            StructField::nullable("price", DataType::decimal(10, 2).unwrap()),
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable(
+                "nested",
+                StructType::new([
+                    StructField::nullable("x", DataType::INTEGER),
+                    StructField::nullable("a b", DataType::DOUBLE),
+                ]),
+            ),
+            StructField::nullable(
+                "point",
+                StructType::new([
+                    StructField::nullable("x", DataType::INTEGER),
+                    StructField::nullable("y", DataType::DOUBLE),
+                ]),
+            ),
+            StructField::nullable("tags", ArrayType::new(DataType::STRING, true)),
+            StructField::nullable(
+                "attrs",
+                MapType::new(DataType::STRING, DataType::INTEGER, true),
+            ),
+        ])
+    }
+
+    fn parse(input: &str) -> Predicate {
+        parse_predicate(input, &schema()).unwrap_or_else(|e| panic!("{input}: {e}"))
+    }
+
+    #[test]
+    fn test_parse_sql() {
+        let array = |element_type: DataType, values: Vec<Scalar>| {
+            let array_type = ArrayType::new(element_type, false);
+            Scalar::Array(ArrayData::try_new(array_type, values).unwrap())
+        };
+        let cases = [
+            (
+                "date >= '2026-01-01' AND region IN ('eu','us')",
+                Pred::and(
+                    column_expr!("date").ge(Scalar::Date(20454)),
+                    Pred::binary(
+                        BinaryPredicateOp::In,
+                        column_expr!("region"),
+                        array(DataType::STRING, vec!["eu".into(), "us".into()]),
+                    ),
+                ),
+            ),
+            (
+                "id = 1 OR id > 10 AND NOT flag",
+                Pred::or(
+                    column_expr!("id").eq(Scalar::Long(1)),
+                    Pred::and(
+                        column_expr!("id").gt(Scalar::Long(10)),
+                        Pred::not(column_pred!("flag")),
+                    ),
+                ),
+            ),
+            (
+                "(id = 1 OR id > 10) and not (flag)",
+                Pred::and(
+                    Pred::or(
+                        column_expr!("id").eq(Scalar::Long(1)),
+                        column_expr!("id").gt(Scalar::Long(10)),
+                    ),
+                    Pred::not(column_pred!("flag")),
+                ),
+            ),
+            (
+                "nested.x IS NOT NULL AND nested.`a b` < 1e3",
+                Pred::and(
+                    column_expr!("nested.x").is_not_null(),
+                    Expr::column(["nested", "a b"]).lt(Scalar::Double(1000.0)),
+                ),
+            ),
+            (
+                "price <> 9.5",
+                column_expr!("price").ne(Scalar::decimal(950, 10, 2).unwrap()),
+            ),
+            (
+                "ts < '2026-01-01 12:00:00' AND 'a' <= region",
+                Pred::and(
+                    column_expr!("ts").lt(Scalar::Timestamp(1767268800000000)),
+                    Expr::literal("a").le(column_expr!("region")),
+                ),
+            ),
+            (
+                "id * 2 + 1 NOT IN (3)",
+                Pred::binary(
+                    BinaryPredicateOp::NotIn,
+                    column_expr!("id") * Scalar::Long(2) + Scalar::Long(1),
+                    array(DataType::LONG, vec![Scalar::Long(3)]),
+                ),
+            ),
+            (
+                "region STARTS_WITH 'e''u' AND id != -1",
+                Pred::and(
+                    column_expr!("region").starts_with(Expr::literal("e'u")),
+                    column_expr!("id").ne(Scalar::Long(-1)),
+                ),
+            ),
+            (
+                "COALESCE(id, 0) = CAST(nested.x AS bigint)",
+                Pred::eq(
+                    Expr::coalesce([column_expr!("id"), Scalar::Long(0).into()]),
+                    Expr::cast(column_expr!("nested.x"), DataType::LONG),
+                ),
+            ),
+            (
+                "attrs = {'a': 1}",
+                Pred::eq(
+                    column_expr!("attrs"),
+                    Scalar::Map(
+                        MapData::try_new(
+                            MapType::new(DataType::STRING, DataType::INTEGER, true),
+                            [("a", 1)],
+                        )
+                        .unwrap(),
+                    ),
+                ),
+            ),
+            (
+                "point = {y: 1.5, x: 2}",
+                Pred::eq(
+                    column_expr!("point"),
+                    Scalar::Struct(
+                        StructData::try_new(
+                            vec![
+                                StructField::nullable("x", DataType::INTEGER),
+                                StructField::nullable("y", DataType::DOUBLE),
+                            ],
+                            vec![Scalar::Integer(2), Scalar::Double(1.5)],
+                        )
+                        .unwrap(),
+                    ),
+                ),
+            ),
+        ];
+        for (input, expected) in cases {
+            let pred = parse(input);
+            assert_eq!(pred, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let price = Scalar::decimal(1234, 10, 2).unwrap();
+        let tags = ArrayType::new(DataType::STRING, true);
+        let tags = Scalar::Array(ArrayData::try_new(tags, ["a", "b"]).unwrap());
+        let point_type = schema().field("point").unwrap().data_type().clone();
+        let DataType::Struct(point_fields) = point_type else {
+            panic!("point is not a struct");
+        };
+        let point = StructData::try_new(
+            point_fields.fields().cloned().collect(),
+            vec![Scalar::Integer(1), Scalar::Double(2.5)],
+        )
+        .unwrap();
+        let preds = [
+            column_expr!("date").ge(Scalar::Date(20454)),
+            column_expr!("ts").lt(Scalar::Timestamp(-5)),
+            Pred::not(column_expr!("price").eq(price)),
+            Pred::or_from([
+                column_pred!("flag"),
+                Pred::literal(false),
+                column_expr!("region").is_null(),
+            ]),
+            Pred::and(Pred::and_from([]), column_expr!("region").is_not_null()),
+            Pred::distinct(column_expr!("id"), Expr::literal(Scalar::Long(3))),
+            Pred::binary(
+                BinaryPredicateOp::In,
+                Expr::literal("a"),
+                column_expr!("tags"),
+            ),
+            Pred::eq(column_expr!("tags"), tags),
+            Pred::eq(column_expr!("point"), Scalar::Struct(point)),
+            Pred::eq(Expr::column(["nested", "a b"]), Scalar::Double(0.1)),
+            column_expr!("region").starts_with(Expr::literal("it's")),
+            ((column_expr!("id") - Scalar::Long(1)) * Scalar::Long(2)).lt(Scalar::Long(10)),
+            (column_expr!("id") - (Expr::literal(Scalar::Long(1)) - column_expr!("id")))
+                .gt(Scalar::Long(0)),
+            Pred::eq(
+                Expr::if_then_else(
+                    column_expr!("flag").is_null(),
+                    Expr::literal(Scalar::Long(1)),
+                    column_expr!("id"),
+                ),
+                Expr::coalesce([column_expr!("id"), Scalar::Long(0).into()]),
+            ),
+            Pred::gt(
+                Expr::cast(column_expr!("nested.x"), DataType::decimal(5, 1).unwrap()),
+                Scalar::decimal(5, 5, 1).unwrap(),
+            ),
+        ];
+        for pred in preds {
+            let displayed = pred.to_string();
+            assert_eq!(parse(&displayed), pred, "{displayed}");
+        }
+
+        let exprs = [
+            Expr::struct_from([column_expr!("id"), column_expr!("region")]),
+            Expr::predicate(column_expr!("id").lt(Scalar::Long(3))),
+        ];
+        for expr in exprs {
+            let displayed = expr.to_string();
+            let parsed = parse_expression(&displayed, &schema()).unwrap();
+            assert_eq!(parsed, expr, "{displayed}");
+        }
+    }
+
+    #[test]
+    fn test_infer_literal_types() {
+        // Literals combined only with each other are typed independently
+        let expr = parse_expression("2.5 * 3000000000", &schema()).unwrap();
+        assert_eq!(expr, Expr::literal(2.5) * Expr::literal(3000000000i64));
+        // ... but take the type of any non-literal they are combined with
+        let expr = parse_expression("1 + 2.5 * 3000000000", &schema()).unwrap();
+        assert_eq!(
+            expr,
+            Expr::literal(1.0) + Expr::literal(2.5) * Expr::literal(3000000000i64)
+        );
+        let expr = parse_expression("COALESCE(null, 1, nested.x)", &schema()).unwrap();
+        assert_eq!(expr.to_string(), "COALESCE(null, 1, Column(nested.x))",);
+        let Expression::Coalesce(children) = expr else {
+            panic!("Expected COALESCE");
+        };
+        let Expression::Literal(Scalar::Null(null_type)) = &children[0] else {
+            panic!("Expected NULL, got {}", children[0]);
+        };
+        assert_eq!(null_type, &DataType::INTEGER);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            "",
+            "id =",
+            "id = 1 1",
+            "(id = 1",
+            "missing = 1",
+            "nested.missing = 1",
+            "region = 'unclosed",
+            "`unclosed = 1",
+            "date = 'not a date'",
+            "id = 'abc'",
+            "flag = 1",
+            "region = 1",
+            "null = null",
+            "1 IN ()",
+            "id = {1: 2}",
+            "point = {x: 1}",
+            "point = {x: 1, z: 2.5}",
+            "point = {x: 1, x: 2}",
+            "point = {x: 1, y: 2.5, z: 3}",
+            "point = {1: 1, y: 2.5}",
+            "AND = 1",
+            "id IS 1",
+            "id NOT 1",
+            "UNKNOWN(id)",
+            "DISTINCT(id)",
+            "IF(flag, 1)",
+            "CAST(id AS array)",
+            "id # 1",
+            "-id < 1",
+        ];
+        for input in cases {
+            let result = parse_predicate(input, &schema());
+            assert!(result.is_err(), "{input} parsed as {result:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_nesting_depth() {
+        let nested = |open: &str, close: &str, depth| {
+            format!("{}flag{}", open.repeat(depth), close.repeat(depth))
+        };
+        let cases = [("NOT ", ""), ("(", ")"), ("AND(", ")"), ("COALESCE(", ")")];
+        for (open, close) in cases {
+            let input = nested(open, close, MAX_NESTING_DEPTH);
+            assert!(parse_predicate(&input, &schema()).is_ok(), "{open}");
+            // Deeply nested input fails instead of overflowing the stack
+            for depth in [MAX_NESTING_DEPTH + 1, 10_000] {
+                let input = nested(open, close, depth);
+                let err = parse_predicate(&input, &schema()).unwrap_err();
+                assert!(
+                    err.to_string().contains("nested too deeply"),
+                    "{open}: {err}"
+                );
+            }
+        }
+        let sum = |terms| vec!["id"; terms].join(" + ");
+        assert!(parse_predicate(&format!("{} = 1", sum(MAX_NESTING_DEPTH + 1)), &schema()).is_ok());
+        let err = parse_predicate(&format!("{} = 1", sum(10_000)), &schema()).unwrap_err();
+        assert!(err.to_string().contains("nested too deeply"), "{err}");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("`and`=a.`b.c`.d AND x<=-1.5e-3 'it''s'").unwrap();
+        let expected = [
+            Token::Column(ColumnName::new(["and"])),
+            Token::Symbol("="),
+            Token::Column(ColumnName::new(["a", "b.c", "d"])),
+            Token::Word("AND".into()),
+            Token::Word("x".into()),
+            Token::Symbol("<="),
+            Token::Symbol("-"),
+            Token::Number("1.5e-3".into()),
+            Token::String("it's".into()),
+        ];
+        assert_eq!(tokens, expected);
+    }
+}
@@ -3,13 +3,15 @@ use std::fmt::{Display, Formatter};
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::actions::schemas::ToDataType;
 use crate::schema::{ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField};
 use crate::utils::require;
 use crate::{DeltaResult, Error};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "DecimalFields", try_from = "DecimalFields")]
 pub struct DecimalData {
     bits: i128,
     ty: DecimalType,
@@ -46,6 +48,37 @@ impl DecimalData {
     }
 }
 
+/// The serialized form of [`DecimalData`]. The unscaled value is a string, because many JSON
+/// readers can't represent 128-bit integers.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DecimalFields {
+    value: String,
+    precision: u8,
+    scale: u8,
+}
+
+impl From<DecimalData> for DecimalFields {
+    fn from(d: DecimalData) -> Self {
+        Self {
+            value: d.bits.to_string(),
+            precision: d.precision(),
+            scale: d.scale(),
+        }
+    }
+}
+
+impl TryFrom<DecimalFields> for DecimalData {
+    type Error = Error;
+
+    fn try_from(d: DecimalFields) -> DeltaResult<Self> {
+        let bits: i128 = d.value.parse().map_err(|_| {
+            Error::invalid_decimal(format!("Invalid unscaled decimal value {:?}", d.value))
+        })?;
+        Self::try_new(bits, DecimalType::try_new(d.precision, d.scale)?)
+    }
+}
+
 /// Computes the decimal precision of a 128-bit number. The largest possible magnitude is i128::MIN
 /// = -2**127 with 39 decimal digits.
 fn get_decimal_precision(value: i128) -> u8 {
@@ -53,8 +86,10 @@ fn get_decimal_precision(value: i128) -> u8 {
     value.unsigned_abs().checked_ilog10().map_or(0, |p| p + 1) as _
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ArrayFields")]
 pub struct ArrayData {
+    #[serde(rename = "type")]
     tpe: ArrayType,
     /// This exists currently for literal list comparisons, but should not be depended on see below
     elements: Vec<Scalar>,
@@ -101,8 +136,27 @@ impl ArrayData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// The serialized form of [`ArrayData`], validated by [`ArrayData::try_new`] when deserializing
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArrayFields {
+    #[serde(rename = "type")]
+    tpe: ArrayType,
+    elements: Vec<Scalar>,
+}
+
+impl TryFrom<ArrayFields> for ArrayData {
+    type Error = Error;
+
+    fn try_from(a: ArrayFields) -> DeltaResult<Self> {
+        Self::try_new(a.tpe, a.elements)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "MapFields")]
 pub struct MapData {
+    #[serde(rename = "type")]
     data_type: MapType,
     pairs: Vec<(Scalar, Scalar)>,
 }
@@ -159,7 +213,25 @@ impl MapData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// The serialized form of [`MapData`], validated by [`MapData::try_new`] when deserializing
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MapFields {
+    #[serde(rename = "type")]
+    data_type: MapType,
+    pairs: Vec<(Scalar, Scalar)>,
+}
+
+impl TryFrom<MapFields> for MapData {
+    type Error = Error;
+
+    fn try_from(m: MapFields) -> DeltaResult<Self> {
+        Self::try_new(m.data_type, m.pairs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "StructFields")]
 pub struct StructData {
     fields: Vec<StructField>,
     values: Vec<Scalar>,
@@ -215,9 +287,29 @@ impl StructData {
     }
 }
 
+/// The serialized form of [`StructData`], validated by [`StructData::try_new`] when deserializing
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StructFields {
+    fields: Vec<StructField>,
+    values: Vec<Scalar>,
+}
+
+impl TryFrom<StructFields> for StructData {
+    type Error = Error;
+
+    fn try_from(s: StructFields) -> DeltaResult<Self> {
+        Self::try_new(s.fields, s.values)
+    }
+}
+
 /// A single value, which can be null. Used for representing literal values
 /// in [Expressions][crate::expressions::Expression].
-#[derive(Debug, Clone)]
+///
+/// Serializes as a single-entry map from the (camelCase) variant name to its value, e.g.
+/// `{"integer": 1}` or `{"null": "long"}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Scalar {
     /// 32bit integer
     Integer(i32),
@@ -310,7 +402,7 @@ impl Display for Scalar {
             Self::Byte(i) => write!(f, "{}", i),
             Self::Float(fl) => write!(f, "{}", fl),
             Self::Double(fl) => write!(f, "{}", fl),
-            Self::String(s) => write!(f, "'{}'", s),
+            Self::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
             Self::Boolean(b) => write!(f, "{}", b),
             Self::Timestamp(ts) => write!(f, "{}", ts),
             Self::TimestampNtz(ts) => write!(f, "{}", ts),
@@ -414,13 +506,80 @@ impl PartialOrd for Scalar {
                 .flatten(),
             (Decimal(_), _) => None,
             (Null(_), _) => None, // NOTE: NULL values are incomparable by definition
-            (Struct(_), _) => None, // TODO: Support Struct?
-            (Array(_), _) => None, // TODO: Support Array?
-            (Map(_), _) => None,  // TODO: Support Map?
+            (Struct(s1), Struct(s2)) => {
+                let same_types = s1.fields.len() == s2.fields.len()
+                    && s1
+                        .fields
+                        .iter()
+                        .zip(&s2.fields)
+                        .all(|(f1, f2)| f1.data_type() == f2.data_type());
+                same_types
+                    .then(|| partial_cmp_nested_values(&s1.values, &s2.values))
+                    .flatten()
+            }
+            (Struct(_), _) => None,
+            (Array(a1), Array(a2)) => (a1.array_type().element_type()
+                == a2.array_type().element_type())
+            .then(|| partial_cmp_nested_values(&a1.elements, &a2.elements))
+            .flatten(),
+            (Array(_), _) => None,
+            (Map(m1), Map(m2)) => {
+                let (t1, t2) = (m1.map_type(), m2.map_type());
+                if t1.key_type() != t2.key_type() || t1.value_type() != t2.value_type() {
+                    return None;
+                }
+                // Maps compare as arrays of key/value entries sorted by key, so that the order in
+                // which the entries were inserted doesn't matter
+                partial_cmp_nested_values(sorted_map_entries(m1)?, sorted_map_entries(m2)?)
+            }
+            (Map(_), _) => None,
         }
     }
 }
 
+/// Returns the keys and values of `map`, interleaved and sorted by key, or `None` if some keys are
+/// incomparable.
+fn sorted_map_entries(map: &MapData) -> Option<Vec<&Scalar>> {
+    let mut pairs: Vec<_> = map.pairs.iter().collect();
+    let mut comparable = true;
+    pairs.sort_by(|(k1, _), (k2, _)| {
+        k1.partial_cmp(k2).unwrap_or_else(|| {
+            comparable = false;
+            Ordering::Equal
+        })
+    });
+    comparable.then(|| pairs.into_iter().flat_map(|(k, v)| [k, v]).collect())
+}
+
+/// Lexicographically compares the values nested inside two struct, array or map scalars, with a
+/// shorter sequence ordering before any longer sequence it is a prefix of.
+///
+/// Unlike top-level comparisons, nested NULL values are comparable: they equal each other and
+/// order before any non-null value. This matches the `nulls_first` ordering arrow uses for nested
+/// arrays, so that kernel and engine evaluate nested comparisons the same way.
+fn partial_cmp_nested_values<'a>(
+    left: impl IntoIterator<Item = &'a Scalar>,
+    right: impl IntoIterator<Item = &'a Scalar>,
+) -> Option<Ordering> {
+    use itertools::EitherOrBoth::*;
+    for values in left.into_iter().zip_longest(right) {
+        let ord = match values {
+            Both(l, r) => match (l.is_null(), r.is_null()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (false, false) => l.partial_cmp(r)?,
+            },
+            Left(_) => Ordering::Greater,
+            Right(_) => Ordering::Less,
+        };
+        if ord != Ordering::Equal {
+            return Some(ord);
+        }
+    }
+    Some(Ordering::Equal)
+}
+
 impl From<i8> for Scalar {
     fn from(i: i8) -> Self {
         Self::Byte(i)
@@ -917,6 +1076,92 @@ mod tests {
         assert_eq!(null.partial_cmp(&null), None);
     }
 
+    #[test]
+    fn test_partial_cmp_nested() {
+        let array_type = ArrayType::new(DataType::INTEGER, true);
+        let array = |values: &[Option<i32>]| {
+            Scalar::Array(ArrayData::try_new(array_type.clone(), values.iter().copied()).unwrap())
+        };
+        let fields = vec![
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::STRING),
+        ];
+        let structure = |a: Option<i32>, b: Option<&str>| {
+            let values = vec![Scalar::from(a), Scalar::from(b.map(String::from))];
+            Scalar::Struct(StructData::try_new(fields.clone(), values).unwrap())
+        };
+        let map_type = MapType::new(DataType::STRING, DataType::INTEGER, true);
+        let map = |pairs: &[(&str, i32)]| {
+            let pairs = pairs.iter().map(|(k, v)| (k.to_string(), *v));
+            Scalar::Map(MapData::try_new(map_type.clone(), pairs).unwrap())
+        };
+
+        // arrays compare lexicographically, with prefixes ordering first
+        assert_eq!(
+            array(&[Some(1), Some(2)]).partial_cmp(&array(&[Some(1), Some(3)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            array(&[Some(2)]).partial_cmp(&array(&[Some(1), Some(3)])),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            array(&[Some(1)]).partial_cmp(&array(&[Some(1), Some(3)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(array(&[]).partial_cmp(&array(&[])), Some(Ordering::Equal));
+
+        // nested NULLs are equal to each other and order first
+        assert_eq!(
+            array(&[Some(1), None]).partial_cmp(&array(&[Some(1), None])),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            array(&[None]).partial_cmp(&array(&[Some(i32::MIN)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            structure(Some(1), None).partial_cmp(&structure(Some(1), Some(""))),
+            Some(Ordering::Less)
+        );
+
+        // structs compare field by field
+        assert_eq!(
+            structure(Some(1), Some("b")).partial_cmp(&structure(Some(1), Some("a"))),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            structure(Some(1), Some("b")).partial_cmp(&structure(Some(2), Some("a"))),
+            Some(Ordering::Less)
+        );
+        assert_eq!(structure(None, Some("a")), structure(None, Some("a")));
+
+        // maps compare as lists of entries sorted by key
+        assert_eq!(map(&[("a", 1)]), map(&[("a", 1)]));
+        assert_eq!(map(&[("a", 1), ("b", 2)]), map(&[("b", 2), ("a", 1)]));
+        assert_eq!(
+            map(&[("c", 1), ("a", 1)]).partial_cmp(&map(&[("b", 1), ("a", 1)])),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            map(&[("a", 1)]).partial_cmp(&map(&[("a", 2)])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            map(&[("b", 1)]).partial_cmp(&map(&[("a", 1), ("c", 1)])),
+            Some(Ordering::Greater)
+        );
+
+        // values of different types and top-level NULLs remain incomparable
+        let long_array = ArrayType::new(DataType::LONG, true);
+        let other = Scalar::Array(ArrayData::try_new(long_array, [1i64]).unwrap());
+        assert_eq!(array(&[Some(1)]).partial_cmp(&other), None);
+        assert_eq!(array(&[Some(1)]).partial_cmp(&map(&[("a", 1)])), None);
+        let null = Scalar::Null(DataType::Array(Box::new(array_type.clone())));
+        assert_eq!(null.partial_cmp(&array(&[])), None);
+        assert_eq!(array(&[]).partial_cmp(&null), None);
+    }
+
     #[test]
     fn test_partial_eq() {
         let a = Scalar::Integer(1);
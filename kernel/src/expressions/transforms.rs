@@ -2,8 +2,8 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 
 use crate::expressions::{
-    BinaryExpression, BinaryPredicate, ColumnName, Expression, JunctionPredicate, Predicate,
-    Scalar, UnaryPredicate,
+    BinaryExpression, BinaryPredicate, CastExpression, ColumnName, Expression, IfExpression,
+    JunctionPredicate, Predicate, Scalar, UnaryPredicate,
 };
 
 /// Generic framework for recursive bottom-up transforms of expressions and
@@ -77,6 +77,28 @@ pub trait ExpressionTransform<'a> {
         self.recurse_into_expr_binary(expr)
     }
 
+    /// Called for each [`CastExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_cast`] if they wish to recursively transform the child.
+    fn transform_expr_cast(&mut self, expr: &'a CastExpression) -> Option<Cow<'a, CastExpression>> {
+        self.recurse_into_expr_cast(expr)
+    }
+
+    /// Called for the expression list of each [`Expression::Coalesce`] encountered during the
+    /// traversal. Implementations can call [`Self::recurse_into_expr_coalesce`] if they wish to
+    /// recursively transform the child expressions.
+    fn transform_expr_coalesce(
+        &mut self,
+        exprs: &'a Vec<Expression>,
+    ) -> Option<Cow<'a, Vec<Expression>>> {
+        self.recurse_into_expr_coalesce(exprs)
+    }
+
+    /// Called for each [`IfExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_if`] if they wish to recursively transform the children.
+    fn transform_expr_if(&mut self, expr: &'a IfExpression) -> Option<Cow<'a, IfExpression>> {
+        self.recurse_into_expr_if(expr)
+    }
+
     /// Called for each [`BinaryPredicate`] encountered during the traversal. Implementations can
     /// call [`Self::recurse_into_pred_binary`] if they wish to recursively transform the children.
     fn transform_pred_binary(
@@ -121,6 +143,18 @@ pub trait ExpressionTransform<'a> {
                 Owned(b) => Owned(Expression::Binary(b)),
                 Borrowed(_) => Borrowed(expr),
             },
+            Expression::Cast(c) => match self.transform_expr_cast(c)? {
+                Owned(c) => Owned(Expression::Cast(c)),
+                Borrowed(_) => Borrowed(expr),
+            },
+            Expression::Coalesce(c) => match self.transform_expr_coalesce(c)? {
+                Owned(c) => Owned(Expression::Coalesce(c)),
+                Borrowed(_) => Borrowed(expr),
+            },
+            Expression::If(i) => match self.transform_expr_if(i)? {
+                Owned(i) => Owned(Expression::If(i)),
+                Borrowed(_) => Borrowed(expr),
+            },
         };
         Some(expr)
     }
@@ -240,6 +274,46 @@ pub trait ExpressionTransform<'a> {
         Some(b)
     }
 
+    /// Recursively transforms a cast expression's child. Returns `None` if the child was removed,
+    /// `Some(Cow::Owned)` if the child was changed, and `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_cast(&mut self, c: &'a CastExpression) -> Option<Cow<'a, CastExpression>> {
+        use Cow::*;
+        let c = match self.transform_expr(&c.expr)? {
+            Owned(expr) => Owned(CastExpression::new(expr, c.data_type.clone())),
+            Borrowed(_) => Borrowed(c),
+        };
+        Some(c)
+    }
+
+    /// Recursively transforms a coalesce expression's children. Returns `None` if all children
+    /// were removed, `Some(Cow::Owned)` if at least one child was changed or removed, and
+    /// `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_coalesce(
+        &mut self,
+        exprs: &'a Vec<Expression>,
+    ) -> Option<Cow<'a, Vec<Expression>>> {
+        recurse_into_children(exprs, |e| self.transform_expr(e))
+    }
+
+    /// Recursively transforms a conditional expression's children. Returns `None` if at least one
+    /// child was removed, `Some(Cow::Owned)` if at least one child changed, and
+    /// `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_if(&mut self, i: &'a IfExpression) -> Option<Cow<'a, IfExpression>> {
+        use Cow::*;
+        let condition = self.transform_pred(&i.condition)?;
+        let then_expr = self.transform_expr(&i.then_expr)?;
+        let else_expr = self.transform_expr(&i.else_expr)?;
+        let i = match (&condition, &then_expr, &else_expr) {
+            (Borrowed(_), Borrowed(_), Borrowed(_)) => Borrowed(i),
+            _ => Owned(IfExpression::new(
+                condition.into_owned(),
+                then_expr.into_owned(),
+                else_expr.into_owned(),
+            )),
+        };
+        Some(i)
+    }
+
     /// Recursively transforms a junction predicate's children. Returns `None` if all children were
     /// removed, `Some(Cow::Owned)` if at least one child was changed or removed, and
     /// `Some(Cow::Borrowed)` otherwise.
@@ -256,7 +330,8 @@ pub trait ExpressionTransform<'a> {
     }
 }
 
-/// Used to recurse into the children of an `Expression::Struct` or `Predicate::Junction`.
+/// Used to recurse into the children of an `Expression::Struct`, `Expression::Coalesce` or
+/// `Predicate::Junction`.
 fn recurse_into_children<'a, T: Clone>(
     children: &'a Vec<T>,
     recurse_fn: impl FnMut(&'a T) -> Option<Cow<'a, T>>,
@@ -0,0 +1,276 @@
+//! A versioned serialized form of predicates and expressions, for shipping them (e.g. a scan
+//! predicate, or the transform returned by [`crate::scan::get_transform_for_row`]) to
+//! remote executors that may run a different kernel version.
+
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::utils::require;
+
+/// The current version of the serialized form of [`Expression`]s, [`Predicate`]s and
+/// [`Scalar`]s.
+///
+/// Adding a new kind of node (e.g. a new [`Expression`] variant) does not change the version:
+/// readers reject the unknown node when they encounter it. The version only changes when the
+/// serialized form of an existing node changes in a way older readers would misinterpret.
+///
+/// [`Expression`]: super::Expression
+/// [`Predicate`]: super::Predicate
+/// [`Scalar`]: super::Scalar
+pub const EXPRESSION_FORMAT_VERSION: u32 = 1;
+
+/// A value (typically an [`Expression`] or [`Predicate`]), tagged with the
+/// [`EXPRESSION_FORMAT_VERSION`] it was serialized with.
+///
+/// Deserialization fails if the version is newer than this kernel supports, without attempting
+/// to interpret the value, and otherwise fails on any node this kernel doesn't know:
+///
+/// ```
+/// # use delta_kernel::expressions::{column_expr, Expression as Expr, Predicate, Versioned};
+/// let pred = column_expr!("a.b").lt(Expr::literal(5));
+/// let json = serde_json::to_string(&Versioned::new(pred.clone())).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"version":1,"value":{"binary":{"op":"lessThan","left":{"column":["a","b"]},"right":{"literal":{"integer":5}}}}}"#
+/// );
+/// let parsed: Versioned<Predicate> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(parsed.into_inner(), pred);
+///
+/// let newer = r#"{"version":2,"value":{"fancyNewNode":{}}}"#;
+/// assert!(serde_json::from_str::<Versioned<Predicate>>(newer).is_err());
+/// ```
+///
+/// [`Expression`]: super::Expression
+/// [`Predicate`]: super::Predicate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Versioned<T> {
+    version: u32,
+    value: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tags `value` with the current [`EXPRESSION_FORMAT_VERSION`].
+    pub fn new(value: T) -> Self {
+        Self {
+            version: EXPRESSION_FORMAT_VERSION,
+            value,
+        }
+    }
+
+    /// The format version the value was serialized with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The versioned value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Consumes the [`Versioned`], returning the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// The serialized fields of [`Versioned`]. The value is buffered so that its version can be
+/// checked before interpreting it, regardless of the order the fields were written in.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VersionedFields {
+    version: u32,
+    value: serde_json::Value,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let VersionedFields { version, value } = VersionedFields::deserialize(deserializer)?;
+        require!(
+            (1..=EXPRESSION_FORMAT_VERSION).contains(&version),
+            de::Error::custom(format!(
+                "Unsupported expression format version {version}, expected 1..={EXPRESSION_FORMAT_VERSION}"
+            ))
+        );
+        let value = T::deserialize(value).map_err(de::Error::custom)?;
+        Ok(Self { version, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::expressions::{
+        column_expr, column_pred, ArrayData, BinaryPredicateOp, ColumnName, Expression as Expr,
+        MapData, Predicate as Pred, Scalar, StructData,
+    };
+    use crate::schema::{ArrayType, DataType, MapType, StructField};
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        let json = serde_json::to_string(&Versioned::new(value)).unwrap();
+        let parsed: Versioned<T> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version(), EXPRESSION_FORMAT_VERSION);
+        parsed.into_inner()
+    }
+
+    #[test]
+    fn test_scalar_round_trip() {
+        let array_type = ArrayType::new(DataType::INTEGER, true);
+        let map_type = MapType::new(DataType::STRING, DataType::LONG, true);
+        let fields = vec![
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::not_null("b", DataType::STRING),
+        ];
+        let scalars = [
+            Scalar::Integer(1),
+            Scalar::Long(i64::MIN),
+            Scalar::Short(-3),
+            Scalar::Byte(4),
+            Scalar::Float(1.5),
+            Scalar::Double(-2.25),
+            Scalar::String("it's".into()),
+            Scalar::Boolean(true),
+            Scalar::Timestamp(1),
+            Scalar::TimestampNtz(2),
+            Scalar::Date(3),
+            Scalar::Binary(vec![0, 255]),
+            Scalar::decimal(10i128.pow(38) - 1, 38, 10).unwrap(),
+            Scalar::Array(ArrayData::try_new(array_type, [Some(1), None]).unwrap()),
+            Scalar::Map(MapData::try_new(map_type, [("k", Some(1i64)), ("n", None)]).unwrap()),
+            Scalar::Struct(StructData::try_new(fields, vec![1.into(), "x".into()]).unwrap()),
+        ];
+        for scalar in scalars {
+            assert_eq!(round_trip(&scalar), scalar);
+        }
+
+        // NULL never equals NULL, so compare the types instead
+        let null = Scalar::Null(DataType::decimal(5, 2).unwrap());
+        assert_eq!(round_trip(&null).data_type(), null.data_type());
+    }
+
+    #[test]
+    fn test_predicate_round_trip() {
+        let preds = [
+            Pred::and_from([
+                column_expr!("a").lt(Expr::literal(1)),
+                Pred::not(column_expr!("b").is_null()),
+                Pred::or(column_pred!("c"), Pred::literal(false)),
+                Pred::distinct(column_expr!("d"), Expr::literal("x")),
+                column_expr!("e").starts_with(Expr::literal("pre")),
+            ]),
+            Pred::binary(
+                BinaryPredicateOp::NotIn,
+                Expr::column(["f", "g.h"]),
+                Scalar::Array(
+                    ArrayData::try_new(ArrayType::new(DataType::LONG, false), [1i64, 2]).unwrap(),
+                ),
+            ),
+            Pred::eq(
+                Expr::if_then_else(
+                    column_expr!("a").gt(Expr::literal(0)),
+                    Expr::cast(column_expr!("a"), DataType::LONG),
+                    Expr::coalesce([column_expr!("b"), Expr::literal(0i64)]),
+                ),
+                (column_expr!("c") + Expr::literal(1i64)) * Expr::literal(2i64),
+            ),
+        ];
+        for pred in preds {
+            assert_eq!(round_trip(&pred), pred);
+        }
+
+        let expr = Arc::new(Expr::struct_from([
+            column_expr!("a"),
+            Expr::predicate(column_expr!("b").ge(Expr::literal(2))),
+        ]));
+        assert_eq!(round_trip(&expr), expr);
+    }
+
+    #[test]
+    fn test_serialized_form() {
+        let pred = Pred::and(
+            Pred::not(column_expr!("a").is_null()),
+            Pred::ge(
+                column_expr!("b") / Expr::literal(2),
+                Scalar::decimal(150, 5, 2).unwrap(),
+            ),
+        );
+        let expected = json!({
+            "version": 1,
+            "value": {
+                "junction": {
+                    "op": "and",
+                    "preds": [
+                        {"not": {"unary": {"op": "isNull", "expr": {"column": ["a"]}}}},
+                        {"binary": {
+                            "op": "greaterThanOrEqual",
+                            "left": {"binary": {
+                                "op": "divide",
+                                "left": {"column": ["b"]},
+                                "right": {"literal": {"integer": 2}}
+                            }},
+                            "right": {"literal": {"decimal": {
+                                "value": "150",
+                                "precision": 5,
+                                "scale": 2
+                            }}}
+                        }}
+                    ]
+                }
+            }
+        });
+        assert_eq!(
+            serde_json::to_value(Versioned::new(pred)).unwrap(),
+            expected
+        );
+
+        let name = ColumnName::new(["a", "b.c"]);
+        assert_eq!(serde_json::to_value(&name).unwrap(), json!(["a", "b.c"]));
+        let null = Expr::null_literal(DataType::LONG);
+        assert_eq!(
+            serde_json::to_value(&null).unwrap(),
+            json!({"literal": {"null": "long"}})
+        );
+    }
+
+    #[test]
+    fn test_reject_unsupported() {
+        let value = json!({"column": ["a"]});
+        let parse = |json| serde_json::from_value::<Versioned<Expr>>(json);
+
+        let result = parse(json!({"version": EXPRESSION_FORMAT_VERSION + 1, "value": value}));
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("Unsupported expression format version"),
+            "{err}"
+        );
+        let result = parse(json!({"version": 0, "value": value}));
+        assert!(result.is_err());
+        assert!(parse(json!({"value": value})).is_err());
+        assert!(parse(json!({"version": 1})).is_err());
+        assert!(parse(json!({"version": 1, "value": value, "extra": 1})).is_err());
+
+        // unknown nodes and fields are rejected
+        let err = parse(json!({"version": 1, "value": {"like": {}}}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown variant `like`"), "{err}");
+        let unknown_op = json!({"binary": {"op": "pow", "left": value, "right": value}});
+        assert!(parse(json!({"version": 1, "value": unknown_op})).is_err());
+        let unknown_field = json!({"cast": {"expr": value, "dataType": "long", "safe": true}});
+        assert!(parse(json!({"version": 1, "value": unknown_field})).is_err());
+
+        // literals are validated
+        let mismatched = json!({"array": {
+            "type": {"type": "array", "elementType": "integer", "containsNull": false},
+            "elements": [{"long": 1}]
+        }});
+        let literal = json!({"literal": mismatched});
+        assert!(parse(json!({"version": 1, "value": literal})).is_err());
+        let too_precise = json!({"decimal": {"value": "12345", "precision": 4, "scale": 0}});
+        let literal = json!({"literal": too_precise});
+        assert!(parse(json!({"version": 1, "value": literal})).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Resolves timestamps to table versions using the timestamps of the commits in the log.
+//!
+//! The timestamp of a commit is its in-commit timestamp (ICT) if in-commit timestamps were enabled
+//! when it was written, and the modification time of its commit file otherwise. File modification
+//! times are not guaranteed to increase with the version, so like Delta Spark we adjust each one
+//! to be at least one millisecond after the timestamp of the previous commit.
+//!
+//! See the [Protocol](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#in-commit-timestamps)
+//! for details on in-commit timestamps.
+
+use std::sync::{Arc, LazyLock};
+
+use crate::engine_data::{GetData, TypedGetData as _};
+use crate::log_segment::list_commit_files;
+use crate::path::ParsedLogPath;
+use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType, StructField, StructType};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, RowVisitor, Version};
+
+/// Resolves the range of timestamps `[start_timestamp, end_timestamp]` (in milliseconds since the
+/// Unix epoch) to a range of versions of the table as of `snapshot`:
+/// - The start version is the earliest version with a timestamp at or after `start_timestamp`.
+/// - The end version is the latest version with a timestamp at or before `end_timestamp`. If
+///   `end_timestamp` is `None`, this is the version of `snapshot`.
+///
+/// This fails if no commit falls in the range.
+pub(crate) fn resolve_timestamp_range(
+    engine: &dyn Engine,
+    snapshot: &Snapshot,
+    start_timestamp: i64,
+    end_timestamp: Option<i64>,
+) -> DeltaResult<(Version, Version)> {
+    if let Some(end_timestamp) = end_timestamp {
+        require!(
+            start_timestamp <= end_timestamp,
+            Error::generic(format!(
+                "Start timestamp {start_timestamp} is greater than end timestamp {end_timestamp}"
+            ))
+        );
+    }
+    let commits = CommitTimestamps::try_new(engine, snapshot)?;
+    let (Some(first), Some(last)) = (commits.commits.first(), commits.commits.last()) else {
+        return Err(Error::generic("No commits found in the log"));
+    };
+
+    // index of the first commit at or after the start timestamp
+    let start = commits.partition_point(|timestamp| timestamp < start_timestamp)?;
+    if start == commits.commits.len() {
+        return Err(Error::generic(format!(
+            "Timestamp {start_timestamp} is after the latest commit (version {}, timestamp {})",
+            last.version,
+            commits.timestamp_at(start - 1)?
+        )));
+    }
+    let Some(end_timestamp) = end_timestamp else {
+        return Ok((commits.commits[start].version, last.version));
+    };
+
+    // index of the first commit after the end timestamp
+    let end = commits.partition_point(|timestamp| timestamp <= end_timestamp)?;
+    if end == 0 {
+        return Err(Error::generic(format!(
+            "Timestamp {end_timestamp} is before the earliest available commit (version {}, timestamp {})",
+            first.version,
+            commits.timestamp_at(0)?
+        )));
+    }
+    require!(
+        start < end,
+        Error::generic(format!(
+            "No commits found between timestamps {start_timestamp} and {end_timestamp}"
+        ))
+    );
+    Ok((
+        commits.commits[start].version,
+        commits.commits[end - 1].version,
+    ))
+}
+
+/// The commits of a table along with a way to find their timestamps. The timestamps of commits
+/// written before in-commit timestamps were enabled are computed up front from the listing;
+/// in-commit timestamps are read from the commit files on demand.
+struct CommitTimestamps<'a> {
+    engine: &'a dyn Engine,
+    commits: Vec<ParsedLogPath>,
+    /// The (adjusted) file modification times of the commits before in-commit timestamps were
+    /// enabled. These are the first `file_timestamps.len()` entries of `commits`.
+    file_timestamps: Vec<i64>,
+}
+
+impl<'a> CommitTimestamps<'a> {
+    fn try_new(engine: &'a dyn Engine, snapshot: &Snapshot) -> DeltaResult<Self> {
+        let log_root = snapshot.log_segment().log_root.clone();
+        let commits = list_commit_files(
+            engine.storage_handler().as_ref(),
+            &log_root,
+            None,
+            snapshot.version(),
+        )?;
+        let ict_enablement_version = snapshot
+            .table_configuration()
+            .in_commit_timestamp_enablement()?
+            .map(|(version, _)| version);
+
+        let mut file_timestamps: Vec<i64> = vec![];
+        for commit in commits
+            .iter()
+            .take_while(|commit| !ict_enablement_version.is_some_and(|v| v <= commit.version))
+        {
+            let timestamp = match file_timestamps.last() {
+                Some(previous) => commit.location.last_modified.max(previous + 1),
+                None => commit.location.last_modified,
+            };
+            file_timestamps.push(timestamp);
+        }
+        Ok(Self {
+            engine,
+            commits,
+            file_timestamps,
+        })
+    }
+
+    /// The timestamp of the commit at `index`.
+    fn timestamp_at(&self, index: usize) -> DeltaResult<i64> {
+        if let Some(timestamp) = self.file_timestamps.get(index) {
+            return Ok(*timestamp);
+        }
+        let commit = &self.commits[index];
+        read_in_commit_timestamp(self.engine, commit)?.ok_or_else(|| {
+            Error::generic(format!(
+                "In-commit timestamps are enabled, but commit {} has no in-commit timestamp",
+                commit.version
+            ))
+        })
+    }
+
+    /// Returns the index of the first commit for which `pred` is false, assuming `pred` is true for
+    /// every commit before it (see [`slice::partition_point`]).
+    fn partition_point(&self, pred: impl Fn(i64) -> bool) -> DeltaResult<usize> {
+        let (mut low, mut high) = (0, self.commits.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(self.timestamp_at(mid)?) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+}
+
+/// Reads the in-commit timestamp from the `commitInfo` action of `commit`, if present.
+fn read_in_commit_timestamp(
+    engine: &dyn Engine,
+    commit: &ParsedLogPath,
+) -> DeltaResult<Option<i64>> {
+    static COMMIT_INFO_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
+        Arc::new(StructType::new([StructField::nullable(
+            "commitInfo",
+            StructType::new([StructField::nullable("inCommitTimestamp", DataType::LONG)]),
+        )]))
+    });
+    let batches = engine.json_handler().read_json_files(
+        &[commit.location.clone()],
+        COMMIT_INFO_SCHEMA.clone(),
+        None,
+    )?;
+    let mut visitor = InCommitTimestampVisitor::default();
+    for batch in batches {
+        visitor.visit_rows_of(batch?.as_ref())?;
+        if visitor.in_commit_timestamp.is_some() {
+            break;
+        }
+    }
+    Ok(visitor.in_commit_timestamp)
+}
+
+#[derive(Default)]
+struct InCommitTimestampVisitor {
+    in_commit_timestamp: Option<i64>,
+}
+
+impl RowVisitor for InCommitTimestampVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![ColumnName::new(["commitInfo", "inCommitTimestamp"])],
+                vec![DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        for i in 0..row_count {
+            if let Some(timestamp) = getters[0].get_opt(i, "commitInfo.inCommitTimestamp")? {
+                self.in_commit_timestamp = Some(timestamp);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::resolve_timestamp_range;
+    use crate::actions::{CommitInfo, Metadata, Protocol};
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::snapshot::Snapshot;
+    use crate::table_features::WriterFeature;
+    use crate::utils::test_utils::{in_memory_table, Action};
+
+    fn commit_info(in_commit_timestamp: i64) -> Action {
+        Action::CommitInfo(CommitInfo {
+            in_commit_timestamp: Some(in_commit_timestamp),
+            ..Default::default()
+        })
+    }
+
+    /// An hour from now, so that in-commit timestamps come after the modification times of the
+    /// commit files written by the test.
+    fn ict_base() -> i64 {
+        chrono::Utc::now().timestamp_millis() + 3_600_000
+    }
+
+    /// Writes a table with in-commit timestamps enabled at version 2, and commits with in-commit
+    /// timestamps `base + 100`, `base + 200` and `base + 300` at versions 2, 3 and 4.
+    fn setup_table(base: i64) -> (DefaultEngine<TokioBackgroundExecutor>, Snapshot) {
+        let protocol = Protocol::try_new(
+            1,
+            7,
+            None::<Vec<String>>,
+            Some([WriterFeature::InCommitTimestamp]),
+        )
+        .unwrap();
+        let metadata = |configuration: HashMap<String, String>| {
+            Action::Metadata(Metadata {
+                id: "test-table".into(),
+                schema_string: r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#.into(),
+                configuration,
+                ..Default::default()
+            })
+        };
+        let ict_configuration = HashMap::from([
+            ("delta.enableInCommitTimestamps".into(), "true".into()),
+            (
+                "delta.inCommitTimestampEnablementVersion".into(),
+                "2".into(),
+            ),
+            (
+                "delta.inCommitTimestampEnablementTimestamp".into(),
+                (base + 100).to_string(),
+            ),
+        ]);
+        let (_store, engine, table) = in_memory_table([
+            vec![Action::Protocol(protocol), metadata(HashMap::new())],
+            vec![],
+            vec![commit_info(base + 100), metadata(ict_configuration)],
+            vec![commit_info(base + 200)],
+            vec![commit_info(base + 300)],
+        ]);
+        let snapshot = table.snapshot(&engine, None).unwrap();
+        (engine, snapshot)
+    }
+
+    #[test]
+    fn test_resolve_in_commit_timestamps() {
+        let base = ict_base();
+        let (engine, snapshot) = setup_table(base);
+        let resolve = |start, end| resolve_timestamp_range(&engine, &snapshot, start, end);
+
+        assert_eq!(resolve(base + 100, Some(base + 300)).unwrap(), (2, 4));
+        assert_eq!(resolve(base + 101, Some(base + 299)).unwrap(), (3, 3));
+        assert_eq!(resolve(base + 150, None).unwrap(), (3, 4));
+        assert_eq!(resolve(base + 300, Some(base + 1000)).unwrap(), (4, 4));
+
+        // no commits between the timestamps
+        assert!(resolve(base + 201, Some(base + 299)).is_err());
+        // the start timestamp is after the latest commit
+        assert!(resolve(base + 301, None).is_err());
+        // the start timestamp is after the end timestamp
+        assert!(resolve(base + 200, Some(base + 100)).is_err());
+    }
+
+    #[test]
+    fn test_resolve_file_modification_times() {
+        let base = ict_base();
+        let (engine, snapshot) = setup_table(base);
+        let resolve = |start, end| resolve_timestamp_range(&engine, &snapshot, start, end);
+
+        // versions 0 and 1 predate in-commit timestamps, so their file modification times are used
+        assert_eq!(resolve(0, None).unwrap(), (0, 4));
+        assert_eq!(resolve(0, Some(base)).unwrap(), (0, 1));
+        assert_eq!(resolve(0, Some(base + 100)).unwrap(), (0, 2));
+        let err = resolve(0, Some(0)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("before the earliest available commit"));
+    }
+}
@@ -83,6 +83,14 @@ pub(crate) trait KernelPredicateEvaluator {
     /// becomes `<col> != <value>`.
     fn eval_pred_eq(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output>;
 
+    /// A (possibly inverted) string prefix match, e.g. `<col> STARTS_WITH <value>`.
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output>;
+
     /// A (possibly inverted) comparison between two scalars, e.g. `<valueA> != <valueB>`.
     fn eval_pred_binary_scalars(
         &self,
@@ -135,7 +143,9 @@ pub(crate) trait KernelPredicateEvaluator {
             Expr::Literal(val) => self.eval_pred_scalar(val, inverted),
             Expr::Column(col) => self.eval_pred_column(col, inverted),
             Expr::Predicate(pred) => self.eval_pred(pred, inverted),
-            Expr::Struct(_) | Expr::Binary(_) => None,
+            Expr::Struct(_) | Expr::Binary(_) | Expr::Cast(_) | Expr::Coalesce(_) | Expr::If(_) => {
+                None
+            }
         }
     }
 
@@ -155,7 +165,12 @@ pub(crate) trait KernelPredicateEvaluator {
                 // partition pruning encounters a non-partition column.
                 Expr::Literal(val) => self.eval_pred_scalar_is_null(val, inverted),
                 Expr::Column(col) => self.eval_pred_is_null(col, inverted),
-                Expr::Predicate(_) | Expr::Struct(_) | Expr::Binary(_) => {
+                Expr::Predicate(_)
+                | Expr::Struct(_)
+                | Expr::Binary(_)
+                | Expr::Cast(_)
+                | Expr::Coalesce(_)
+                | Expr::If(_) => {
                     debug!("Unsupported operand: IS [NOT] NULL: {expr:?}");
                     None
                 }
@@ -187,14 +202,22 @@ pub(crate) trait KernelPredicateEvaluator {
 
     /// A (possibly inverted) IN-list check, e.g. `<col> [NOT] IN <array-value>`.
     ///
-    /// Unsupported by default, but implementations can override it if they wish.
-    fn eval_pred_in(
-        &self,
-        _col: &ColumnName,
-        _val: &Scalar,
-        _inverted: bool,
-    ) -> Option<Self::Output> {
-        None // TODO?
+    /// By default, `<col> IN (v1, v2, ...)` is evaluated as `OR(<col> = v1, <col> = v2, ...)`,
+    /// and `<col> NOT IN (v1, v2, ...)` as `AND(<col> != v1, <col> != v2, ...)`. This gives the
+    /// expected SQL semantics: A NULL list element makes IN return NULL (rather than FALSE) when
+    /// no other element matches, and prevents NOT IN from ever returning TRUE. An empty list
+    /// makes IN return FALSE and NOT IN return TRUE.
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
+        let Scalar::Array(array) = val else {
+            debug!("Unsupported IN-list value: {val:?}");
+            return None;
+        };
+        #[allow(deprecated)]
+        let preds = array
+            .array_elements()
+            .iter()
+            .map(|val| self.eval_pred_eq(col, val, inverted));
+        self.finish_eval_pred_junction(JunctionPredicateOp::Or, preds, inverted)
     }
 
     /// Dispatches a (possibly inverted) binary expression to each operator's specific implementation.
@@ -232,6 +255,7 @@ pub(crate) trait KernelPredicateEvaluator {
             Distinct => self.eval_pred_distinct(col, val, inverted),
             In => self.eval_pred_in(col, val, inverted),
             NotIn => self.eval_pred_in(col, val, !inverted),
+            StartsWith => self.eval_pred_starts_with(col, val, inverted),
         }
     }
 
@@ -464,6 +488,14 @@ impl KernelPredicateEvaluatorDefaults {
         Some(matched != inverted)
     }
 
+    /// A (possibly inverted) string prefix match of two scalars. Non-string inputs produce NULL.
+    pub(crate) fn starts_with_scalars(a: &Scalar, b: &Scalar, inverted: bool) -> Option<bool> {
+        match (a, b) {
+            (Scalar::String(a), Scalar::String(b)) => Some(a.starts_with(b.as_str()) != inverted),
+            _ => None,
+        }
+    }
+
     /// Directly evaluates a boolean comparison. See [`KernelPredicateEvaluator::eval_pred_binary_scalars`].
     pub(crate) fn eval_pred_binary_scalars(
         op: BinaryPredicateOp,
@@ -479,6 +511,7 @@ impl KernelPredicateEvaluatorDefaults {
             LessThanOrEqual => Self::partial_cmp_scalars(Ordering::Greater, left, right, !inverted),
             GreaterThan => Self::partial_cmp_scalars(Ordering::Greater, left, right, inverted),
             GreaterThanOrEqual => Self::partial_cmp_scalars(Ordering::Less, left, right, !inverted),
+            StartsWith => Self::starts_with_scalars(left, right, inverted),
             Distinct | In | NotIn => {
                 debug!("Unsupported binary operator: {left:?} {op:?} {right:?}");
                 None
@@ -600,6 +633,16 @@ impl<R: ResolveColumnAsScalar> KernelPredicateEvaluator for DefaultKernelPredica
         self.eval_pred_binary_scalars(BinaryPredicateOp::Equal, &col, val, inverted)
     }
 
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        self.eval_pred_binary_scalars(BinaryPredicateOp::StartsWith, &col, val, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -632,6 +675,33 @@ impl<R: ResolveColumnAsScalar> KernelPredicateEvaluator for DefaultKernelPredica
     }
 }
 
+/// Writers truncate string statistics to a prefix of (by default) this many characters. A truncated
+/// min stat remains a lower bound, and writers adjust a truncated max stat to remain an upper bound,
+/// but neither is necessarily an actual column value.
+pub(crate) const STRING_STATS_PREFIX_LENGTH: usize = 32;
+
+/// Returns the smallest string greater than every string that starts with `prefix`, or `None` if
+/// no such string exists (e.g. if `prefix` is empty).
+fn string_prefix_successor(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(c) = chars.pop() {
+        // Skip over the surrogate code points, which are not valid chars
+        let next = match c {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
+/// Data skipping evaluates IN-lists with at most this many elements as an OR of per-element
+/// equality checks. Longer lists fall back to a (less selective, but much smaller) range check.
+const MAX_IN_LIST_EQUALITY_CHECKS: usize = 10;
+
 /// A predicate evaluator that implements data skipping semantics over various column stats. For
 /// example, comparisons involving a column are converted into comparisons over that column's
 /// min/max stats, and NULL checks are converted into comparisons involving the column's nullcount
@@ -787,6 +857,99 @@ pub(crate) trait DataSkippingPredicateEvaluator {
         };
         self.finish_eval_pred_junction(op, preds, false)
     }
+
+    /// See [`KernelPredicateEvaluator::eval_pred_starts_with`]
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        let Scalar::String(prefix) = val else {
+            debug!("Unsupported STARTS_WITH prefix: {val:?}");
+            return None;
+        };
+        if inverted {
+            // Given `NOT(col STARTS_WITH prefix)`:
+            // Skip if every value in [min, max] starts with the prefix, implies
+            // Skip if `min >= prefix AND max < successor(prefix)` implies
+            // Keep if `min < prefix OR max >= successor(prefix)`
+            //
+            // Truncated stats are safe here: A truncated min is a lower bound of the true min, and
+            // writers round a truncated max up to remain an upper bound of the true max.
+            //
+            // A prefix with no successor imposes no upper bound, so the max check is omitted.
+            let successor = string_prefix_successor(prefix).map(Scalar::from);
+            let preds = std::iter::once(self.partial_cmp_min_stat(col, val, Ordering::Less, false))
+                .chain(successor.map(|successor| {
+                    self.partial_cmp_max_stat(col, &successor, Ordering::Less, true)
+                }));
+            return self.finish_eval_pred_junction(JunctionPredicateOp::Or, preds, false);
+        }
+
+        // Given `col STARTS_WITH prefix`:
+        // Skip if no value in [min, max] starts with the prefix, implies
+        // Skip if `max < prefix OR min >= successor(prefix)` implies
+        // Keep if `max >= prefix AND min < successor(prefix)`
+        //
+        // A max stat truncated to fewer characters than the prefix could compare less than the
+        // prefix even though the true max starts with it. Matching only the leading characters
+        // that survive truncation is a weaker condition that avoids the problem.
+        let prefix: String = prefix.chars().take(STRING_STATS_PREFIX_LENGTH).collect();
+        let successor = string_prefix_successor(&prefix).map(Scalar::from);
+        let prefix = Scalar::from(prefix);
+        let preds = std::iter::once(self.partial_cmp_max_stat(col, &prefix, Ordering::Less, true))
+            .chain(successor.map(|successor| {
+                self.partial_cmp_min_stat(col, &successor, Ordering::Less, false)
+            }));
+        self.finish_eval_pred_junction(JunctionPredicateOp::And, preds, false)
+    }
+
+    /// See [`KernelPredicateEvaluator::eval_pred_in`]
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
+        let Scalar::Array(array) = val else {
+            debug!("Unsupported IN-list value: {val:?}");
+            return None;
+        };
+        #[allow(deprecated)]
+        let elements = array.array_elements();
+        if elements.len() <= MAX_IN_LIST_EQUALITY_CHECKS {
+            let preds = elements
+                .iter()
+                .map(|val| self.eval_pred_eq(col, val, inverted));
+            return self.finish_eval_pred_junction(JunctionPredicateOp::Or, preds, inverted);
+        }
+        if inverted {
+            // Given `col NOT IN (...)` with a long list:
+            // Skip only if min == max == some list element, which is rare and not worth the cost
+            // of checking every element. Keep the file.
+            return None;
+        }
+
+        // Given `col IN (...)` with a long list:
+        // Keep if the column's [min, max] range overlaps the list's range, i.e.
+        // Keep if `col >= min(list) AND col <= max(list)`
+        //
+        // NULL elements can never compare equal, so they cannot make the predicate TRUE.
+        let mut values = elements.iter().filter(|val| !val.is_null());
+        let first = values.next()?;
+        let (lo, hi) = values.try_fold((first, first), |(lo, hi), val| {
+            let lo = match val.partial_cmp(lo)? {
+                Ordering::Less => val,
+                _ => lo,
+            };
+            let hi = match val.partial_cmp(hi)? {
+                Ordering::Greater => val,
+                _ => hi,
+            };
+            Some((lo, hi))
+        })?;
+        let preds = [
+            self.eval_pred_lt(col, lo, true),
+            self.eval_pred_le(col, hi, false),
+        ];
+        self.finish_eval_pred_junction(JunctionPredicateOp::And, preds, false)
+    }
 }
 
 impl<T: DataSkippingPredicateEvaluator> KernelPredicateEvaluator for T {
@@ -816,6 +979,15 @@ impl<T: DataSkippingPredicateEvaluator> KernelPredicateEvaluator for T {
         self.eval_pred_eq(col, val, inverted)
     }
 
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        self.eval_pred_starts_with(col, val, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -836,6 +1008,10 @@ impl<T: DataSkippingPredicateEvaluator> KernelPredicateEvaluator for T {
         None // Unsupported
     }
 
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
+        self.eval_pred_in(col, val, inverted)
+    }
+
     fn finish_eval_pred_junction(
         &self,
         op: JunctionPredicateOp,
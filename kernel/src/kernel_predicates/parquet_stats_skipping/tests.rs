@@ -1,6 +1,7 @@
 use super::*;
-use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+use crate::expressions::{column_expr, ArrayData, Expression as Expr, Predicate as Pred};
 use crate::kernel_predicates::KernelPredicateEvaluator as _;
+use crate::schema::ArrayType;
 use crate::DataType;
 
 const TRUE: Option<bool> = Some(true);
@@ -196,6 +197,65 @@ fn test_eval_binary_comparisons() {
     do_test(FIVE, FIFTEEN, &[TRUE, TRUE, TRUE, TRUE, TRUE, TRUE]);
 }
 
+#[test]
+fn test_eval_in_list() {
+    let in_list = |values: &[i32]| {
+        let tpe = ArrayType::new(DataType::INTEGER, true);
+        Scalar::Array(ArrayData::try_new(tpe, values.iter().copied()).unwrap())
+    };
+    let short_list = in_list(&[5, 15]);
+    let long_list = in_list(&[5, 15, 17, 19, 21, 23, 25, 27, 29, 31, 33]);
+    let predicates = [
+        Pred::binary(BinaryPredicateOp::In, column_expr!("x"), short_list.clone()),
+        Pred::binary(BinaryPredicateOp::NotIn, column_expr!("x"), short_list),
+        Pred::binary(BinaryPredicateOp::In, column_expr!("x"), long_list.clone()),
+        Pred::binary(BinaryPredicateOp::NotIn, column_expr!("x"), long_list),
+    ];
+
+    let do_test = |min: i32, max: i32, expected: &[Option<bool>]| {
+        let filter = MinMaxTestFilter::new(Some(min.into()), Some(max.into()));
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            expect_eq!(filter.eval(pred), *expect, "{pred:#?} with [{min}..{max}]");
+        }
+    };
+
+    // Range below, between, and above the list values. Long lists only check the list's range.
+    do_test(1, 4, &[FALSE, TRUE, FALSE, NULL]);
+    do_test(8, 12, &[FALSE, TRUE, TRUE, NULL]);
+    do_test(40, 50, &[FALSE, TRUE, FALSE, NULL]);
+
+    // Range containing a list value
+    do_test(1, 10, &[TRUE, TRUE, TRUE, NULL]);
+
+    // Every row equals a list value
+    do_test(5, 5, &[TRUE, FALSE, TRUE, NULL]);
+}
+
+#[test]
+fn test_eval_starts_with() {
+    let predicates = [
+        Pred::starts_with(column_expr!("x"), Expr::literal("abc")),
+        Pred::not(Pred::starts_with(column_expr!("x"), Expr::literal("abc"))),
+    ];
+
+    let do_test = |min: &str, max: &str, expected: &[Option<bool>]| {
+        let filter = MinMaxTestFilter::new(Some(min.into()), Some(max.into()));
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            expect_eq!(filter.eval(pred), *expect, "{pred:#?} with [{min}..{max}]");
+        }
+    };
+
+    do_test("a", "abb", &[FALSE, TRUE]);
+    do_test("abd", "b", &[FALSE, TRUE]);
+    do_test("ab", "abd", &[TRUE, TRUE]);
+    do_test("abc", "abcz", &[TRUE, FALSE]);
+
+    // Non-string stats cannot be compared against the prefix
+    let filter = MinMaxTestFilter::new(Some(1.into()), Some(2.into()));
+    let pred = &predicates[0];
+    expect_eq!(filter.eval(pred), NULL, "{pred:#?} with [1..2]");
+}
+
 struct NullCountTestFilter {
     nullcount: Option<i64>,
     rowcount: i64,
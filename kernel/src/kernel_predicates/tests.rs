@@ -1,9 +1,9 @@
 use super::*;
 use crate::expressions::{
-    column_expr, column_name, column_pred, ArrayData, Expression as Expr, Predicate as Pred,
-    StructData,
+    column_expr, column_name, column_pred, ArrayData, Expression as Expr, MapData,
+    Predicate as Pred, StructData,
 };
-use crate::schema::ArrayType;
+use crate::schema::{ArrayType, MapType, StructField};
 use crate::DataType;
 
 use std::collections::HashMap;
@@ -57,6 +57,9 @@ fn test_default_partial_cmp_scalars() {
     use Ordering::*;
     use Scalar::*;
 
+    let long_field = StructField::nullable("x", DataType::LONG);
+    let long_array = ArrayType::new(DataType::LONG, false);
+    let long_map = MapType::new(DataType::LONG, DataType::LONG, false);
     let smaller_values = &[
         Integer(1),
         Long(1),
@@ -72,8 +75,9 @@ fn test_default_partial_cmp_scalars() {
         Binary(vec![1]),
         Scalar::decimal(1, 10, 10).unwrap(),
         Null(DataType::LONG),
-        Struct(StructData::try_new(vec![], vec![]).unwrap()),
-        Array(ArrayData::try_new(ArrayType::new(DataType::LONG, false), &[] as &[i64]).unwrap()),
+        Struct(StructData::try_new(vec![long_field.clone()], vec![Long(1)]).unwrap()),
+        Array(ArrayData::try_new(long_array.clone(), [1i64]).unwrap()),
+        Map(MapData::try_new(long_map.clone(), [(1i64, 1i64)]).unwrap()),
     ];
     let larger_values = &[
         Integer(10),
@@ -90,8 +94,9 @@ fn test_default_partial_cmp_scalars() {
         Binary(vec![10]),
         Scalar::decimal(10, 10, 10).unwrap(),
         Null(DataType::LONG),
-        Struct(StructData::try_new(vec![], vec![]).unwrap()),
-        Array(ArrayData::try_new(ArrayType::new(DataType::LONG, false), &[] as &[i64]).unwrap()),
+        Struct(StructData::try_new(vec![long_field.clone()], vec![Long(10)]).unwrap()),
+        Array(ArrayData::try_new(long_array.clone(), [10i64]).unwrap()),
+        Map(MapData::try_new(long_map.clone(), [(10i64, 10i64)]).unwrap()),
     ];
 
     // scalars of different types are always incomparable
@@ -112,7 +117,7 @@ fn test_default_partial_cmp_scalars() {
     }
 
     let expect_if_comparable_type = |s: &_, expect| match s {
-        Null(_) | Struct(_) | Array(_) => None,
+        Null(_) => None,
         _ => Some(expect),
     };
 
@@ -460,6 +465,106 @@ fn test_eval_distinct() {
     );
 }
 
+#[test]
+fn test_eval_in() {
+    let in_list = |values: &[Scalar]| {
+        let tpe = ArrayType::new(DataType::INTEGER, true);
+        Scalar::Array(ArrayData::try_new(tpe, values.iter().cloned()).unwrap())
+    };
+    let one = Scalar::from(1);
+    let two = Scalar::from(2);
+    let null = Scalar::Null(DataType::INTEGER);
+    let col = &column_name!("x");
+
+    let test_cases = [
+        (
+            one.clone(),
+            in_list(&[one.clone(), two.clone()]),
+            Some(true),
+        ),
+        (one.clone(), in_list(&[two.clone()]), Some(false)),
+        (
+            one.clone(),
+            in_list(&[one.clone(), null.clone()]),
+            Some(true),
+        ),
+        (one.clone(), in_list(&[two.clone(), null.clone()]), None),
+        (one.clone(), in_list(&[]), Some(false)),
+        (null.clone(), in_list(&[one.clone(), two.clone()]), None),
+        (null.clone(), in_list(&[]), Some(false)),
+    ];
+    for (value, list, expect) in test_cases {
+        let filter = DefaultKernelPredicateEvaluator::from(value.clone());
+        expect_eq!(
+            filter.eval_pred_in(col, &list, false),
+            expect,
+            "x IN {list:?} (x = {value:?})"
+        );
+        expect_eq!(
+            filter.eval_pred_in(col, &list, true),
+            expect.map(|v| !v),
+            "x NOT IN {list:?} (x = {value:?})"
+        );
+    }
+
+    // Only array literals are valid IN-lists
+    let filter = DefaultKernelPredicateEvaluator::from(one.clone());
+    expect_eq!(filter.eval_pred_in(col, &one, false), None, "x IN 1");
+
+    // Routing through binary predicates, including NOT IN
+    let list = in_list(&[one.clone(), two.clone()]);
+    let pred = Pred::binary(BinaryPredicateOp::In, column_expr!("x"), list.clone());
+    expect_eq!(filter.eval(&pred), Some(true), "{pred:?}");
+    let pred = Pred::binary(BinaryPredicateOp::NotIn, column_expr!("x"), list);
+    expect_eq!(filter.eval(&pred), Some(false), "{pred:?}");
+}
+
+#[test]
+fn test_eval_starts_with() {
+    let col = &column_name!("x");
+    let test_cases = [
+        (Scalar::from("abc"), Scalar::from("ab"), Some(true)),
+        (Scalar::from("abc"), Scalar::from("abc"), Some(true)),
+        (Scalar::from("abc"), Scalar::from(""), Some(true)),
+        (Scalar::from("abc"), Scalar::from("abcd"), Some(false)),
+        (Scalar::from("abc"), Scalar::from("b"), Some(false)),
+        (Scalar::Null(DataType::STRING), Scalar::from("ab"), None),
+        (Scalar::from("abc"), Scalar::Null(DataType::STRING), None),
+        (Scalar::from(1), Scalar::from("1"), None),
+    ];
+    for (value, prefix, expect) in test_cases {
+        let filter = DefaultKernelPredicateEvaluator::from(value.clone());
+        expect_eq!(
+            filter.eval_pred_starts_with(col, &prefix, false),
+            expect,
+            "x STARTS_WITH {prefix:?} (x = {value:?})"
+        );
+        expect_eq!(
+            filter.eval_pred_starts_with(col, &prefix, true),
+            expect.map(|v| !v),
+            "NOT(x STARTS_WITH {prefix:?}) (x = {value:?})"
+        );
+    }
+}
+
+#[test]
+fn test_string_prefix_successor() {
+    let test_cases = [
+        ("", None),
+        ("abc", Some("abd")),
+        ("ab\u{10FFFF}", Some("ac")),
+        ("\u{10FFFF}\u{10FFFF}", None),
+        ("a\u{D7FF}", Some("a\u{E000}")),
+    ];
+    for (prefix, expect) in test_cases {
+        assert_eq!(
+            string_prefix_successor(prefix).as_deref(),
+            expect,
+            "prefix: {prefix:?}"
+        );
+    }
+}
+
 // NOTE: We're testing routing here -- the actual comparisons are already validated by
 // test_eval_binary_scalars.
 #[test]
@@ -78,6 +78,7 @@ pub mod checkpoint;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
+pub mod log_compaction;
 pub mod scan;
 pub mod schema;
 pub mod snapshot;
@@ -92,6 +93,8 @@ mod arrow_compat;
 #[cfg(any(feature = "arrow-54", feature = "arrow-55"))]
 pub use arrow_compat::*;
 
+pub(crate) mod crc;
+pub(crate) mod history_manager;
 pub(crate) mod kernel_predicates;
 pub(crate) mod utils;
 
@@ -537,6 +540,12 @@ pub trait ParquetHandler: AsAny {
     /// the columns requested by physical schema . The ParquetHandler _must_ return exactly the
     /// columns specified in `physical_schema`, and they _must_ be in schema order.
     ///
+    /// `physical_schema` may also contain metadata columns (see
+    /// [`schema::StructField::create_metadata_column`]), which are not read from the file but
+    /// populated by the handler. A [`schema::MetadataColumnSpec::RowIndex`] column _must_ contain
+    /// the 0-based physical position of each row within its file, even when the handler skips
+    /// some of the file's rows (e.g. row groups pruned by `predicate`).
+    ///
     /// # Parameters
     ///
     /// - `files` - File metadata for files to be read.
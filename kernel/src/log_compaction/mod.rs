@@ -0,0 +1,230 @@
+//! This module implements the API for writing log compaction files.
+//!
+//! A log compaction file `<lo>.<hi>.compacted.json` contains the reconciled actions of all the
+//! commits in the (inclusive) version range `[lo, hi]`. Readers may replay the compaction file in
+//! place of the individual commits it covers, which greatly reduces the number of small JSON files
+//! read between checkpoints for tables that commit frequently.
+//!
+//! The entry-points for this API are:
+//! 1. [`Snapshot::log_compaction_writer`]
+//! 2. [`Table::log_compaction_writer`]
+//!
+//! ## Compaction Contents
+//!
+//! The actions are reconciled with the same deduplication rules as checkpoint log replay (see
+//! [`crate::checkpoint`]), with one difference: _all_ remove actions (tombstones) are retained,
+//! regardless of their deletion timestamp. A compaction file is only a replacement for a range of
+//! commits and is always replayed on top of an older checkpoint or commits, so the removes are
+//! needed to cancel out any older add actions for the same files. This means the compaction
+//! contains:
+//! - the newest protocol and metadata actions in the range (if any)
+//! - the newest `txn` action for each app ID in the range
+//! - the newest add or remove action for each (path, dvId) pair in the range
+//!
+//! `commitInfo` and `cdc` actions are not included.
+//!
+//! ## Usage
+//!
+//! 1. Create a [`LogCompactionWriter`] using [`Snapshot::log_compaction_writer`] or
+//!    [`Table::log_compaction_writer`]
+//! 2. Get the compaction file path from [`LogCompactionWriter::compaction_path`]
+//! 3. Get the compaction data from [`LogCompactionWriter::compaction_data`]
+//! 4. Write the selected rows of the data to the path as JSON (engine-specific), without
+//!    overwriting any existing file
+//!
+//! ```no_run
+//! # use delta_kernel::log_compaction::LogCompactionDataIterator;
+//! # use delta_kernel::Engine;
+//! # use delta_kernel::table::Table;
+//! # use delta_kernel::DeltaResult;
+//! # use delta_kernel::Error;
+//! # use url::Url;
+//! fn write_compaction_file(path: Url, data: LogCompactionDataIterator) -> DeltaResult<()> {
+//!     todo!() /* engine-specific logic to write data to object storage*/
+//! }
+//!
+//! let engine: &dyn Engine = todo!(); /* create engine instance */
+//!
+//! // Compact commits 10 through 19 (inclusive) of the table
+//! let table = Table::try_from_uri("./tests/data/app-txn-no-checkpoint")?;
+//! let writer = table.log_compaction_writer(engine, 10, 19)?;
+//!
+//! let compaction_path = writer.compaction_path()?;
+//! let compaction_data = writer.compaction_data(engine)?;
+//! write_compaction_file(compaction_path, compaction_data)?;
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! ## Note
+//! Tables that support the `domainMetadata` writer feature cannot be compacted yet, since
+//! `domainMetadata` actions are not reconciled by log replay.
+//!
+//! [`Table::log_compaction_writer`]: crate::table::Table::log_compaction_writer
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::{
+    schemas::GetStructField, Add, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME,
+    METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME, SIDECAR_NAME,
+};
+use crate::checkpoint::log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
+use crate::engine_data::FilteredEngineData;
+use crate::log_replay::LogReplayProcessor;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::{SchemaRef, StructType};
+use crate::snapshot::Snapshot;
+use crate::table_features::WriterFeature;
+use crate::{DeltaResult, Engine, Error, Version};
+
+use url::Url;
+
+#[cfg(test)]
+mod tests;
+
+/// Schema for extracting the reconciled actions from the commits being compacted. Commits never
+/// contain sidecar actions, but log segment replay requires the column whenever file actions are
+/// read (it is always null, and so is never written out).
+static COMPACTION_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([
+        Option::<Add>::get_struct_field(ADD_NAME),
+        Option::<Remove>::get_struct_field(REMOVE_NAME),
+        Option::<Metadata>::get_struct_field(METADATA_NAME),
+        Option::<Protocol>::get_struct_field(PROTOCOL_NAME),
+        Option::<SetTransaction>::get_struct_field(SET_TRANSACTION_NAME),
+        Option::<Sidecar>::get_struct_field(SIDECAR_NAME),
+    ]))
+});
+
+/// An iterator over the log compaction data to be written to the file.
+///
+/// This iterator yields filtered batches ([`FilteredEngineData`]) of reconciled actions. Only the
+/// selected rows of each batch must be written to the compaction file.
+pub struct LogCompactionDataIterator {
+    /// The nested iterator that yields batches with action counts
+    batch_iterator: Box<dyn Iterator<Item = DeltaResult<CheckpointBatch>>>,
+    /// Running total of actions selected for the compaction file
+    actions_count: i64,
+    /// Running total of add actions selected for the compaction file
+    add_actions_count: i64,
+}
+
+impl LogCompactionDataIterator {
+    /// The number of actions selected so far. Only final once the iterator is exhausted.
+    pub fn actions_count(&self) -> i64 {
+        self.actions_count
+    }
+
+    /// The number of add actions selected so far. Only final once the iterator is exhausted.
+    pub fn add_actions_count(&self) -> i64 {
+        self.add_actions_count
+    }
+}
+
+impl Iterator for LogCompactionDataIterator {
+    type Item = DeltaResult<FilteredEngineData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.batch_iterator.next()?.map(|batch| {
+            self.actions_count += batch.actions_count;
+            self.add_actions_count += batch.add_actions_count;
+            batch.filtered_data
+        }))
+    }
+}
+
+/// Orchestrates the process of creating a log compaction file for a range of commits.
+///
+/// # See Also
+/// See the [module-level documentation](self) for the complete log compaction workflow
+#[derive(Debug)]
+pub struct LogCompactionWriter {
+    /// Reference to a snapshot of the table at (or after) the end of the compacted range
+    snapshot: Arc<Snapshot>,
+    /// The first version to compact (inclusive)
+    start_version: Version,
+    /// The last version to compact (inclusive)
+    end_version: Version,
+}
+
+impl LogCompactionWriter {
+    /// Creates a new [`LogCompactionWriter`] for the commits in `[start_version, end_version]`.
+    ///
+    /// Fails if the range is empty or covers a single commit, if `end_version` is newer than the
+    /// snapshot, or if the table supports the `domainMetadata` writer feature.
+    pub(crate) fn try_new(
+        snapshot: Arc<Snapshot>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Self> {
+        if start_version >= end_version {
+            return Err(Error::log_compaction_write(format!(
+                "Invalid version range [{start_version}, {end_version}]: the start version must be \
+                 less than the end version"
+            )));
+        }
+        if end_version > snapshot.version() {
+            return Err(Error::log_compaction_write(format!(
+                "End version {end_version} is newer than the snapshot version {}",
+                snapshot.version()
+            )));
+        }
+        if snapshot
+            .protocol()
+            .has_writer_feature(&WriterFeature::DomainMetadata)
+        {
+            return Err(Error::unsupported(
+                "Log compaction is not supported for tables with the domainMetadata feature",
+            ));
+        }
+        Ok(Self {
+            snapshot,
+            start_version,
+            end_version,
+        })
+    }
+
+    /// Returns the URL where the log compaction file should be written, following the Delta log
+    /// compaction naming convention (where both versions are zero-padded to 20 digits):
+    ///
+    /// `<table_root>/_delta_log/<start_version>.<end_version>.compacted.json`
+    pub fn compaction_path(&self) -> DeltaResult<Url> {
+        ParsedLogPath::new_log_compaction(
+            self.snapshot.table_root(),
+            self.start_version,
+            self.end_version,
+        )
+        .map(|parsed| parsed.location)
+    }
+
+    /// Returns the reconciled actions to be written to the log compaction file.
+    ///
+    /// # Parameters
+    /// - `engine`: Implementation of [`Engine`] APIs.
+    pub fn compaction_data(&self, engine: &dyn Engine) -> DeltaResult<LogCompactionDataIterator> {
+        // Read exactly the commits in the range. Reading existing compaction files is not worth the
+        // trouble, since they may not align with the requested range.
+        let log_segment = LogSegment::for_table_changes(
+            engine.storage_handler().as_ref(),
+            self.snapshot.log_segment().log_root.clone(),
+            self.start_version,
+            self.end_version,
+        )?;
+        let actions = log_segment.read_actions(
+            engine,
+            COMPACTION_ACTIONS_SCHEMA.clone(),
+            COMPACTION_ACTIONS_SCHEMA.clone(),
+            None,
+        )?;
+
+        // Every tombstone is younger than i64::MIN, so no remove is ever considered expired, and no
+        // txn expiration timestamp is given, so no txn action is considered expired either
+        let batch_iterator =
+            CheckpointLogReplayProcessor::new(i64::MIN, None).process_actions_iter(actions);
+
+        Ok(LogCompactionDataIterator {
+            batch_iterator: Box::new(batch_iterator),
+            actions_count: 0,
+            add_actions_count: 0,
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::actions::{Protocol, Remove};
+use crate::arrow::array::{BooleanArray, RecordBatch};
+use crate::arrow::compute::filter_record_batch;
+use crate::engine::arrow_data::ArrowEngineData;
+use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
+use crate::utils::test_utils::{
+    create_add_action, create_basic_protocol_action, create_metadata_action, create_txn_action,
+    in_memory_table, Action,
+};
+use crate::{DeltaResult, Engine, EngineData, Error, Table};
+
+use itertools::Itertools;
+use serde_json::Value;
+use url::Url;
+
+/// Builds a table with four commits:
+/// - 0: protocol, metadata, add `a`
+/// - 1: add `b`, txn `app` v1
+/// - 2: remove `a`, add `b` (rewritten), txn `app` v2
+/// - 3: add `c`
+fn setup_table() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Table) {
+    // An ancient tombstone, which a checkpoint would drop as expired
+    let remove_a = Action::Remove(Remove {
+        path: "a".into(),
+        data_change: true,
+        deletion_timestamp: Some(1),
+        ..Default::default()
+    });
+    in_memory_table([
+        vec![
+            create_basic_protocol_action(),
+            create_metadata_action(),
+            create_add_action("a"),
+        ],
+        vec![create_add_action("b"), create_txn_action("app", 1)],
+        vec![
+            remove_a,
+            create_add_action("b"),
+            create_txn_action("app", 2),
+        ],
+        vec![create_add_action("c")],
+    ])
+}
+
+fn into_selected_record_batch(
+    data: Box<dyn EngineData>,
+    selection_vector: Vec<bool>,
+) -> RecordBatch {
+    let batch = ArrowEngineData::try_from_engine_data(data).unwrap().into();
+    filter_record_batch(&batch, &BooleanArray::from(selection_vector)).unwrap()
+}
+
+fn read_json_lines(store: &Arc<InMemory>, path: &str) -> Vec<Value> {
+    let bytes = futures::executor::block_on(async {
+        store
+            .get(&Path::from(path))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap()
+    });
+    serde_json::Deserializer::from_slice(&bytes)
+        .into_iter::<Value>()
+        .try_collect()
+        .unwrap()
+}
+
+#[test]
+fn test_log_compaction_writer_rejects_invalid_ranges() -> DeltaResult<()> {
+    let (_store, engine, table) = setup_table();
+
+    // empty and single-commit ranges
+    for (start, end) in [(2, 1), (2, 2)] {
+        let snapshot = Arc::new(table.snapshot(&engine, None)?);
+        let err = snapshot.log_compaction_writer(start, end).unwrap_err();
+        assert!(matches!(err, Error::LogCompactionWrite(_)), "{err}");
+    }
+
+    // range ends after the snapshot
+    let snapshot = Arc::new(table.snapshot(&engine, Some(2))?);
+    let err = snapshot.log_compaction_writer(1, 3).unwrap_err();
+    assert!(matches!(err, Error::LogCompactionWrite(_)), "{err}");
+    Ok(())
+}
+
+#[test]
+fn test_log_compaction_writer_rejects_domain_metadata_tables() -> DeltaResult<()> {
+    let protocol =
+        Protocol::try_new(3, 7, Some(Vec::<String>::new()), Some(["domainMetadata"])).unwrap();
+    let (_store, engine, table) = in_memory_table([
+        vec![Action::Protocol(protocol), create_metadata_action()],
+        vec![create_add_action("a")],
+    ]);
+
+    let err = table.log_compaction_writer(&engine, 0, 1).unwrap_err();
+    assert!(matches!(err, Error::Unsupported(_)), "{err}");
+    Ok(())
+}
+
+#[test]
+fn test_log_compaction_path() -> DeltaResult<()> {
+    let (_store, engine, table) = setup_table();
+    let writer = table.log_compaction_writer(&engine, 1, 3)?;
+    assert_eq!(
+        writer.compaction_path()?,
+        Url::parse(
+            "memory:///_delta_log/00000000000000000001.00000000000000000003.compacted.json"
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_log_compaction_data_is_equivalent_to_commits() -> DeltaResult<()> {
+    let (store, engine, table) = setup_table();
+
+    let writer = table.log_compaction_writer(&engine, 1, 2)?;
+    let mut data = writer.compaction_data(&engine)?;
+
+    // Commits are replayed newest first: all of commit 2 is kept (including the expired remove),
+    // while commit 1 is entirely superseded by it (and so is not returned at all).
+    let batch = data.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, true, true]);
+    let batches = vec![into_selected_record_batch(
+        batch.data,
+        batch.selection_vector,
+    )];
+    assert!(data.next().is_none());
+    assert_eq!(data.actions_count(), 3);
+    assert_eq!(data.add_actions_count(), 1);
+
+    // Write the compaction file the way an engine would
+    let compaction_path = writer.compaction_path()?;
+    engine.json_handler().write_json_file(
+        &compaction_path,
+        Box::new(
+            batches
+                .into_iter()
+                .map(|batch| Ok(Box::new(ArrowEngineData::new(batch)) as Box<dyn EngineData>)),
+        ),
+        false,
+    )?;
+    let actions = read_json_lines(
+        &store,
+        "_delta_log/00000000000000000001.00000000000000000002.compacted.json",
+    );
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0]["remove"]["path"], "a");
+    assert_eq!(actions[1]["add"]["path"], "b");
+    assert_eq!(actions[2]["txn"]["version"], 2);
+
+    // The compaction file is picked up by log replay, and gives the same results
+    let snapshot = Arc::new(table.snapshot(&engine, None)?);
+    assert_eq!(snapshot.log_segment().ascending_compaction_files.len(), 1);
+    assert_eq!(
+        snapshot.clone().get_app_id_version("app", &engine)?,
+        Some(2)
+    );
+    let mut paths = vec![];
+    for scan_metadata in snapshot.scan_builder().build()?.scan_metadata(&engine)? {
+        paths = scan_metadata?
+            .visit_scan_files(paths, |paths: &mut Vec<String>, path, _, _, _, _, _| {
+                paths.push(path.to_string())
+            })?;
+    }
+    paths.sort();
+    assert_eq!(paths, ["b", "c"]);
+    Ok(())
+}
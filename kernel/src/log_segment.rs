@@ -9,13 +9,14 @@ use crate::actions::{
     get_log_schema, Metadata, Protocol, ADD_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
     SIDECAR_NAME,
 };
+use crate::crc::{read_version_checksum_file, VersionChecksum};
 use crate::path::{LogPathFileType, ParsedLogPath};
 use crate::schema::SchemaRef;
 use crate::snapshot::LastCheckpointHint;
 use crate::utils::require;
 use crate::{
-    DeltaResult, Engine, EngineData, Error, Expression, ParquetHandler, Predicate, PredicateRef,
-    RowVisitor, StorageHandler, Version,
+    DeltaResult, Engine, EngineData, Error, Expression, FileMeta, ParquetHandler, Predicate,
+    PredicateRef, RowVisitor, StorageHandler, Version,
 };
 use delta_kernel_derive::internal_api;
 
@@ -26,14 +27,19 @@ use url::Url;
 #[cfg(test)]
 mod tests;
 
-/// A [`LogSegment`] represents a contiguous section of the log and is made of checkpoint files
-/// and commit files and guarantees the following:
+/// A [`LogSegment`] represents a contiguous section of the log and is made of checkpoint files,
+/// commit files and (optionally) log compaction files, and guarantees the following:
 ///     1. Commit file versions will not have any gaps between them.
 ///     2. If checkpoint(s) is/are present in the range, only commits with versions greater than the most
 ///        recent checkpoint version are retained. There will not be a gap between the checkpoint
 ///        version and the first commit version.
 ///     3. All checkpoint_parts must belong to the same checkpoint version, and must form a complete
 ///        version. Multi-part checkpoints must have all their parts.
+///     4. Every log compaction file covers a range of versions that lies entirely after the
+///        checkpoint version (if any) and at or before the end version. Compaction files are only
+///        an optimization: the commit files for the covered versions are always retained too.
+///     5. The version checksum file (if any) is the newest one at or before the end version, and
+///        every commit after it is in the segment (i.e. it is not older than the checkpoint).
 ///
 /// [`LogSegment`] is used in [`Snapshot`] when built with [`LogSegment::for_snapshot`], and
 /// and in `TableChanges` when built with [`LogSegment::for_table_changes`].
@@ -47,20 +53,57 @@ pub(crate) struct LogSegment {
     pub log_root: Url,
     /// Sorted commit files in the log segment (ascending)
     pub ascending_commit_files: Vec<ParsedLogPath>,
+    /// Sorted (by start version, ascending) log compaction files in the log segment
+    pub ascending_compaction_files: Vec<ParsedLogPath>,
     /// Checkpoint files in the log segment.
     pub checkpoint_parts: Vec<ParsedLogPath>,
+    /// The newest version checksum (`.crc`) file in the log segment.
+    pub latest_crc_file: Option<ParsedLogPath>,
+}
+
+/// The log files found by listing the `_delta_log` directory, grouped by kind. See
+/// [`list_log_files_with_version`] for the guarantees provided by each field.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[internal_api]
+pub(crate) struct ListedLogFiles {
+    /// Sorted commit files (ascending)
+    pub ascending_commit_files: Vec<ParsedLogPath>,
+    /// Log compaction files, sorted by start version (ascending)
+    pub ascending_compaction_files: Vec<ParsedLogPath>,
+    /// All the parts of a single complete checkpoint
+    pub checkpoint_parts: Vec<ParsedLogPath>,
+    /// The newest version checksum file that is not older than the checkpoint
+    pub latest_crc_file: Option<ParsedLogPath>,
+}
+
+impl ListedLogFiles {
+    /// Returns true if the listing found no commits, compactions or checkpoints. Version checksum
+    /// files alone are not considered, since they never advance the table version.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ascending_commit_files.is_empty()
+            && self.ascending_compaction_files.is_empty()
+            && self.checkpoint_parts.is_empty()
+    }
 }
 
 impl LogSegment {
     pub(crate) fn try_new(
-        mut ascending_commit_files: Vec<ParsedLogPath>,
-        checkpoint_parts: Vec<ParsedLogPath>,
+        listed_files: ListedLogFiles,
         log_root: Url,
         end_version: Option<Version>,
     ) -> DeltaResult<Self> {
+        let ListedLogFiles {
+            mut ascending_commit_files,
+            mut ascending_compaction_files,
+            checkpoint_parts,
+            latest_crc_file,
+        } = listed_files;
+
         // Commit file versions must be greater than the most recent checkpoint version if it exists
         let checkpoint_version = checkpoint_parts.first().map(|checkpoint_file| {
             ascending_commit_files.retain(|log_path| checkpoint_file.version < log_path.version);
+            ascending_compaction_files
+                .retain(|log_path| checkpoint_file.version < log_path.version);
             checkpoint_file.version
         });
 
@@ -105,12 +148,37 @@ impl LogSegment {
             );
         }
 
+        // Compaction files are only usable if they lie within the range of commits being replayed
+        ascending_compaction_files.retain(|log_path| match log_path.file_type {
+            LogPathFileType::CompactedCommit { hi } => {
+                log_path.version <= hi
+                    && hi <= effective_version
+                    && ascending_commit_files
+                        .first()
+                        .is_some_and(|first| first.version <= log_path.version)
+            }
+            _ => false,
+        });
+
+        // The checksum file is only usable if every commit after it is replayed
+        let first_replayed_version = match checkpoint_version {
+            Some(checkpoint_version) => checkpoint_version + 1,
+            None => ascending_commit_files
+                .first()
+                .map_or(effective_version + 1, |first| first.version),
+        };
+        let latest_crc_file = latest_crc_file.filter(|crc| {
+            first_replayed_version <= crc.version + 1 && crc.version <= effective_version
+        });
+
         Ok(LogSegment {
             end_version: effective_version,
             checkpoint_version,
             log_root,
             ascending_commit_files,
+            ascending_compaction_files,
             checkpoint_parts,
+            latest_crc_file,
         })
     }
 
@@ -133,27 +201,23 @@ impl LogSegment {
     ) -> DeltaResult<Self> {
         let time_travel_version = time_travel_version.into();
 
-        let (ascending_commit_files, checkpoint_parts) =
-            match (checkpoint_hint.into(), time_travel_version) {
-                (Some(cp), None) => list_log_files_with_checkpoint(&cp, storage, &log_root, None)?,
-                (Some(cp), Some(end_version)) if cp.version <= end_version => {
-                    list_log_files_with_checkpoint(&cp, storage, &log_root, Some(end_version))?
-                }
-                _ => list_log_files_with_version(storage, &log_root, None, time_travel_version)?,
-            };
+        let listed_files = match (checkpoint_hint.into(), time_travel_version) {
+            (Some(cp), None) => list_log_files_with_checkpoint(&cp, storage, &log_root, None)?,
+            (Some(cp), Some(end_version)) if cp.version <= end_version => {
+                list_log_files_with_checkpoint(&cp, storage, &log_root, Some(end_version))?
+            }
+            _ => list_log_files_with_version(storage, &log_root, None, time_travel_version)?,
+        };
 
-        LogSegment::try_new(
-            ascending_commit_files,
-            checkpoint_parts,
-            log_root,
-            time_travel_version,
-        )
+        LogSegment::try_new(listed_files, log_root, time_travel_version)
     }
 
     /// Constructs a [`LogSegment`] to be used for `TableChanges`. For a TableChanges between versions
     /// `start_version` and `end_version`: Its LogSegment is made of zero checkpoints and all commits
     /// between versions `start_version` (inclusive) and `end_version` (inclusive). If no `end_version`
     /// is specified it will be the most recent version by default.
+    ///
+    /// Log compaction files are never included: table changes must be computed per commit.
     #[internal_api]
     pub(crate) fn for_table_changes(
         storage: &dyn StorageHandler,
@@ -188,14 +252,19 @@ impl LogSegment {
                 start_version
             ))
         );
-        LogSegment::try_new(ascending_commit_files, vec![], log_root, end_version)
+        let listed_files = ListedLogFiles {
+            ascending_commit_files,
+            ..Default::default()
+        };
+        LogSegment::try_new(listed_files, log_root, end_version)
     }
 
     /// Read a stream of actions from this log segment. This returns an iterator of (EngineData,
     /// bool) pairs, where the boolean flag indicates whether the data was read from a commit file
     /// (true) or a checkpoint file (false).
     ///
-    /// The log files will be read from most recent to oldest.
+    /// The log files will be read from most recent to oldest. Where available, log compaction
+    /// files are read in place of the commits they cover (see [`Self::find_commit_cover`]).
     ///
     /// `commit_read_schema` is the (physical) schema to read the commit files with, and
     /// `checkpoint_read_schema` is the (physical) schema to read checkpoint files with. This can be
@@ -211,14 +280,8 @@ impl LogSegment {
         checkpoint_read_schema: SchemaRef,
         meta_predicate: Option<PredicateRef>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<(Box<dyn EngineData>, bool)>> + Send> {
-        // `replay` expects commit files to be sorted in descending order, so we reverse the sorted
-        // commit files
-        let commit_files: Vec<_> = self
-            .ascending_commit_files
-            .iter()
-            .rev()
-            .map(|f| f.location.clone())
-            .collect();
+        // `replay` expects commit files to be sorted in descending order
+        let commit_files = self.find_commit_cover();
         let commit_stream = engine
             .json_handler()
             .read_json_files(&commit_files, commit_read_schema, meta_predicate.clone())?
@@ -230,6 +293,45 @@ impl LogSegment {
         Ok(commit_stream.chain(checkpoint_stream))
     }
 
+    /// Selects the set of commit and log compaction files that together cover every version after
+    /// the checkpoint (if any), preferring compaction files because they replace several commits
+    /// with a single (smaller) file. Versions not covered by any compaction file fall back to
+    /// their individual commit files. The result is sorted in descending order of version, as
+    /// expected by log replay.
+    ///
+    /// The selection is greedy: walking the files in ascending order of start version, a file is
+    /// skipped if its start version is already covered by the previously selected compaction file,
+    /// and among files with the same start version the one covering the widest range wins.
+    pub(crate) fn find_commit_cover(&self) -> Vec<FileMeta> {
+        // Merge into a single sequence sorted by (start version, end version), e.g.
+        // [00.json, 00.09.compacted.json, 00.19.compacted.json, 01.json, ..., 20.json]
+        let all_files = self
+            .ascending_commit_files
+            .iter()
+            .merge_by(&self.ascending_compaction_files, |a, b| {
+                (a.version, end_version_of(a)) <= (b.version, end_version_of(b))
+            });
+
+        let mut selected_files: Vec<&ParsedLogPath> = vec![];
+        for next in all_files {
+            match selected_files.last() {
+                // Resolve version ties in favor of the later file (it covers a wider range)
+                Some(prev) if prev.version == next.version => {
+                    selected_files.pop();
+                }
+                // Skip files whose start version is already covered by the previous selection
+                Some(prev) if next.version <= end_version_of(prev) => continue,
+                _ => {}
+            }
+            selected_files.push(next);
+        }
+        selected_files
+            .into_iter()
+            .rev()
+            .map(|f| f.location.clone())
+            .collect()
+    }
+
     /// Returns an iterator over checkpoint data, processing sidecar files when necessary.
     ///
     /// By default, `create_checkpoint_stream` checks for the presence of sidecar files, and
@@ -367,12 +469,19 @@ impl LogSegment {
     }
 
     // Do a lightweight protocol+metadata log replay to find the latest Protocol and Metadata in
-    // the LogSegment
+    // the LogSegment. If the LogSegment has a version checksum file, only the commits after it are
+    // replayed, and the checksum provides the protocol and metadata that no newer commit changed.
     pub(crate) fn protocol_and_metadata(
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<(Option<Metadata>, Option<Protocol>)> {
-        let data_batches = self.replay_for_metadata(engine)?;
+        let checksum = self.read_latest_checksum(engine);
+        let data_batches = match &checksum {
+            Some((crc_version, _)) => self
+                .commits_after(*crc_version)
+                .replay_for_metadata(engine)?,
+            None => self.replay_for_metadata(engine)?,
+        };
         let (mut metadata_opt, mut protocol_opt) = (None, None);
         for batch in data_batches {
             let (batch, _) = batch?;
@@ -387,9 +496,54 @@ impl LogSegment {
                 break;
             }
         }
+        if let Some((_, checksum)) = checksum {
+            metadata_opt.get_or_insert(checksum.metadata);
+            protocol_opt.get_or_insert(checksum.protocol);
+        }
         Ok((metadata_opt, protocol_opt))
     }
 
+    /// Read the latest version checksum file of this log segment, if any, along with its version.
+    /// The checksum is only an optimization, so any failure to read it is ignored.
+    fn read_latest_checksum(&self, engine: &dyn Engine) -> Option<(Version, VersionChecksum)> {
+        let crc_file = self.latest_crc_file.as_ref()?;
+        let storage = engine.storage_handler();
+        read_version_checksum_file(storage.as_ref(), &crc_file.location.location)
+            .inspect_err(|e| {
+                warn!(
+                    "Failed to read checksum file for version {}: {e}",
+                    crc_file.version
+                )
+            })
+            .ok()
+            .flatten()
+            .map(|checksum| (crc_file.version, checksum))
+    }
+
+    /// The suffix of this log segment made of the commits (and log compactions) after `version`.
+    fn commits_after(&self, version: Version) -> LogSegment {
+        let after = |log_path: &&ParsedLogPath| version < log_path.version;
+        LogSegment {
+            end_version: self.end_version,
+            checkpoint_version: None,
+            log_root: self.log_root.clone(),
+            ascending_commit_files: self
+                .ascending_commit_files
+                .iter()
+                .filter(after)
+                .cloned()
+                .collect(),
+            ascending_compaction_files: self
+                .ascending_compaction_files
+                .iter()
+                .filter(after)
+                .cloned()
+                .collect(),
+            checkpoint_parts: vec![],
+            latest_crc_file: None,
+        }
+    }
+
     // Get the most up-to-date Protocol and Metadata actions
     pub(crate) fn read_metadata(&self, engine: &dyn Engine) -> DeltaResult<(Metadata, Protocol)> {
         match self.protocol_and_metadata(engine)? {
@@ -438,7 +592,8 @@ fn list_log_files(
     Ok(storage
         .list_from(&start_from)?
         .map(|meta| ParsedLogPath::try_from(meta?))
-        // TODO this filters out .crc files etc which start with "." - how do we want to use these kind of files?
+        // NB: this filters out hidden files such as the `.<name>.crc` files written by Hadoop file
+        // systems, which are not version checksum files and are not part of the log.
         .filter_map_ok(identity)
         .take_while(move |path_res| match path_res {
             Ok(path) => !end_version.is_some_and(|end_version| end_version < path.version),
@@ -446,11 +601,35 @@ fn list_log_files(
         }))
 }
 
-/// List all commit and checkpoint files with versions above the provided `start_version` (inclusive).
-/// If successful, this returns a tuple `(ascending_commit_files, checkpoint_parts)` of type
-/// `(Vec<ParsedLogPath>, Vec<ParsedLogPath>)`. The commit files are guaranteed to be sorted in
-/// ascending order by version. The elements of `checkpoint_parts` are all the parts of the same
-/// checkpoint. Checkpoint parts share the same version.
+/// Lists all commit files with versions between `start_version` and `end_version` (inclusive), in
+/// ascending order. If no `start_version` is given, listing starts from version 0, and if no
+/// `end_version` is given, all commits from `start_version` onwards are listed.
+pub(crate) fn list_commit_files(
+    storage: &dyn StorageHandler,
+    log_root: &Url,
+    start_version: impl Into<Option<Version>>,
+    end_version: impl Into<Option<Version>>,
+) -> DeltaResult<Vec<ParsedLogPath>> {
+    list_log_files(storage, log_root, start_version, end_version)?
+        .filter_ok(|path| path.is_commit())
+        .try_collect()
+}
+
+/// Returns the last version covered by a log file: the `hi` version of a log compaction file, or
+/// the file's own version otherwise.
+fn end_version_of(path: &ParsedLogPath) -> Version {
+    match path.file_type {
+        LogPathFileType::CompactedCommit { hi } => hi,
+        _ => path.version,
+    }
+}
+
+/// List all commit, log compaction and checkpoint files with versions above the provided
+/// `start_version` (inclusive). If successful, this returns a [`ListedLogFiles`]. The commit files
+/// are guaranteed to be sorted in ascending order by version, and the compaction files in
+/// ascending order by start version. Neither includes files at or before the returned checkpoint.
+/// The elements of `checkpoint_parts` are all the parts of the same checkpoint. Checkpoint parts
+/// share the same version.
 // TODO: encode some of these guarantees in the output types. e.g. we could have:
 // - SortedCommitFiles: Vec<ParsedLogPath>, is_ascending: bool, end_version: Version
 // - CheckpointParts: Vec<ParsedLogPath>, checkpoint_version: Version (guarantee all same version)
@@ -459,7 +638,7 @@ pub(crate) fn list_log_files_with_version(
     log_root: &Url,
     start_version: Option<Version>,
     end_version: Option<Version>,
-) -> DeltaResult<(Vec<ParsedLogPath>, Vec<ParsedLogPath>)> {
+) -> DeltaResult<ListedLogFiles> {
     // We expect 10 commit files per checkpoint, so start with that size. We could adjust this based
     // on config at some point
 
@@ -467,7 +646,9 @@ pub(crate) fn list_log_files_with_version(
 
     log_files.process_results(|iter| {
         let mut commit_files = Vec::with_capacity(10);
+        let mut compaction_files = vec![];
         let mut checkpoint_parts = vec![];
+        let mut latest_crc_file: Option<ParsedLogPath> = None;
 
         // Group log files by version
         let log_files_per_version = iter.chunk_by(|x| x.version);
@@ -477,8 +658,12 @@ pub(crate) fn list_log_files_with_version(
             for file in files {
                 if file.is_commit() {
                     commit_files.push(file);
+                } else if file.is_compaction() {
+                    compaction_files.push(file);
                 } else if file.is_checkpoint() {
                     new_checkpoint_parts.push(file);
+                } else if file.file_type == LogPathFileType::Crc {
+                    latest_crc_file = Some(file);
                 } else {
                     warn!(
                         "Found a file with unknown file type {:?} at version {}",
@@ -495,13 +680,66 @@ pub(crate) fn list_log_files_with_version(
                 .find(|(num_parts, part_files)| part_files.len() == *num_parts as usize)
             {
                 checkpoint_parts = complete_checkpoint;
-                commit_files.clear(); // Log replay only uses commits after a complete checkpoint
+                // Log replay only uses commits and compactions after a complete checkpoint (and
+                // a checksum file at the checkpoint version or later)
+                commit_files.clear();
+                compaction_files.clear();
+                latest_crc_file.take_if(|crc| crc.version < version);
             }
         }
-        (commit_files, checkpoint_parts)
+        // Compaction files with the same start version list in lexicographic (= numeric) order
+        // of their end version, so this is a no-op sort in practice; it just keeps us honest.
+        compaction_files.sort_by_key(|f| (f.version, end_version_of(f)));
+        ListedLogFiles {
+            ascending_commit_files: commit_files,
+            ascending_compaction_files: compaction_files,
+            checkpoint_parts,
+            latest_crc_file,
+        }
     })
 }
 
+/// Lists the log files at or before `end_version` that have expired, i.e. that are no longer
+/// needed to reconstruct any version of the table that was committed after `cutoff_timestamp`
+/// (in milliseconds since the Unix epoch).
+///
+/// The newest complete checkpoint that was written at or before the cutoff is the cleanup
+/// boundary: every version after it can be reconstructed from it. A log file is expired if it
+/// only covers versions before the boundary, and if it was itself written at or before the
+/// cutoff. If there is no such checkpoint, nothing has expired.
+pub(crate) fn list_expired_log_files(
+    storage: &dyn StorageHandler,
+    log_root: &Url,
+    end_version: Version,
+    cutoff_timestamp: i64,
+) -> DeltaResult<Vec<ParsedLogPath>> {
+    let log_files: Vec<_> = list_log_files(storage, log_root, None, end_version)?
+        .filter_ok(|log_path| {
+            log_path.file_type != LogPathFileType::Unknown
+                && log_path.location.last_modified <= cutoff_timestamp
+        })
+        .try_collect()?;
+
+    let mut cleanup_boundary = None;
+    for (version, files) in &log_files.iter().chunk_by(|log_path| log_path.version) {
+        let checkpoint_parts = files.filter(|log_path| log_path.is_checkpoint()).cloned();
+        if group_checkpoint_parts(checkpoint_parts.collect())
+            .into_iter()
+            // `num_parts` is guaranteed to be non-negative and within `usize` range
+            .any(|(num_parts, part_files)| part_files.len() == num_parts as usize)
+        {
+            cleanup_boundary = Some(version);
+        }
+    }
+    let Some(cleanup_boundary) = cleanup_boundary else {
+        return Ok(vec![]);
+    };
+    Ok(log_files
+        .into_iter()
+        .filter(|log_path| end_version_of(log_path) < cleanup_boundary)
+        .collect())
+}
+
 /// Groups all checkpoint parts according to the checkpoint they belong to.
 ///
 /// NOTE: There could be a single-part and/or any number of uuid-based checkpoints. They
@@ -548,27 +786,31 @@ fn group_checkpoint_parts(parts: Vec<ParsedLogPath>) -> HashMap<u32, Vec<ParsedL
     checkpoints
 }
 
-/// List all commit and checkpoint files after the provided checkpoint. It is guaranteed that all
-/// the returned [`ParsedLogPath`]s will have a version less than or equal to the `end_version`.
-/// See [`list_log_files_with_version`] for details on the return type.
+/// List all commit, compaction and checkpoint files after the provided checkpoint. It is guaranteed
+/// that all the returned [`ParsedLogPath`]s will have a version less than or equal to the
+/// `end_version`. See [`list_log_files_with_version`] for details on the return type.
 fn list_log_files_with_checkpoint(
     checkpoint_metadata: &LastCheckpointHint,
     storage: &dyn StorageHandler,
     log_root: &Url,
     end_version: Option<Version>,
-) -> DeltaResult<(Vec<ParsedLogPath>, Vec<ParsedLogPath>)> {
-    let (commit_files, checkpoint_parts) = list_log_files_with_version(
+) -> DeltaResult<ListedLogFiles> {
+    let listed_files = list_log_files_with_version(
         storage,
         log_root,
         Some(checkpoint_metadata.version),
         end_version,
     )?;
+    let checkpoint_parts = &listed_files.checkpoint_parts;
 
     let Some(latest_checkpoint) = checkpoint_parts.last() else {
-        // TODO: We could potentially recover here
-        return Err(Error::invalid_checkpoint(
-            "Had a _last_checkpoint hint but didn't find any checkpoints",
-        ));
+        // The hinted checkpoint no longer exists (e.g. it was cleaned up after a newer checkpoint
+        // was written, before `_last_checkpoint` was updated), so list the whole log instead.
+        warn!(
+            "_last_checkpoint hint points to missing checkpoint version {}. Listing the whole log",
+            checkpoint_metadata.version
+        );
+        return list_log_files_with_version(storage, log_root, None, end_version);
     };
     if latest_checkpoint.version != checkpoint_metadata.version {
         warn!(
@@ -583,5 +825,5 @@ fn list_log_files_with_checkpoint(
             checkpoint_parts.len()
         )));
     }
-    Ok((commit_files, checkpoint_parts))
+    Ok(listed_files)
 }
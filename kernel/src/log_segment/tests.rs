@@ -16,9 +16,9 @@ use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
 use crate::engine::default::filesystem::ObjectStoreStorageHandler;
 use crate::engine::default::DefaultEngine;
 use crate::engine::sync::SyncEngine;
-use crate::log_segment::LogSegment;
+use crate::log_segment::{ListedLogFiles, LogSegment};
 use crate::parquet::arrow::ArrowWriter;
-use crate::path::ParsedLogPath;
+use crate::path::{LogPathFileType, ParsedLogPath};
 use crate::scan::test_utils::{
     add_batch_simple, add_batch_with_remove, sidecar_batch_with_given_paths,
 };
@@ -263,6 +263,44 @@ fn build_snapshot_with_uuid_checkpoint_json() {
     assert_eq!(versions, expected_versions);
 }
 
+#[test]
+fn build_snapshot_with_last_checkpoint_hint_to_missing_checkpoint() {
+    // The hinted checkpoint at version 5 was cleaned up (or never written), so the log is listed
+    // from the start, and the newest checkpoint found is used.
+    let checkpoint_metadata = LastCheckpointHint {
+        version: 5,
+        size: 10,
+        parts: None,
+        size_in_bytes: None,
+        num_of_add_files: None,
+        checkpoint_schema: None,
+        checksum: None,
+    };
+
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            delta_path_for_version(1, "json"),
+            delta_path_for_version(2, "checkpoint.parquet"),
+            delta_path_for_version(2, "json"),
+            delta_path_for_version(3, "json"),
+            delta_path_for_version(4, "json"),
+        ],
+        Some(&checkpoint_metadata),
+    );
+
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    let commit_files = log_segment.ascending_commit_files;
+    let checkpoint_parts = log_segment.checkpoint_parts;
+
+    assert_eq!(checkpoint_parts.len(), 1);
+    assert_eq!(checkpoint_parts[0].version, 2);
+
+    let versions = commit_files.into_iter().map(|x| x.version).collect_vec();
+    assert_eq!(versions, vec![3, 4]);
+}
+
 #[test]
 fn build_snapshot_with_correct_last_uuid_checkpoint() {
     let checkpoint_metadata = LastCheckpointHint {
@@ -422,7 +460,7 @@ fn build_snapshot_with_correct_last_multipart_checkpoint() {
 }
 
 #[test]
-fn build_snapshot_with_missing_checkpoint_part_from_hint_fails() {
+fn build_snapshot_with_missing_checkpoint_part_from_hint() {
     let checkpoint_metadata = LastCheckpointHint {
         version: 5,
         size: 10,
@@ -452,9 +490,17 @@ fn build_snapshot_with_missing_checkpoint_part_from_hint_fails() {
         Some(&checkpoint_metadata),
     );
 
+    // The incomplete checkpoint cannot be used, so the whole log is listed instead
     let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None);
-    assert!(log_segment.is_err())
+        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    assert_eq!(log_segment.checkpoint_parts.len(), 1);
+    assert_eq!(log_segment.checkpoint_parts[0].version, 3);
+    let versions = log_segment
+        .ascending_commit_files
+        .into_iter()
+        .map(|x| x.version)
+        .collect_vec();
+    assert_eq!(versions, vec![4, 5, 6, 7]);
 }
 #[test]
 fn build_snapshot_with_bad_checkpoint_hint_fails() {
@@ -962,8 +1008,10 @@ fn test_create_checkpoint_stream_errors_when_schema_has_remove_but_no_sidecar_ac
 
     // Create the stream over checkpoint batches.
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path("file:///00000000000000000001.parquet")],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path("file:///00000000000000000001.parquet")],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -987,8 +1035,10 @@ fn test_create_checkpoint_stream_errors_when_schema_has_add_but_no_sidecar_actio
 
     // Create the stream over checkpoint batches.
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path("file:///00000000000000000001.parquet")],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path("file:///00000000000000000001.parquet")],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1019,8 +1069,10 @@ fn test_create_checkpoint_stream_returns_checkpoint_batches_as_is_if_schema_has_
     let v2_checkpoint_read_schema = get_log_schema().project(&[METADATA_NAME])?;
 
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path(&checkpoint_one_file)],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path(&checkpoint_one_file)],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1071,11 +1123,13 @@ fn test_create_checkpoint_stream_returns_checkpoint_batches_if_checkpoint_is_mul
     let v2_checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
 
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![
-            create_log_path(&checkpoint_one_file),
-            create_log_path(&checkpoint_two_file),
-        ],
+        ListedLogFiles {
+            checkpoint_parts: vec![
+                create_log_path(&checkpoint_one_file),
+                create_log_path(&checkpoint_two_file),
+            ],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1118,8 +1172,10 @@ fn test_create_checkpoint_stream_reads_parquet_checkpoint_batch_without_sidecars
     let v2_checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
 
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path(&checkpoint_one_file)],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path(&checkpoint_one_file)],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1157,8 +1213,10 @@ fn test_create_checkpoint_stream_reads_json_checkpoint_batch_without_sidecars()
     let v2_checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
 
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path(&checkpoint_one_file)],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path(&checkpoint_one_file)],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1217,8 +1275,10 @@ fn test_create_checkpoint_stream_reads_checkpoint_file_and_returns_sidecar_batch
     let v2_checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
 
     let log_segment = LogSegment::try_new(
-        vec![],
-        vec![create_log_path(&checkpoint_file_path)],
+        ListedLogFiles {
+            checkpoint_parts: vec![create_log_path(&checkpoint_file_path)],
+            ..Default::default()
+        },
         log_root,
         None,
     )?;
@@ -1255,3 +1315,286 @@ fn test_create_checkpoint_stream_reads_checkpoint_file_and_returns_sidecar_batch
 
     Ok(())
 }
+
+fn compaction_path(lo: u64, hi: u64) -> Path {
+    Path::from(format!("_delta_log/{lo:020}.{hi:020}.compacted.json").as_str())
+}
+
+fn cover_versions(log_segment: &LogSegment) -> Vec<(u64, u64)> {
+    log_segment
+        .find_commit_cover()
+        .into_iter()
+        .map(|file| {
+            let path = ParsedLogPath::try_from(file).unwrap().unwrap();
+            match path.file_type {
+                LogPathFileType::CompactedCommit { hi } => (path.version, hi),
+                _ => (path.version, path.version),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn build_snapshot_with_compaction_files() {
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            compaction_path(0, 2),
+            delta_path_for_version(1, "json"),
+            delta_path_for_version(2, "json"),
+            delta_path_for_version(2, "checkpoint.parquet"),
+            delta_path_for_version(3, "json"),
+            compaction_path(3, 5),
+            compaction_path(3, 8),
+            delta_path_for_version(4, "json"),
+            delta_path_for_version(5, "json"),
+            delta_path_for_version(6, "json"),
+            compaction_path(6, 7),
+            delta_path_for_version(7, "json"),
+            delta_path_for_version(8, "json"),
+        ],
+        None,
+    );
+
+    ///////// Latest version: compactions at or before the checkpoint are dropped /////////
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, None).unwrap();
+    assert_eq!(log_segment.checkpoint_version, Some(2));
+    let versions = log_segment
+        .ascending_compaction_files
+        .iter()
+        .map(|x| x.version)
+        .collect_vec();
+    assert_eq!(versions, vec![3, 3, 6]);
+    // the widest compaction wins the tie at version 3 and makes (6, 7) redundant
+    assert_eq!(cover_versions(&log_segment), vec![(3, 8)]);
+
+    ///////// Time travel: compactions ending after the end version are dropped /////////
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, Some(7)).unwrap();
+    assert_eq!(cover_versions(&log_segment), vec![(6, 7), (3, 5)]);
+
+    ///////// Commits are still retained for table changes /////////
+    let log_segment = LogSegment::for_table_changes(storage.as_ref(), log_root, 0, 8).unwrap();
+    assert!(log_segment.ascending_compaction_files.is_empty());
+    let versions = log_segment
+        .ascending_commit_files
+        .iter()
+        .map(|x| x.version)
+        .collect_vec();
+    assert_eq!(versions, (0..=8).collect_vec());
+}
+
+#[test]
+fn find_commit_cover_falls_back_to_commits_for_gaps() {
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            delta_path_for_version(1, "json"),
+            compaction_path(1, 2),
+            delta_path_for_version(2, "json"),
+            compaction_path(2, 4),
+            delta_path_for_version(3, "json"),
+            delta_path_for_version(4, "json"),
+            delta_path_for_version(5, "json"),
+            compaction_path(5, 6),
+            delta_path_for_version(6, "json"),
+            delta_path_for_version(7, "json"),
+        ],
+        None,
+    );
+
+    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, None).unwrap();
+    // (2, 4) overlaps with the already selected (1, 2), so versions 3 and 4 are read from commits
+    assert_eq!(
+        cover_versions(&log_segment),
+        vec![(7, 7), (5, 6), (4, 4), (3, 3), (1, 2), (0, 0)]
+    );
+}
+
+#[test]
+fn test_scan_with_compaction_file() -> DeltaResult<()> {
+    let (store, log_root) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    let add = |path: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true}}}}"#
+        )
+    };
+    let remove = |path: &str| {
+        format!(r#"{{"remove":{{"path":"{path}","deletionTimestamp":1,"dataChange":true}}}}"#)
+    };
+    let protocol = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string();
+    let metadata = r#"{"metaData":{"id":"test","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1}}"#.to_string();
+    let files = [
+        (
+            delta_path_for_version(0, "json"),
+            vec![protocol, metadata, add("a")],
+        ),
+        (delta_path_for_version(1, "json"), vec![add("b")]),
+        (delta_path_for_version(2, "json"), vec![remove("a")]),
+        (compaction_path(1, 2), vec![remove("a"), add("b")]),
+        (delta_path_for_version(3, "json"), vec![add("c")]),
+    ];
+    block_on(async {
+        for (path, actions) in files {
+            store.put(&path, actions.join("\n").into()).await.unwrap();
+        }
+    });
+
+    let table = Table::new(log_root.join("..")?);
+    let snapshot = Arc::new(table.snapshot(&engine, None)?);
+    assert_eq!(
+        cover_versions(snapshot.log_segment()),
+        vec![(3, 3), (1, 2), (0, 0)]
+    );
+
+    let mut paths = vec![];
+    for scan_metadata in snapshot.scan_builder().build()?.scan_metadata(&engine)? {
+        paths = scan_metadata?
+            .visit_scan_files(paths, |paths: &mut Vec<String>, path, _, _, _, _, _| {
+                paths.push(path.to_string())
+            })?;
+    }
+    paths.sort();
+    assert_eq!(paths, vec!["b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn build_snapshot_with_crc_files() {
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            delta_path_for_version(0, "crc"),
+            delta_path_for_version(1, "json"),
+            delta_path_for_version(2, "json"),
+            delta_path_for_version(2, "crc"),
+            delta_path_for_version(3, "json"),
+            delta_path_for_version(4, "json"),
+            delta_path_for_version(4, "checkpoint.parquet"),
+            delta_path_for_version(5, "json"),
+            delta_path_for_version(6, "json"),
+            delta_path_for_version(6, "crc"),
+            delta_path_for_version(7, "json"),
+        ],
+        None,
+    );
+    let crc_version = |end_version: Option<u64>| {
+        LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, end_version)
+            .unwrap()
+            .latest_crc_file
+            .map(|crc| crc.version)
+    };
+
+    // the newest checksum at or before the end version is used...
+    assert_eq!(crc_version(None), Some(6));
+    assert_eq!(crc_version(Some(6)), Some(6));
+    assert_eq!(crc_version(Some(3)), Some(2));
+    assert_eq!(crc_version(Some(1)), Some(0));
+    // ...unless it is older than the checkpoint
+    assert_eq!(crc_version(Some(5)), None);
+}
+
+#[test]
+fn test_protocol_and_metadata_from_crc_file() -> DeltaResult<()> {
+    let (store, log_root) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    let protocol = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#;
+    let metadata = |id: &str| {
+        format!(
+            r#"{{"id":"{id}","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{{}}}}]}}","partitionColumns":[],"configuration":{{}},"createdTime":1}}"#
+        )
+    };
+    let add = r#"{"add":{"path":"a","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#;
+    // The checksum's metadata id differs from the commits', so we can tell where it came from
+    let crc = format!(
+        r#"{{"tableSizeBytes":1,"numFiles":1,"numMetadata":1,"numProtocol":1,"metadata":{},"protocol":{{"minReaderVersion":1,"minWriterVersion":2}}}}"#,
+        metadata("from-crc")
+    );
+    let files = [
+        (
+            delta_path_for_version(0, "json"),
+            format!("{protocol}\n{{\"metaData\":{}}}", metadata("from-log")),
+        ),
+        (delta_path_for_version(1, "json"), add.to_string()),
+        (delta_path_for_version(1, "crc"), crc),
+        (delta_path_for_version(2, "json"), add.to_string()),
+    ];
+    block_on(async {
+        for (path, content) in files {
+            store.put(&path, content.into()).await.unwrap();
+        }
+    });
+    let table = Table::new(log_root.join("..")?);
+
+    // commits after the checksum don't change the metadata, so it comes from the checksum
+    let snapshot = table.snapshot(&engine, None)?;
+    assert_eq!(snapshot.metadata().id, "from-crc");
+    assert_eq!(snapshot.table_size(&engine)?, None);
+    let snapshot = table.snapshot(&engine, Some(1))?;
+    assert_eq!(snapshot.metadata().id, "from-crc");
+    let table_size = snapshot.table_size(&engine)?.unwrap();
+    assert_eq!((table_size.size_in_bytes, table_size.num_files), (1, 1));
+    // the checksum is not used to time travel before it
+    let snapshot = table.snapshot(&engine, Some(0))?;
+    assert_eq!(snapshot.metadata().id, "from-log");
+
+    // a newer metadata action takes precedence over the checksum
+    let new_metadata = format!(r#"{{"metaData":{}}}"#, metadata("newer"));
+    block_on(store.put(&delta_path_for_version(3, "json"), new_metadata.into()))?;
+    let snapshot = table.snapshot(&engine, None)?;
+    assert_eq!(snapshot.metadata().id, "newer");
+
+    // an invalid checksum is ignored
+    block_on(store.put(&delta_path_for_version(1, "crc"), "invalid".into()))?;
+    let snapshot = table.snapshot(&engine, Some(2))?;
+    assert_eq!(snapshot.metadata().id, "from-log");
+    Ok(())
+}
+
+#[test]
+fn test_list_expired_log_files() {
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            delta_path_for_version(1, "json"),
+            delta_path_for_version(1, "crc"),
+            delta_path_for_version(2, "json"),
+            delta_path_for_version(2, "checkpoint.parquet"),
+            delta_path_for_version(3, "json"),
+            compaction_path(3, 4),
+            compaction_path(3, 5),
+            delta_path_for_version(4, "json"),
+            delta_path_for_version(5, "json"),
+            delta_path_for_multipart_checkpoint(5, 1, 2),
+            delta_path_for_version(6, "json"),
+        ],
+        None,
+    );
+    let expired_files = |end_version, cutoff_timestamp| {
+        super::list_expired_log_files(storage.as_ref(), &log_root, end_version, cutoff_timestamp)
+            .unwrap()
+            .into_iter()
+            .map(|log_path| log_path.filename)
+            .collect_vec()
+    };
+
+    // Everything was written before the cutoff. The incomplete checkpoint at version 5 cannot be
+    // the cleanup boundary, so the checkpoint at version 2 is.
+    assert_eq!(
+        expired_files(6, i64::MAX),
+        [
+            "00000000000000000000.json",
+            "00000000000000000001.crc",
+            "00000000000000000001.json",
+        ]
+    );
+
+    // Nothing is expired if every file is within the retention period
+    assert!(expired_files(6, 0).is_empty());
+    // ...or if there is no checkpoint at or before the end version
+    assert!(expired_files(1, i64::MAX).is_empty());
+}
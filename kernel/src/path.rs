@@ -32,7 +32,6 @@ pub(crate) enum LogPathFileType {
         part_num: u32,
         num_parts: u32,
     },
-    #[allow(unused)]
     CompactedCommit {
         hi: Version,
     },
@@ -170,6 +169,11 @@ impl<Location: AsUrl> ParsedLogPath<Location> {
         )
     }
 
+    #[internal_api]
+    pub(crate) fn is_compaction(&self) -> bool {
+        matches!(self.file_type, LogPathFileType::CompactedCommit { .. })
+    }
+
     #[internal_api]
     #[allow(dead_code)] // currently only used in tests, which don't "count"
     pub(crate) fn is_unknown(&self) -> bool {
@@ -232,8 +236,23 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
-    // TODO: remove after support for writing CRC files
-    #[allow(unused)]
+    /// Create a new ParsedLogPath<Url> for a log compaction file covering the (inclusive) version
+    /// range `[start_version, end_version]`
+    pub(crate) fn new_log_compaction(
+        table_root: &Url,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Self> {
+        let filename = format!("{:020}.{:020}.compacted.json", start_version, end_version);
+        let path = Self::create_path(table_root, filename)?;
+        if !path.is_compaction() {
+            return Err(Error::internal_error(
+                "ParsedLogPath::new_log_compaction created a non-compaction path",
+            ));
+        }
+        Ok(path)
+    }
+
     /// Create a new ParsedCommitPath<Url> for a new CRC file
     pub(crate) fn new_crc(table_root: &Url, version: Version) -> DeltaResult<Self> {
         let filename = format!("{:020}.crc", version);
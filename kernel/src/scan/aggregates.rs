@@ -0,0 +1,391 @@
+//! Metadata-only aggregates, computed from the statistics of the files of a scan instead of their
+//! data.
+//!
+//! Every add action may carry statistics (`numRecords`, and the `minValues`, `maxValues` and
+//! `nullCount` of stat-indexed columns), which can answer `COUNT(*)`, `MIN`, `MAX` and null counts
+//! without reading any data. Statistics can only be trusted for files without a deletion vector,
+//! whose statistics are complete and not truncated. Any other file is reported to the caller, who
+//! must read it to finish the aggregation.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde_json::Value;
+use tracing::warn;
+
+use super::data_skipping::parse_json_stat;
+use super::{PhysicalPredicate, Scan};
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{column_name, ColumnName, Scalar};
+use crate::schema::{ColumnNamesAndTypes, DataType, PrimitiveType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error};
+
+/// Aggregates of one column, computed from file statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStatsAggregates {
+    /// The smallest non-null value of the column, or `None` if it has no non-null values.
+    pub min: Option<Scalar>,
+    /// The largest non-null value of the column, or `None` if it has no non-null values.
+    pub max: Option<Scalar>,
+    /// The number of null values of the column.
+    pub null_count: u64,
+}
+
+/// The result of [`Scan::aggregate_stats`].
+///
+/// The aggregates only cover the files whose statistics could answer them. Any other files are
+/// listed in [`StatsAggregates::files_to_read`], and the caller must read them and combine their
+/// data with these aggregates to get the final result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsAggregates {
+    /// The number of records in the files covered by the aggregates.
+    pub num_records: u64,
+    /// The aggregates of each requested column, over the files covered by the aggregates.
+    pub columns: HashMap<ColumnName, ColumnStatsAggregates>,
+    /// The paths (relative to the table root) of the files not covered by the aggregates.
+    pub files_to_read: Vec<String>,
+}
+
+impl StatsAggregates {
+    /// Whether the aggregates cover every file of the scan, so no file needs to be read.
+    pub fn is_complete(&self) -> bool {
+        self.files_to_read.is_empty()
+    }
+}
+
+/// A column to aggregate, resolved against the scan's schema.
+struct ResolvedColumn {
+    logical_name: ColumnName,
+    physical_path: Vec<String>,
+    data_type: PrimitiveType,
+}
+
+impl Scan {
+    /// Computes `COUNT(*)`, and the `MIN`, `MAX` and null count of each of `columns`, from the
+    /// statistics of the scan's files. See [`StatsAggregates`] for how files whose statistics can't
+    /// be used (because they have a deletion vector, or missing or truncated statistics) are
+    /// reported.
+    ///
+    /// The columns must be non-partition columns of primitive type in the scan's schema. String
+    /// statistics can be truncated by writers, and timestamp statistics are only kept at
+    /// millisecond precision, so files with such statistics must be read to compute the `MIN` and
+    /// `MAX` of those columns.
+    ///
+    /// Returns an error if the scan has a predicate, since file statistics can't tell which rows
+    /// of a file satisfy it.
+    pub fn aggregate_stats(
+        &self,
+        engine: &dyn Engine,
+        columns: &[ColumnName],
+    ) -> DeltaResult<StatsAggregates> {
+        require!(
+            !matches!(self.physical_predicate, PhysicalPredicate::Some(..)),
+            Error::generic("Metadata-only aggregates are not supported for scans with a predicate")
+        );
+        let columns: Vec<_> = columns
+            .iter()
+            .map(|column| self.resolve_stats_column(column))
+            .collect::<DeltaResult<_>>()?;
+        let mut visitor = StatsAggregatesVisitor {
+            columns: &columns,
+            aggregates: StatsAggregates {
+                num_records: 0,
+                columns: columns
+                    .iter()
+                    .map(|column| {
+                        let aggregates = ColumnStatsAggregates {
+                            min: None,
+                            max: None,
+                            null_count: 0,
+                        };
+                        (column.logical_name.clone(), aggregates)
+                    })
+                    .collect(),
+                files_to_read: vec![],
+            },
+            selection_vector: vec![],
+        };
+        for scan_metadata in self.scan_metadata(engine)? {
+            let FilteredEngineData {
+                data,
+                selection_vector,
+            } = scan_metadata?.scan_files;
+            visitor.selection_vector = selection_vector;
+            visitor.visit_rows_of(data.as_ref())?;
+        }
+        Ok(visitor.aggregates)
+    }
+
+    /// Finds `column` in the scan's schema, and checks that it can have statistics.
+    fn resolve_stats_column(&self, column: &ColumnName) -> DeltaResult<ResolvedColumn> {
+        let not_stats_column = |reason: &str| {
+            Error::generic(format!(
+                "Column {column} cannot be aggregated from statistics: {reason}"
+            ))
+        };
+        let mut path = column.iter();
+        let first = path
+            .next()
+            .ok_or_else(|| Error::generic("Cannot aggregate an empty column name"))?;
+        if self.snapshot.metadata().partition_columns.contains(first) {
+            return Err(not_stats_column("partition columns have no statistics"));
+        }
+        let mut field = self
+            .logical_schema
+            .field(first)
+            .ok_or_else(|| Error::missing_column(column.to_string()))?;
+        let mut physical_path = vec![field.physical_name().to_string()];
+        for name in path {
+            let DataType::Struct(ref struct_type) = field.data_type else {
+                return Err(Error::missing_column(column.to_string()));
+            };
+            field = struct_type
+                .field(name)
+                .ok_or_else(|| Error::missing_column(column.to_string()))?;
+            physical_path.push(field.physical_name().to_string());
+        }
+        let DataType::Primitive(ref data_type) = field.data_type else {
+            return Err(not_stats_column("only primitive columns have statistics"));
+        };
+        Ok(ResolvedColumn {
+            logical_name: column.clone(),
+            physical_path,
+            data_type: data_type.clone(),
+        })
+    }
+}
+
+/// Folds the statistics of each selected scan file into the aggregates, or records the file as one
+/// that must be read.
+struct StatsAggregatesVisitor<'a> {
+    columns: &'a [ResolvedColumn],
+    aggregates: StatsAggregates,
+    selection_vector: Vec<bool>,
+}
+
+impl RowVisitor for StatsAggregatesVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![
+                    column_name!("path"),
+                    column_name!("stats"),
+                    column_name!("deletionVector.storageType"),
+                ],
+                vec![DataType::STRING, DataType::STRING, DataType::STRING],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 3,
+            Error::InternalError(format!(
+                "Wrong number of StatsAggregatesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if !self.selection_vector.get(i).copied().unwrap_or(true) {
+                continue;
+            }
+            let Some(path) = getters[0].get_opt(i, "scanFile.path")? else {
+                continue;
+            };
+            let stats: Option<String> = getters[1].get_opt(i, "scanFile.stats")?;
+            let dv_storage_type: Option<String> =
+                getters[2].get_opt(i, "scanFile.deletionVector.storageType")?;
+            let file_stats = match (stats, dv_storage_type) {
+                (Some(stats), None) => FileStats::try_new(&stats, self.columns),
+                _ => None,
+            };
+            match file_stats {
+                Some(file_stats) => file_stats.fold_into(self.columns, &mut self.aggregates),
+                None => self.aggregates.files_to_read.push(path),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The statistics of a single file that are needed for the aggregates, all known to be usable.
+struct FileStats {
+    num_records: u64,
+    // For each resolved column: (min, max, null_count)
+    columns: Vec<(Option<Scalar>, Option<Scalar>, u64)>,
+}
+
+impl FileStats {
+    /// Parses the statistics of a file, returning `None` if they can't answer the aggregates of
+    /// every column.
+    fn try_new(stats: &str, columns: &[ResolvedColumn]) -> Option<Self> {
+        let stats: Value = serde_json::from_str(stats)
+            .inspect_err(|e| warn!("Invalid stats string {stats}: {e}"))
+            .ok()?;
+        let num_records = stats.get("numRecords")?.as_u64()?;
+        let columns = columns
+            .iter()
+            .map(|column| {
+                let stat = |name: &str| {
+                    let value = column
+                        .physical_path
+                        .iter()
+                        .try_fold(stats.get(name)?, |value, field| value.get(field))?;
+                    (!value.is_null()).then_some(value)
+                };
+                let null_count = stat("nullCount")?.as_u64()?;
+                if null_count >= num_records {
+                    // No non-null values, so no min or max
+                    return Some((None, None, null_count));
+                }
+                let min = parse_json_stat(stat("minValues")?, &column.data_type)?;
+                let max = parse_json_stat(stat("maxValues")?, &column.data_type)?;
+                Some((Some(min), Some(max), null_count))
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            num_records,
+            columns,
+        })
+    }
+
+    fn fold_into(self, columns: &[ResolvedColumn], aggregates: &mut StatsAggregates) {
+        aggregates.num_records += self.num_records;
+        for (column, (min, max, null_count)) in columns.iter().zip(self.columns) {
+            let Some(column_aggregates) = aggregates.columns.get_mut(&column.logical_name) else {
+                continue;
+            };
+            column_aggregates.null_count += null_count;
+            column_aggregates.min = pick(column_aggregates.min.take(), min, Ordering::Less);
+            column_aggregates.max = pick(column_aggregates.max.take(), max, Ordering::Greater);
+        }
+    }
+}
+
+/// Returns whichever of `a` and `b` compares as `ord` to the other (or the one that is present).
+fn pick(a: Option<Scalar>, b: Option<Scalar>, ord: Ordering) -> Option<Scalar> {
+    match (a, b) {
+        (Some(a), Some(b)) if b.partial_cmp(&a) == Some(ord) => Some(b),
+        (Some(a), _) => Some(a),
+        (None, b) => b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::expressions::{column_expr, column_name, Expression as Expr, Scalar};
+    use crate::object_store::memory::InMemory;
+    use crate::snapshot::Snapshot;
+    use test_utils::add_commit;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregate_stats() -> DeltaResult<()> {
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let schema = r#"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}},{\"name\":\"name\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}"#;
+        let add = |path: &str, stats: &str| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{"part":"1"}},"size":1,"modificationTime":1,"dataChange":true,"stats":"{stats}"}}}}"#
+            )
+        };
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string(),
+            format!(
+                r#"{{"metaData":{{"id":"id","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{schema}","partitionColumns":["part"],"configuration":{{}},"createdTime":1}}}}"#
+            ),
+            add(
+                "a.parquet",
+                r#"{\"numRecords\":3,\"minValues\":{\"id\":1,\"name\":\"b\"},\"maxValues\":{\"id\":5,\"name\":\"d\"},\"nullCount\":{\"id\":0,\"name\":1}}"#,
+            ),
+            add(
+                "b.parquet",
+                r#"{\"numRecords\":2,\"minValues\":{\"id\":-2,\"name\":\"a\"},\"maxValues\":{\"id\":3,\"name\":\"c\"},\"nullCount\":{\"id\":0,\"name\":0}}"#,
+            ),
+            // all nulls, so no min or max
+            add(
+                "c.parquet",
+                r#"{\"numRecords\":1,\"minValues\":{},\"maxValues\":{},\"nullCount\":{\"id\":1,\"name\":1}}"#,
+            ),
+        ]
+        .join("\n");
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+
+        let url = url::Url::parse("memory:///")?;
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        let scan = snapshot.clone().scan_builder().build()?;
+        let aggregates =
+            scan.aggregate_stats(&engine, &[column_name!("id"), column_name!("name")])?;
+        assert!(aggregates.is_complete());
+        assert_eq!(aggregates.num_records, 6);
+        assert_eq!(
+            aggregates.columns[&column_name!("id")],
+            ColumnStatsAggregates {
+                min: Some(Scalar::Long(-2)),
+                max: Some(Scalar::Long(5)),
+                null_count: 1,
+            }
+        );
+        assert_eq!(
+            aggregates.columns[&column_name!("name")],
+            ColumnStatsAggregates {
+                min: Some(Scalar::from("a")),
+                max: Some(Scalar::from("d")),
+                null_count: 2,
+            }
+        );
+
+        // A file with missing stats, a file with truncated stats, and a file with a DV
+        let commit = [
+            add(
+                "d.parquet",
+                r#"{\"numRecords\":1,\"minValues\":{\"id\":0},\"maxValues\":{\"id\":0},\"nullCount\":{\"id\":0}}"#,
+            ),
+            add(
+                "e.parquet",
+                r#"{\"numRecords\":1,\"minValues\":{\"id\":0,\"name\":\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"},\"maxValues\":{\"id\":0,\"name\":\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"},\"nullCount\":{\"id\":0,\"name\":0}}"#,
+            ),
+            r#"{"add":{"path":"f.parquet","partitionValues":{"part":"1"},"size":1,"modificationTime":1,"dataChange":true,"stats":"{\"numRecords\":1,\"minValues\":{\"id\":0,\"name\":\"a\"},\"maxValues\":{\"id\":0,\"name\":\"a\"},\"nullCount\":{\"id\":0,\"name\":0}}","deletionVector":{"storageType":"u","pathOrInlineDv":"vBn[lx{q8@P<9BNH/isA","offset":1,"sizeInBytes":36,"cardinality":1}}}"#.to_string(),
+        ]
+        .join("\n");
+        add_commit(store.as_ref(), 1, commit).await.unwrap();
+
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let scan = snapshot.clone().scan_builder().build()?;
+        let mut aggregates =
+            scan.aggregate_stats(&engine, &[column_name!("id"), column_name!("name")])?;
+        aggregates.files_to_read.sort();
+        assert!(!aggregates.is_complete());
+        assert_eq!(
+            aggregates.files_to_read,
+            vec!["d.parquet", "e.parquet", "f.parquet"]
+        );
+        assert_eq!(aggregates.num_records, 6);
+
+        // Only `id` has stats in every file without a DV
+        let aggregates = scan.aggregate_stats(&engine, &[column_name!("id")])?;
+        assert_eq!(aggregates.files_to_read, vec!["f.parquet"]);
+        assert_eq!(aggregates.num_records, 8);
+        assert_eq!(aggregates.columns[&column_name!("id")].null_count, 1);
+
+        // Partition columns, unknown columns and predicates are rejected
+        assert!(scan
+            .aggregate_stats(&engine, &[column_name!("part")])
+            .is_err());
+        assert!(scan.aggregate_stats(&engine, &[column_name!("x")]).is_err());
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(column_expr!("id").gt(Expr::literal(1i64))))
+            .build()?;
+        assert!(scan.aggregate_stats(&engine, &[]).is_err());
+        Ok(())
+    }
+}
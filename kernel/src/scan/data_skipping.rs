@@ -2,17 +2,19 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::sync::{Arc, LazyLock};
 
+use serde_json::Value;
 use tracing::debug;
 
 use crate::actions::get_log_add_schema;
 use crate::actions::visitors::SelectionVectorVisitor;
 use crate::error::DeltaResult;
 use crate::expressions::{
-    column_expr, joined_column_expr, BinaryPredicateOp, ColumnName, Expression as Expr,
+    column_expr, joined_column_expr, pad_scale, BinaryPredicateOp, ColumnName, Expression as Expr,
     JunctionPredicateOp, Predicate as Pred, PredicateRef, Scalar,
 };
 use crate::kernel_predicates::{
     DataSkippingPredicateEvaluator, KernelPredicateEvaluator, KernelPredicateEvaluatorDefaults,
+    STRING_STATS_PREFIX_LENGTH,
 };
 use crate::schema::{DataType, PrimitiveType, SchemaRef, SchemaTransform, StructField, StructType};
 use crate::{
@@ -291,3 +293,31 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
         Some(Pred::junction(op, preds))
     }
 }
+
+/// Parses a min or max value from the JSON `stats` of an add action, returning `None` if the value
+/// isn't known to be an exact column value.
+///
+/// Timestamp stats are truncated to milliseconds, and string stats may be truncated to a prefix
+/// of [`STRING_STATS_PREFIX_LENGTH`] characters, so neither is exact (unless the string is shorter
+/// than the prefix length). Decimals written as JSON numbers lose their trailing zeros, and their
+/// digits beyond what an `f64` holds exactly.
+pub(crate) fn parse_json_stat(value: &Value, data_type: &PrimitiveType) -> Option<Scalar> {
+    match (value, data_type) {
+        (_, PrimitiveType::Timestamp | PrimitiveType::TimestampNtz) => None,
+        (Value::String(s), PrimitiveType::String) => {
+            (s.chars().count() < STRING_STATS_PREFIX_LENGTH).then(|| Scalar::String(s.clone()))
+        }
+        (Value::String(s), _) => data_type.parse_scalar(s).ok(),
+        (Value::Number(n), PrimitiveType::Decimal(dtype)) => {
+            if n.is_f64() && u32::from(dtype.precision()) > f64::DIGITS {
+                return None;
+            }
+            data_type
+                .parse_scalar(&pad_scale(&n.to_string(), dtype.scale()))
+                .ok()
+        }
+        (Value::Number(n), _) => data_type.parse_scalar(&n.to_string()).ok(),
+        (Value::Bool(b), _) => data_type.parse_scalar(&b.to_string()).ok(),
+        _ => None,
+    }
+}
@@ -1,7 +1,10 @@
 use super::*;
 
-use crate::expressions::column_name;
-use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, UnimplementedColumnResolver};
+use crate::expressions::{column_name, ArrayData, BinaryPredicateOp};
+use crate::kernel_predicates::{
+    DefaultKernelPredicateEvaluator, UnimplementedColumnResolver, STRING_STATS_PREFIX_LENGTH,
+};
+use crate::schema::ArrayType;
 use std::collections::HashMap;
 
 const TRUE: Option<bool> = Some(true);
@@ -106,6 +109,92 @@ fn test_eval_binary_comparisons() {
     do_test(five, fifteen, &[TRUE, TRUE, TRUE, TRUE, TRUE, TRUE]);
 }
 
+#[test]
+fn test_eval_in_list() {
+    let col = &column_expr!("x");
+    let in_list = |values: &[Scalar]| {
+        let tpe = ArrayType::new(DataType::INTEGER, true);
+        Scalar::Array(ArrayData::try_new(tpe, values.iter().cloned()).unwrap())
+    };
+    let null = Scalar::Null(DataType::INTEGER);
+    let short_list = in_list(&[5.into(), 15.into(), null]);
+    let long_list = in_list(&(0..11).map(|i| Scalar::from(5 + 2 * i)).collect::<Vec<_>>());
+
+    let predicates = [
+        Pred::binary(BinaryPredicateOp::In, col.clone(), short_list.clone()),
+        Pred::binary(BinaryPredicateOp::NotIn, col.clone(), short_list),
+        Pred::binary(BinaryPredicateOp::In, col.clone(), long_list.clone()),
+    ];
+
+    let do_test = |min: i32, max: i32, expected: &[Option<bool>]| {
+        let resolver = HashMap::from_iter([
+            (column_name!("minValues.x"), Scalar::from(min)),
+            (column_name!("maxValues.x"), Scalar::from(max)),
+        ]);
+        let filter = DefaultKernelPredicateEvaluator::from(resolver);
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            let skipping_pred = as_data_skipping_predicate(pred).unwrap();
+            expect_eq!(
+                filter.eval(&skipping_pred),
+                *expect,
+                "{pred:#?} became {skipping_pred:#?} with [{min}..{max}]"
+            );
+        }
+    };
+
+    // NOTE: The NULL list element means the short-list IN can never be FALSE, and NOT IN can
+    // never be TRUE. Either way, the file is only kept if the predicate might be TRUE.
+    do_test(1, 4, &[NULL, NULL, FALSE]);
+    do_test(1, 10, &[TRUE, NULL, TRUE]);
+    do_test(6, 7, &[NULL, NULL, TRUE]);
+    do_test(30, 40, &[NULL, NULL, FALSE]);
+    do_test(5, 5, &[TRUE, FALSE, TRUE]);
+
+    // Long NOT IN lists are not worth the effort of data skipping
+    let pred = Pred::binary(BinaryPredicateOp::NotIn, col.clone(), long_list);
+    assert!(as_data_skipping_predicate(&pred).is_none());
+}
+
+#[test]
+fn test_eval_starts_with() {
+    let col = &column_expr!("x");
+    let long_prefix = "a".repeat(STRING_STATS_PREFIX_LENGTH + 8);
+    let truncated_max = "a".repeat(STRING_STATS_PREFIX_LENGTH);
+    let predicates = [
+        Pred::starts_with(col.clone(), Scalar::from("abc")),
+        Pred::not(Pred::starts_with(col.clone(), Scalar::from("abc"))),
+        Pred::starts_with(col.clone(), Scalar::from(long_prefix.as_str())),
+        Pred::starts_with(col.clone(), Scalar::from("")),
+    ];
+
+    let do_test = |min: &str, max: &str, expected: &[Option<bool>]| {
+        let resolver = HashMap::from_iter([
+            (column_name!("minValues.x"), Scalar::from(min)),
+            (column_name!("maxValues.x"), Scalar::from(max)),
+        ]);
+        let filter = DefaultKernelPredicateEvaluator::from(resolver);
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            let skipping_pred = as_data_skipping_predicate(pred).unwrap();
+            expect_eq!(
+                filter.eval(&skipping_pred),
+                *expect,
+                "{pred:#?} became {skipping_pred:#?} with [{min}..{max}]"
+            );
+        }
+    };
+
+    // Range entirely below, above, or around the prefix
+    do_test("a", "abb", &[FALSE, TRUE, TRUE, TRUE]);
+    do_test("abd", "b", &[FALSE, TRUE, FALSE, TRUE]);
+    do_test("ab", "abd", &[TRUE, TRUE, FALSE, TRUE]);
+
+    // Every value starts with the prefix
+    do_test("abc", "abcz", &[TRUE, FALSE, FALSE, TRUE]);
+
+    // A max stat truncated to fewer characters than the prefix must not cause skipping
+    do_test("a", &truncated_max, &[FALSE, TRUE, TRUE, TRUE]);
+}
+
 #[test]
 fn test_eval_junction() {
     let test_cases = &[
@@ -339,3 +428,38 @@ fn test_sql_where() {
     do_test(ALL_NULL, pred, PRESENT, None, Some(false));
     do_test(ALL_NULL, pred, MISSING, None, None);
 }
+
+#[test]
+fn test_parse_json_stat() {
+    let parse = |json: &str, precision, scale| {
+        let value: Value = serde_json::from_str(json).unwrap();
+        let data_type = PrimitiveType::decimal(precision, scale).unwrap();
+        parse_json_stat(&value, &data_type)
+    };
+    let long_string = "a".repeat(STRING_STATS_PREFIX_LENGTH);
+    assert_eq!(
+        parse_json_stat(&Value::from("abc"), &PrimitiveType::String),
+        Some(Scalar::from("abc"))
+    );
+    // A string of the prefix length may have been truncated
+    assert_eq!(
+        parse_json_stat(&Value::from(long_string), &PrimitiveType::String),
+        None
+    );
+    assert_eq!(
+        parse_json_stat(&Value::from(1), &PrimitiveType::Timestamp),
+        None
+    );
+    let decimal = |bits, precision, scale| Scalar::decimal(bits, precision, scale).unwrap();
+    assert_eq!(parse("1.50", 5, 2), Some(decimal(150, 5, 2)));
+    assert_eq!(parse("-3", 5, 2), Some(decimal(-300, 5, 2)));
+    assert_eq!(parse("\"1.50\"", 5, 2), Some(decimal(150, 5, 2)));
+    // More fractional digits than the scale
+    assert_eq!(parse("1.505", 5, 2), None);
+    // Too many digits for an f64 to hold exactly
+    assert_eq!(parse("12345678901234567.89", 20, 2), None);
+    assert_eq!(
+        parse("1234567890123456789", 20, 0),
+        Some(decimal(1234567890123456789i64, 20, 0))
+    );
+}
@@ -0,0 +1,329 @@
+//! A cache of the active files of a snapshot, which allows scans to skip log replay.
+//!
+//! The [`FileState`] of a snapshot holds the batches of add actions read from its log, along with
+//! the selection of the adds that are active (i.e. survived log replay). The adds are kept as read,
+//! so scans served from the file state still get their deletion vectors and stats (for data
+//! skipping). The file state of a newer snapshot is computed incrementally, by replaying only the
+//! new commits on top of the old file state.
+//!
+//! See [`Snapshot::cache_file_state`] for the public API.
+//!
+//! [`Snapshot::cache_file_state`]: crate::snapshot::Snapshot::cache_file_state
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::{debug, warn};
+
+use super::log_replay::select_active_adds;
+use super::replay_file_actions;
+use crate::log_replay::FileActionKey;
+use crate::log_segment::LogSegment;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, EngineData, Error, Version};
+
+/// A batch of file actions, along with the selection of its active adds.
+pub(crate) struct FileStateBatch {
+    /// The batch of actions, as read from the log
+    pub(crate) actions: Arc<dyn EngineData>,
+    /// Selects the rows of `actions` that are active adds
+    pub(crate) active_adds: Vec<bool>,
+}
+
+/// The reconciled active files of a table at a given version. Only batches that contain at least
+/// one active add are retained.
+pub(crate) struct FileState {
+    version: Version,
+    /// The maximum number of action rows the file state may retain
+    max_rows: usize,
+    /// The number of action rows retained
+    num_rows: usize,
+    batches: Vec<FileStateBatch>,
+}
+
+impl std::fmt::Debug for FileState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileState")
+            .field("version", &self.version)
+            .field("num_batches", &self.batches.len())
+            .field("num_files", &self.num_files())
+            .finish()
+    }
+}
+
+impl FileState {
+    /// Materialize the file state of the snapshot by replaying its log. Returns `None` if the file
+    /// state would retain more than `max_rows` action rows.
+    pub(crate) fn try_new(
+        snapshot: &Snapshot,
+        engine: &dyn Engine,
+        max_rows: usize,
+    ) -> DeltaResult<Option<Self>> {
+        let mut file_state = Self {
+            version: snapshot.version(),
+            max_rows,
+            num_rows: 0,
+            batches: vec![],
+        };
+        let mut seen_file_keys = HashSet::new();
+        let actions = replay_file_actions(snapshot.log_segment(), engine)?;
+        if !file_state.try_extend(&mut seen_file_keys, actions)? {
+            debug!(
+                "file state of version {} exceeds {max_rows} rows",
+                snapshot.version()
+            );
+            return Ok(None);
+        }
+        Ok(Some(file_state))
+    }
+
+    /// Compute the file state of `snapshot` (a newer version of the same table) by replaying only
+    /// the commits after this file state's version. Falls back to materializing the file state
+    /// from scratch if those commits can no longer be read (e.g. because they were cleaned up).
+    /// Returns `None` if the file state would retain more than the maximum number of rows.
+    pub(crate) fn try_refresh(
+        &self,
+        snapshot: &Snapshot,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<Self>> {
+        let new_version = snapshot.version();
+        if new_version < self.version {
+            return Err(Error::generic(format!(
+                "Cannot refresh the file state of version {} to older version {new_version}",
+                self.version
+            )));
+        }
+        let log_segment = match LogSegment::for_table_changes(
+            engine.storage_handler().as_ref(),
+            snapshot.log_segment().log_root.clone(),
+            self.version + 1,
+            new_version,
+        ) {
+            Ok(log_segment) => log_segment,
+            Err(err) => {
+                warn!(
+                    "Failed to list commits {}..={new_version} to refresh the file state, \
+                     materializing it from scratch instead: {err}",
+                    self.version + 1
+                );
+                return Self::try_new(snapshot, engine, self.max_rows);
+            }
+        };
+
+        // The new commits are newer than every action in this file state, so replay them first
+        let mut file_state = Self {
+            version: new_version,
+            max_rows: self.max_rows,
+            num_rows: 0,
+            batches: vec![],
+        };
+        let mut seen_file_keys = HashSet::new();
+        let actions = replay_file_actions(&log_segment, engine)?;
+        if !file_state.try_extend(&mut seen_file_keys, actions)? {
+            return Ok(None);
+        }
+
+        // The adds of this file state are already reconciled, so they only need to be checked
+        // against the file actions of the new commits
+        for batch in &self.batches {
+            let active_adds = select_active_adds(
+                &mut seen_file_keys,
+                batch.actions.as_ref(),
+                batch.active_adds.clone(),
+                false,
+            )?;
+            let batch = FileStateBatch {
+                actions: batch.actions.clone(),
+                active_adds,
+            };
+            if !file_state.try_push(batch) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(file_state))
+    }
+
+    /// The number of active files
+    pub(crate) fn num_files(&self) -> usize {
+        self.batches
+            .iter()
+            .map(|batch| batch.active_adds.iter().filter(|active| **active).count())
+            .sum()
+    }
+
+    pub(crate) fn batches(&self) -> &[FileStateBatch] {
+        &self.batches
+    }
+
+    /// Reconcile the (newest first) batches of file actions, which are older than any batch
+    /// already in this file state, and add them to it. Returns `false` if the maximum number of
+    /// rows is exceeded.
+    fn try_extend(
+        &mut self,
+        seen_file_keys: &mut HashSet<FileActionKey>,
+        actions: impl Iterator<Item = DeltaResult<(Box<dyn EngineData>, bool)>>,
+    ) -> DeltaResult<bool> {
+        for action_res in actions {
+            let (actions, is_log_batch) = action_res?;
+            let active_adds = select_active_adds(
+                seen_file_keys,
+                actions.as_ref(),
+                vec![true; actions.len()],
+                is_log_batch,
+            )?;
+            let batch = FileStateBatch {
+                actions: actions.into(),
+                active_adds,
+            };
+            if !self.try_push(batch) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Add the batch to this file state, unless it has no active adds. Returns `false` if the
+    /// maximum number of rows is exceeded.
+    fn try_push(&mut self, batch: FileStateBatch) -> bool {
+        if !batch.active_adds.contains(&true) {
+            return true;
+        }
+        self.num_rows += batch.actions.len();
+        self.batches.push(batch);
+        self.num_rows <= self.max_rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::object_store::memory::InMemory;
+    use crate::object_store::ObjectStore;
+    use url::Url;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+    use crate::snapshot::Snapshot;
+    use crate::utils::test_utils::{
+        create_add_action_with_stats, create_basic_protocol_action, create_metadata_action,
+        create_remove_action, in_memory_table, write_commit_to_store,
+    };
+    use crate::{DeltaResult, Engine, PredicateRef};
+    use test_utils::delta_path_for_version;
+
+    fn delete_commit(store: &InMemory, version: u64) {
+        futures::executor::block_on(store.delete(&delta_path_for_version(version, "json")))
+            .unwrap();
+    }
+
+    /// Returns the sorted paths of the files selected by a scan of the snapshot
+    fn scan_paths(
+        snapshot: &Arc<Snapshot>,
+        engine: &dyn Engine,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<Vec<String>> {
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(predicate)
+            .build()?;
+        let mut paths = vec![];
+        for scan_metadata in scan.scan_metadata(engine)? {
+            paths = scan_metadata?
+                .visit_scan_files(paths, |paths: &mut Vec<String>, path, _, _, _, _, _| {
+                    paths.push(path.to_string())
+                })?;
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Builds a table with two commits:
+    /// - 0: protocol, metadata, add `a` (value 1), add `b` (value 5)
+    /// - 1: add `c` (value 5), remove `a`
+    fn setup_table() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let (store, engine, table) = in_memory_table([
+            vec![
+                create_basic_protocol_action(),
+                create_metadata_action(),
+                create_add_action_with_stats("a", 1),
+                create_add_action_with_stats("b", 5),
+            ],
+            vec![
+                create_add_action_with_stats("c", 5),
+                create_remove_action("a"),
+            ],
+        ]);
+        (store, engine, table.location().clone())
+    }
+
+    #[test]
+    fn test_scan_from_file_state() -> DeltaResult<()> {
+        let (store, engine, table_root) = setup_table();
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+        assert!(!snapshot.has_cached_file_state());
+        assert!(snapshot.cache_file_state(&engine, 100)?);
+        assert!(snapshot.has_cached_file_state());
+        let file_state = snapshot.file_state().unwrap();
+        assert_eq!(file_state.version, 1);
+        assert_eq!(file_state.num_files(), 2);
+
+        // The scan no longer needs the log
+        delete_commit(&store, 0);
+        delete_commit(&store, 1);
+        assert_eq!(scan_paths(&snapshot, &engine, None)?, ["b", "c"]);
+
+        // The stats are kept for data skipping
+        let predicate = Arc::new(Pred::gt(column_expr!("value"), Expr::literal(3)));
+        assert_eq!(scan_paths(&snapshot, &engine, Some(predicate))?, ["b", "c"]);
+        let predicate = Arc::new(Pred::lt(column_expr!("value"), Expr::literal(3)));
+        assert!(scan_paths(&snapshot, &engine, Some(predicate))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_file_state() -> DeltaResult<()> {
+        let (store, engine, table_root) = setup_table();
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+        assert!(snapshot.cache_file_state(&engine, 100)?);
+
+        // The new snapshot's file state only needs the new commit
+        let commit = vec![
+            create_remove_action("b"),
+            create_add_action_with_stats("d", 1),
+            create_add_action_with_stats("c", 7),
+        ];
+        write_commit_to_store(&store, commit, 2)?;
+        delete_commit(&store, 0);
+        delete_commit(&store, 1);
+        let new_snapshot = Snapshot::try_new_from(snapshot.clone(), &engine, None)?;
+        let file_state = new_snapshot.file_state().unwrap();
+        assert_eq!(file_state.version, 2);
+        assert_eq!(file_state.num_files(), 2);
+        assert_eq!(scan_paths(&new_snapshot, &engine, None)?, ["c", "d"]);
+
+        // The re-added `c` has the newer stats
+        let predicate = Arc::new(Pred::gt(column_expr!("value"), Expr::literal(6)));
+        assert_eq!(scan_paths(&new_snapshot, &engine, Some(predicate))?, ["c"]);
+
+        // The old snapshot's file state is unchanged
+        assert_eq!(scan_paths(&snapshot, &engine, None)?, ["b", "c"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_state_is_bounded() -> DeltaResult<()> {
+        let (_store, engine, table_root) = setup_table();
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+
+        // Each commit is read as a single batch: commit 0 has 4 rows and commit 1 has 2
+        assert!(!snapshot.cache_file_state(&engine, 5)?);
+        assert!(!snapshot.has_cached_file_state());
+        assert_eq!(scan_paths(&snapshot, &engine, None)?, ["b", "c"]);
+
+        assert!(snapshot.cache_file_state(&engine, 6)?);
+        assert!(snapshot.has_cached_file_state());
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+//! Incremental scans, which return the files added and removed by each commit in a version range.
+//!
+//! Unlike a change data feed, an incremental scan does not require the `changeDataFeed` table
+//! feature: it only reports the file actions of each commit, as they appear in the log. This is
+//! what a streaming reader needs to process new data as it arrives, and to detect commits that
+//! change or delete existing data (removes with `dataChange` set).
+use std::sync::{Arc, LazyLock};
+
+use itertools::Itertools;
+
+use super::log_replay::{get_add_transform_expr, ScanLogReplayProcessor, SCAN_ROW_SCHEMA};
+use super::{PhysicalPredicate, Scan, ScanMetadata};
+use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME};
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{column_expr, column_name, ColumnName, Expression};
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, ExpressionEvaluator, Version};
+
+/// Schema of the added files returned by an incremental scan: the scan row schema (see
+/// [`scan_row_schema`]), with an additional `dataChange` column.
+///
+/// [`scan_row_schema`]: super::scan_row_schema
+static ADDED_FILE_ROW_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(
+        SCAN_ROW_SCHEMA
+            .fields()
+            .cloned()
+            .chain([StructField::nullable("dataChange", DataType::BOOLEAN)]),
+    ))
+});
+
+/// Schema of the removed files returned by an incremental scan.
+static REMOVED_FILE_ROW_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    // Note that fields projected out of a nullable struct must be nullable
+    let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
+    let file_constant_values =
+        StructType::new([StructField::nullable("partitionValues", partition_values)]);
+    let deletion_vector = StructType::new([
+        StructField::nullable("storageType", DataType::STRING),
+        StructField::nullable("pathOrInlineDv", DataType::STRING),
+        StructField::nullable("offset", DataType::INTEGER),
+        StructField::nullable("sizeInBytes", DataType::INTEGER),
+        StructField::nullable("cardinality", DataType::LONG),
+    ]);
+    Arc::new(StructType::new([
+        StructField::nullable("path", DataType::STRING),
+        StructField::nullable("size", DataType::LONG),
+        StructField::nullable("deletionTimestamp", DataType::LONG),
+        StructField::nullable("deletionVector", deletion_vector),
+        StructField::nullable("fileConstantValues", file_constant_values),
+        StructField::nullable("dataChange", DataType::BOOLEAN),
+    ]))
+});
+
+/// Get the schema that added files (from [`IncrementalScanMetadata::added_files`]) will be
+/// returned with. It is the scan row schema (see [`scan_row_schema`]), with an additional
+/// `dataChange: boolean` column.
+///
+/// [`scan_row_schema`]: super::scan_row_schema
+pub fn added_file_row_schema() -> SchemaRef {
+    ADDED_FILE_ROW_SCHEMA.clone()
+}
+
+/// Get the schema that removed files (from [`IncrementalScanMetadata::removed_files`]) will be
+/// returned with.
+///
+/// It is:
+/// ```ignored
+/// {
+///    path: string,
+///    size: long,
+///    deletionTimestamp: long,
+///    deletionVector: {
+///      storageType: string,
+///      pathOrInlineDv: string,
+///      offset: int,
+///      sizeInBytes: int,
+///      cardinality: long,
+///    },
+///    fileConstantValues: {
+///      partitionValues: map<string, string>
+///    },
+///    dataChange: boolean
+/// }
+/// ```
+pub fn removed_file_row_schema() -> SchemaRef {
+    REMOVED_FILE_ROW_SCHEMA.clone()
+}
+
+fn get_added_file_transform_expr() -> Expression {
+    let Expression::Struct(mut fields) = get_add_transform_expr() else {
+        unreachable!("the add transform is a struct expression");
+    };
+    fields.push(column_expr!("add.dataChange"));
+    Expression::Struct(fields)
+}
+
+fn get_removed_file_transform_expr() -> Expression {
+    Expression::Struct(vec![
+        column_expr!("remove.path"),
+        column_expr!("remove.size"),
+        column_expr!("remove.deletionTimestamp"),
+        column_expr!("remove.deletionVector"),
+        Expression::Struct(vec![column_expr!("remove.partitionValues")]),
+        column_expr!("remove.dataChange"),
+    ])
+}
+
+/// The files added and removed by (part of) a commit, as returned by
+/// [`Scan::incremental_scan_metadata`].
+pub struct IncrementalScanMetadata {
+    /// The version of the commit that added and removed the files
+    pub version: Version,
+    /// The files added by the commit, in the same form as the files returned by
+    /// [`Scan::scan_metadata`] (including the transforms to apply to the data read from them).
+    /// The rows have the schema given by [`added_file_row_schema`], which includes the
+    /// `dataChange` flag of each file.
+    pub added_files: ScanMetadata,
+    /// The files removed by the commit. Only selected rows are removed files. The rows have the
+    /// schema given by [`removed_file_row_schema`], which includes the deletion vector and the
+    /// `dataChange` flag of each file.
+    pub removed_files: FilteredEngineData,
+}
+
+/// Selects the rows of a batch of actions that are remove actions
+struct RemoveSelectionVisitor {
+    selection_vector: Vec<bool>,
+}
+
+impl RowVisitor for RemoveSelectionVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("remove.path")], vec![DataType::STRING]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of RemoveSelectionVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let path: Option<&str> = getters[0].get_opt(i, "remove.path")?;
+            self.selection_vector.push(path.is_some());
+        }
+        Ok(())
+    }
+}
+
+/// Processes the batches of actions of each commit into [`IncrementalScanMetadata`].
+struct IncrementalScanProcessor {
+    /// Selects the adds (with data skipping and partition pruning) and computes their transforms
+    scan_processor: ScanLogReplayProcessor,
+    /// Whether the scan's predicate statically skips every file, in which case no adds are selected
+    skip_all_adds: bool,
+    added_file_transform: Arc<dyn ExpressionEvaluator>,
+    removed_file_transform: Arc<dyn ExpressionEvaluator>,
+}
+
+impl IncrementalScanProcessor {
+    fn process_commit_batch(
+        &mut self,
+        version: Version,
+        actions: &dyn EngineData,
+    ) -> DeltaResult<IncrementalScanMetadata> {
+        let (added_selection_vector, added_file_transforms) = if self.skip_all_adds {
+            (vec![false; actions.len()], vec![])
+        } else {
+            self.scan_processor.select_commit_adds(actions)?
+        };
+        let mut visitor = RemoveSelectionVisitor {
+            selection_vector: Vec::with_capacity(actions.len()),
+        };
+        visitor.visit_rows_of(actions)?;
+        Ok(IncrementalScanMetadata {
+            version,
+            added_files: ScanMetadata::new(
+                self.added_file_transform.evaluate(actions)?,
+                added_selection_vector,
+                added_file_transforms,
+            ),
+            removed_files: FilteredEngineData {
+                data: self.removed_file_transform.evaluate(actions)?,
+                selection_vector: visitor.selection_vector,
+            },
+        })
+    }
+}
+
+impl Scan {
+    /// Get an iterator of [`IncrementalScanMetadata`]s with the files added and removed by each
+    /// commit after `start_version`, up to and including the version of the scan's snapshot. Only
+    /// those commits are replayed: the adds and removes of different commits are not reconciled
+    /// with each other, and no checkpoint is read.
+    ///
+    /// The commits are returned in ascending version order, with one or more items per commit
+    /// (commits without file actions may be skipped). The added files are filtered by the scan's
+    /// predicate (if any) and come with the transforms needed to read them with the scan's
+    /// schema, just like the files returned by [`Scan::scan_metadata`]. The removed files are
+    /// never filtered, since they are needed to detect changes to existing data.
+    ///
+    /// Returns an error if `start_version` is newer than the scan's snapshot, or if any of the
+    /// commits to replay are missing from the log.
+    pub fn incremental_scan_metadata(
+        &self,
+        engine: &dyn Engine,
+        start_version: Version,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<IncrementalScanMetadata>>> {
+        let end_version = self.snapshot.version();
+        require!(
+            start_version <= end_version,
+            Error::generic(format!(
+                "Incremental scan start version {start_version} is newer than the snapshot \
+                 version {end_version}"
+            ))
+        );
+        let commit_files = if start_version < end_version {
+            LogSegment::for_table_changes(
+                engine.storage_handler().as_ref(),
+                self.snapshot.log_segment().log_root.clone(),
+                start_version + 1,
+                end_version,
+            )?
+            .ascending_commit_files
+        } else {
+            vec![]
+        };
+
+        let (physical_predicate, skip_all_adds) = match self.physical_predicate.clone() {
+            PhysicalPredicate::StaticSkipAll => (None, true),
+            PhysicalPredicate::Some(predicate, schema) => (Some((predicate, schema)), false),
+            PhysicalPredicate::None => (None, false),
+        };
+        let read_schema = get_log_schema().project(&[ADD_NAME, REMOVE_NAME])?;
+        let evaluation_handler = engine.evaluation_handler();
+        let mut processor = IncrementalScanProcessor {
+            scan_processor: ScanLogReplayProcessor::new(
+                engine,
+                physical_predicate,
+                self.logical_schema.clone(),
+                self.static_transform(),
+            ),
+            skip_all_adds,
+            added_file_transform: evaluation_handler.new_expression_evaluator(
+                read_schema.clone(),
+                get_added_file_transform_expr(),
+                ADDED_FILE_ROW_SCHEMA.as_ref().clone().into(),
+            ),
+            removed_file_transform: evaluation_handler.new_expression_evaluator(
+                read_schema.clone(),
+                get_removed_file_transform_expr(),
+                REMOVED_FILE_ROW_SCHEMA.as_ref().clone().into(),
+            ),
+        };
+
+        let json_handler = engine.json_handler();
+        let actions: Vec<_> = commit_files
+            .into_iter()
+            .map(|commit_file: ParsedLogPath| -> DeltaResult<_> {
+                let actions = json_handler.read_json_files(
+                    &[commit_file.location.clone()],
+                    read_schema.clone(),
+                    None,
+                )?;
+                Ok(actions.map_ok(move |actions| (commit_file.version, actions)))
+            })
+            .try_collect()?;
+        Ok(actions
+            .into_iter()
+            .flatten()
+            .map(move |res| {
+                let (version, actions) = res?;
+                processor.process_commit_batch(version, actions.as_ref())
+            })
+            .filter(|res| {
+                res.as_ref().map_or(true, |metadata| {
+                    metadata
+                        .added_files
+                        .scan_files
+                        .selection_vector
+                        .contains(&true)
+                        || metadata.removed_files.selection_vector.contains(&true)
+                })
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use itertools::Itertools;
+
+    use super::IncrementalScanMetadata;
+    use crate::actions::deletion_vector::DeletionVectorDescriptor;
+    use crate::actions::Remove;
+    use crate::arrow::array::{Array as _, AsArray as _, RecordBatch};
+    use crate::arrow::datatypes::Int64Type;
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+    use crate::object_store::memory::InMemory;
+    use crate::utils::test_utils::{
+        create_add_action_with_stats, create_basic_protocol_action, create_metadata_action,
+        create_remove_action, in_memory_table, write_commit_to_store, Action,
+    };
+    use crate::{DeltaResult, EngineData, Error, Table};
+
+    /// Builds a table with four commits:
+    /// - 0: protocol, metadata, add `a` (value 1)
+    /// - 1: add `b` (value 5), add `c` (value 1, without data change)
+    /// - 2: remove `a` (with a DV)
+    /// - 3: protocol only
+    fn setup_table() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Table) {
+        let mut add_c = create_add_action_with_stats("c", 1);
+        if let Action::Add(add) = &mut add_c {
+            add.data_change = false;
+        }
+        let remove_a = Action::Remove(Remove {
+            path: "a".into(),
+            data_change: true,
+            deletion_timestamp: Some(100),
+            deletion_vector: Some(DeletionVectorDescriptor {
+                storage_type: "u".into(),
+                path_or_inline_dv: "vBn[lx{q8@P<9BNH/isA".into(),
+                offset: Some(1),
+                size_in_bytes: 36,
+                cardinality: 2,
+            }),
+            ..Default::default()
+        });
+        in_memory_table([
+            vec![
+                create_basic_protocol_action(),
+                create_metadata_action(),
+                create_add_action_with_stats("a", 1),
+            ],
+            vec![create_add_action_with_stats("b", 5), add_c],
+            vec![remove_a],
+            vec![create_basic_protocol_action()],
+        ])
+    }
+
+    fn selected_rows(data: Box<dyn EngineData>, selection_vector: &[bool]) -> RecordBatch {
+        let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data).unwrap().into();
+        let indices = selection_vector.iter().positions(|selected| *selected);
+        let rows = indices.map(|i| batch.slice(i, 1)).collect_vec();
+        crate::arrow::compute::concat_batches(&batch.schema(), &rows).unwrap()
+    }
+
+    /// Returns the (path, dataChange) pairs of the selected rows
+    fn paths_and_data_changes(batch: &RecordBatch) -> Vec<(String, bool)> {
+        let paths = batch.column_by_name("path").unwrap().as_string::<i32>();
+        let data_changes = batch.column_by_name("dataChange").unwrap().as_boolean();
+        (0..batch.num_rows())
+            .map(|i| (paths.value(i).to_string(), data_changes.value(i)))
+            .collect()
+    }
+
+    /// The version, added files (path and dataChange) and removed files of an item
+    type Changes = (u64, Vec<(String, bool)>, RecordBatch);
+
+    fn collect_changes(
+        changes: impl Iterator<Item = DeltaResult<IncrementalScanMetadata>>,
+    ) -> DeltaResult<Vec<Changes>> {
+        changes
+            .map_ok(|changes| {
+                let added = selected_rows(
+                    changes.added_files.scan_files.data,
+                    &changes.added_files.scan_files.selection_vector,
+                );
+                let removed = selected_rows(
+                    changes.removed_files.data,
+                    &changes.removed_files.selection_vector,
+                );
+                (changes.version, paths_and_data_changes(&added), removed)
+            })
+            .try_collect()
+    }
+
+    #[test]
+    fn test_incremental_scan_metadata() -> DeltaResult<()> {
+        let (_store, engine, table) = setup_table();
+
+        let changes = collect_changes(table.incremental_scan_metadata(&engine, 0, None)?)?;
+        assert_eq!(changes.len(), 2);
+        let (version, added, removed) = &changes[0];
+        assert_eq!(*version, 1);
+        assert_eq!(added, &[("b".to_string(), true), ("c".to_string(), false)]);
+        assert_eq!(removed.num_rows(), 0);
+
+        let (version, added, removed) = &changes[1];
+        assert_eq!(*version, 2);
+        assert!(added.is_empty());
+        assert_eq!(paths_and_data_changes(removed), [("a".to_string(), true)]);
+        let deletion_vector = removed.column_by_name("deletionVector").unwrap();
+        assert!(!deletion_vector.is_null(0));
+        let deletion_timestamp = removed.column_by_name("deletionTimestamp").unwrap();
+        assert_eq!(deletion_timestamp.as_primitive::<Int64Type>().value(0), 100);
+
+        // Only the commits in the range are replayed
+        let changes = collect_changes(table.incremental_scan_metadata(&engine, 1, Some(1))?)?;
+        assert!(changes.is_empty());
+        let changes = collect_changes(table.incremental_scan_metadata(&engine, 1, Some(2))?)?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_scan_metadata_with_predicate() -> DeltaResult<()> {
+        let (_store, engine, table) = setup_table();
+        let snapshot = Arc::new(table.snapshot(&engine, None)?);
+
+        // Data skipping applies to the added files but not the removed ones
+        let predicate = Arc::new(Pred::gt(column_expr!("value"), Expr::literal(3)));
+        let scan = snapshot.scan_builder().with_predicate(predicate).build()?;
+        let changes = collect_changes(scan.incremental_scan_metadata(&engine, 0)?)?;
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].1, [("b".to_string(), true)]);
+        assert_eq!(changes[1].2.num_rows(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_scan_metadata_add_and_remove_same_file() -> DeltaResult<()> {
+        let (store, engine, table) = setup_table();
+        // A commit may remove and re-add the same file (e.g. to update its stats), in any order
+        let commit = vec![
+            create_remove_action("b"),
+            create_add_action_with_stats("b", 6),
+            create_add_action_with_stats("d", 1),
+            create_remove_action("d"),
+        ];
+        write_commit_to_store(&store, commit, 4)?;
+
+        let changes = collect_changes(table.incremental_scan_metadata(&engine, 3, None)?)?;
+        assert_eq!(changes.len(), 1);
+        let (version, added, removed) = &changes[0];
+        assert_eq!(*version, 4);
+        assert_eq!(added, &[("b".to_string(), true), ("d".to_string(), true)]);
+        assert_eq!(removed.num_rows(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_scan_metadata_invalid_range() -> DeltaResult<()> {
+        let (_store, engine, table) = setup_table();
+        let snapshot = Arc::new(table.snapshot(&engine, Some(2))?);
+        let scan = snapshot.scan_builder().build()?;
+        let res = scan.incremental_scan_metadata(&engine, 3);
+        assert!(matches!(res, Err(Error::Generic(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_added_files_have_scan_row_schema() -> DeltaResult<()> {
+        let (_store, engine, table) = setup_table();
+        let mut changes = table.incremental_scan_metadata(&engine, 0, Some(1))?;
+        let changes = changes.next().unwrap()?;
+        let paths = changes
+            .added_files
+            .visit_scan_files(vec![], |paths: &mut Vec<String>, path, _, _, _, _, _| {
+                paths.push(path.to_string())
+            })?;
+        assert_eq!(paths, ["b", "c"]);
+        Ok(())
+    }
+}
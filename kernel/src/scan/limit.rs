@@ -0,0 +1,306 @@
+//! Limit pushdown for scans, using the `numRecords` statistic of each file to stop emitting scan
+//! files once they are guaranteed to contain enough rows.
+use std::sync::LazyLock;
+
+use serde_json::Value;
+
+use super::data_skipping::parse_json_stat;
+use super::ScanMetadata;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{column_name, ColumnName, Predicate, PredicateRef, Scalar};
+use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
+use crate::kernel_predicates::KernelPredicateEvaluator as _;
+use crate::schema::{ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// Tracks how many more rows the scan must guarantee, and unselects the scan files that are not
+/// needed once enough rows are guaranteed.
+///
+/// A file guarantees `numRecords` minus its deletion vector's cardinality rows. If the scan has a
+/// predicate, a file only guarantees rows if its stats prove that every row satisfies the
+/// predicate; otherwise it guarantees nothing (but is still scanned).
+pub(crate) struct LimitFilter {
+    remaining: u64,
+    // The negation of the scan's (physical) predicate, if any
+    negated_predicate: Option<Predicate>,
+}
+
+impl LimitFilter {
+    pub(crate) fn new(limit: u64, physical_predicate: Option<PredicateRef>) -> Self {
+        Self {
+            remaining: limit,
+            negated_predicate: physical_predicate
+                .map(|predicate| Predicate::not(predicate.as_ref().clone())),
+        }
+    }
+
+    /// Whether the files seen so far are guaranteed to contain enough rows.
+    pub(crate) fn is_satisfied(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Counts the rows guaranteed by the selected files of `scan_metadata`, and unselects every
+    /// file after the one that satisfied the limit.
+    pub(crate) fn apply(&mut self, scan_metadata: &mut ScanMetadata) -> DeltaResult<()> {
+        let mut visitor = LimitVisitor {
+            filter: self,
+            selection_vector: &mut scan_metadata.scan_files.selection_vector,
+        };
+        visitor.visit_rows_of(scan_metadata.scan_files.data.as_ref())
+    }
+
+    /// The number of rows the file is guaranteed to contribute to the scan.
+    fn guaranteed_rows(&self, stats: Option<&str>, dv_cardinality: Option<i64>) -> u64 {
+        let Some(stats) = stats.and_then(|stats| serde_json::from_str::<Value>(stats).ok()) else {
+            return 0;
+        };
+        let Some(num_records) = stats.get("numRecords").and_then(Value::as_i64) else {
+            return 0;
+        };
+        if let Some(ref negated_predicate) = self.negated_predicate {
+            let provider = JsonStatsProvider {
+                stats: &stats,
+                num_records,
+            };
+            // If no row can satisfy the negated predicate, every row satisfies the predicate or
+            // makes it NULL. Rows can only make it NULL if a referenced column is NULL.
+            let all_rows_match = provider.eval_sql_where(negated_predicate) == Some(false)
+                && negated_predicate
+                    .references()
+                    .into_iter()
+                    .all(|col| provider.get_parquet_nullcount_stat(col) == Some(0));
+            if !all_rows_match {
+                return 0;
+            }
+        }
+        let num_rows = num_records - dv_cardinality.unwrap_or(0);
+        num_rows.try_into().unwrap_or(0)
+    }
+}
+
+struct LimitVisitor<'a> {
+    filter: &'a mut LimitFilter,
+    selection_vector: &'a mut Vec<bool>,
+}
+
+impl RowVisitor for LimitVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![
+                    column_name!("stats"),
+                    column_name!("deletionVector.storageType"),
+                    column_name!("deletionVector.cardinality"),
+                ],
+                vec![DataType::STRING, DataType::STRING, DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 3,
+            Error::InternalError(format!(
+                "Wrong number of LimitVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if !self.selection_vector[i] {
+                continue;
+            }
+            if self.filter.is_satisfied() {
+                self.selection_vector[i] = false;
+                continue;
+            }
+            let stats: Option<String> = getters[0].get_opt(i, "scanFile.stats")?;
+            let dv_storage_type: Option<String> =
+                getters[1].get_opt(i, "scanFile.deletionVector.storageType")?;
+            let dv_cardinality: Option<i64> =
+                getters[2].get_opt(i, "scanFile.deletionVector.cardinality")?;
+            if dv_storage_type.is_some() && dv_cardinality.is_none() {
+                // A deletion vector of unknown cardinality could delete every row
+                continue;
+            }
+            let rows = self
+                .filter
+                .guaranteed_rows(stats.as_deref(), dv_cardinality);
+            self.filter.remaining = self.filter.remaining.saturating_sub(rows);
+        }
+        Ok(())
+    }
+}
+
+/// Provides the stats of a file (parsed from the JSON `stats` of its add action) for data
+/// skipping.
+struct JsonStatsProvider<'a> {
+    stats: &'a Value,
+    num_records: i64,
+}
+
+impl JsonStatsProvider<'_> {
+    fn get_stat(&self, stat_name: &str, col: &ColumnName) -> Option<&Value> {
+        col.iter()
+            .try_fold(self.stats.get(stat_name)?, |value, field| value.get(field))
+            .filter(|value| !value.is_null())
+    }
+
+    fn get_value_stat(
+        &self,
+        stat_name: &str,
+        col: &ColumnName,
+        data_type: &DataType,
+    ) -> Option<Scalar> {
+        let DataType::Primitive(data_type) = data_type else {
+            return None;
+        };
+        parse_json_stat(self.get_stat(stat_name, col)?, data_type)
+    }
+}
+
+impl ParquetStatsProvider for JsonStatsProvider<'_> {
+    fn get_parquet_min_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
+        self.get_value_stat("minValues", col, data_type)
+    }
+
+    fn get_parquet_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
+        self.get_value_stat("maxValues", col, data_type)
+    }
+
+    fn get_parquet_nullcount_stat(&self, col: &ColumnName) -> Option<i64> {
+        self.get_stat("nullCount", col)?.as_i64()
+    }
+
+    fn get_parquet_rowcount_stat(&self) -> i64 {
+        self.num_records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::expressions::{column_expr, Expression as Expr};
+    use crate::object_store::memory::InMemory;
+    use crate::scan::state::{DvInfo, Stats};
+    use crate::scan::ScanBuilder;
+    use crate::snapshot::Snapshot;
+    use crate::{DeltaResult, Engine, ExpressionRef};
+    use test_utils::add_commit;
+
+    fn scan_file_paths(engine: &dyn Engine, scan_builder: ScanBuilder) -> DeltaResult<Vec<String>> {
+        fn callback(
+            paths: &mut Vec<String>,
+            path: &str,
+            _: i64,
+            _: Option<Stats>,
+            _: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            paths.push(path.to_string());
+        }
+        let scan = scan_builder.build()?;
+        let mut paths = vec![];
+        for scan_metadata in scan.scan_metadata(engine)? {
+            paths = scan_metadata?.visit_scan_files(paths, callback)?;
+        }
+        Ok(paths)
+    }
+
+    #[tokio::test]
+    async fn scan_with_limit() -> DeltaResult<()> {
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let schema = r#"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}"#;
+        let add = |path: &str, stats: &str, dv: &str| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true,"stats":"{stats}"{dv}}}}}"#
+            )
+        };
+        let dv = r#","deletionVector":{"storageType":"u","pathOrInlineDv":"vBn[lx{q8@P<9BNH/isA","offset":1,"sizeInBytes":36,"cardinality":2}"#;
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string(),
+            format!(
+                r#"{{"metaData":{{"id":"id","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{schema}","partitionColumns":[],"configuration":{{}},"createdTime":1}}}}"#
+            ),
+            add(
+                "a.parquet",
+                r#"{\"numRecords\":3,\"minValues\":{\"id\":1},\"maxValues\":{\"id\":5},\"nullCount\":{\"id\":0}}"#,
+                "",
+            ),
+            add(
+                "b.parquet",
+                r#"{\"numRecords\":2,\"minValues\":{\"id\":-2},\"maxValues\":{\"id\":3},\"nullCount\":{\"id\":0}}"#,
+                "",
+            ),
+            add("c.parquet", r#"{}"#, ""),
+            add(
+                "d.parquet",
+                r#"{\"numRecords\":5,\"minValues\":{\"id\":5},\"maxValues\":{\"id\":9},\"nullCount\":{\"id\":0}}"#,
+                dv,
+            ),
+            add(
+                "e.parquet",
+                r#"{\"numRecords\":5,\"minValues\":{\"id\":5},\"maxValues\":{\"id\":9},\"nullCount\":{\"id\":1}}"#,
+                "",
+            ),
+        ]
+        .join("\n");
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+        let url = url::Url::parse("memory:///")?;
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let scan_builder = || snapshot.clone().scan_builder();
+
+        let all_files = [
+            "a.parquet",
+            "b.parquet",
+            "c.parquet",
+            "d.parquet",
+            "e.parquet",
+        ];
+        assert_eq!(scan_file_paths(&engine, scan_builder())?, all_files);
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(100))?,
+            all_files
+        );
+        assert!(scan_file_paths(&engine, scan_builder().with_limit(0))?.is_empty());
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(3))?,
+            ["a.parquet"]
+        );
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(4))?,
+            ["a.parquet", "b.parquet"]
+        );
+        // Files without stats don't count, and the DV removes rows
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(8))?,
+            ["a.parquet", "b.parquet", "c.parquet", "d.parquet"]
+        );
+
+        // With a predicate, only files whose every row satisfies it count. `b` has rows that don't
+        // satisfy it, and `e` has NULLs.
+        let predicate = Arc::new(column_expr!("id").gt(Expr::literal(0i64)));
+        let scan_builder = || scan_builder().with_predicate(predicate.clone());
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(3))?,
+            ["a.parquet"]
+        );
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(4))?,
+            ["a.parquet", "b.parquet", "c.parquet", "d.parquet"]
+        );
+        assert_eq!(
+            scan_file_paths(&engine, scan_builder().with_limit(7))?,
+            all_files
+        );
+        Ok(())
+    }
+}
@@ -5,14 +5,17 @@ use std::sync::{Arc, LazyLock};
 use itertools::Itertools;
 
 use super::data_skipping::DataSkippingFilter;
+use super::file_state::{FileState, FileStateBatch};
 use super::{ScanMetadata, Transform};
 use crate::actions::get_log_add_schema;
 use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use crate::expressions::{
-    column_expr, column_name, ColumnName, Expression, ExpressionRef, PredicateRef,
+    column_expr, column_name, ColumnName, Expression, ExpressionRef, Predicate, PredicateRef,
 };
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
-use crate::log_replay::{FileActionDeduplicator, FileActionKey, LogReplayProcessor};
+use crate::log_replay::{
+    FileActionDeduplicator, FileActionKey, HasSelectionVector as _, LogReplayProcessor,
+};
 use crate::scan::{Scalar, TransformExpr};
 use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
 use crate::utils::require;
@@ -54,7 +57,7 @@ pub(crate) struct ScanLogReplayProcessor {
 
 impl ScanLogReplayProcessor {
     /// Create a new [`ScanLogReplayProcessor`] instance
-    fn new(
+    pub(crate) fn new(
         engine: &dyn Engine,
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
         logical_schema: SchemaRef,
@@ -75,6 +78,82 @@ impl ScanLogReplayProcessor {
     }
 }
 
+/// The indexes (into the logical schema) of the partition columns of a [`Transform`].
+fn partition_field_indices(transform: &Transform) -> impl Iterator<Item = usize> + '_ {
+    transform
+        .iter()
+        .filter_map(|transform_expr| match transform_expr {
+            TransformExpr::Partition(field_idx) => Some(*field_idx),
+            TransformExpr::Static(_) => None,
+        })
+}
+
+/// Parses the values of the partition columns at `partition_field_indices` of `logical_schema`
+/// out of the `partitionValues` of a file action. Each value is keyed by the index of its column,
+/// and paired with the column's physical name. Columns without a partition value are null.
+pub(crate) fn parse_partition_values(
+    logical_schema: &StructType,
+    partition_field_indices: impl IntoIterator<Item = usize>,
+    partition_values: &HashMap<String, String>,
+) -> DeltaResult<HashMap<usize, (String, Scalar)>> {
+    partition_field_indices
+        .into_iter()
+        .map(|field_idx| {
+            let Some((_, field)) = logical_schema.fields.get_index(field_idx) else {
+                return Err(Error::InternalError(format!(
+                    "out of bounds partition column field index {field_idx}"
+                )));
+            };
+            let name = field.physical_name();
+            let partition_value =
+                super::parse_partition_value(partition_values.get(name), field.data_type())?;
+            Ok((field_idx, (name.to_string(), partition_value)))
+        })
+        .try_collect()
+}
+
+/// True if the (physical) `partition_filter` proves that no row of a file with the given parsed
+/// partition values can satisfy it. See [`parse_partition_values`].
+pub(crate) fn is_file_partition_pruned(
+    partition_filter: Option<&Predicate>,
+    partition_values: &HashMap<usize, (String, Scalar)>,
+) -> bool {
+    if partition_values.is_empty() {
+        return false;
+    }
+    let Some(partition_filter) = partition_filter else {
+        return false;
+    };
+    let partition_values: HashMap<_, _> = partition_values
+        .values()
+        .map(|(k, v)| (ColumnName::new([k]), v.clone()))
+        .collect();
+    let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
+    evaluator.eval_sql_where(partition_filter) == Some(false)
+}
+
+/// Compute an expression that will transform from physical to logical for a given Add file action
+fn get_transform_expr(
+    transform: &Transform,
+    mut partition_values: HashMap<usize, (String, Scalar)>,
+) -> DeltaResult<ExpressionRef> {
+    let transforms = transform
+        .iter()
+        .map(|transform_expr| match transform_expr {
+            TransformExpr::Partition(field_idx) => {
+                let Some((_, partition_value)) = partition_values.remove(field_idx) else {
+                    return Err(Error::InternalError(format!(
+                        "missing partition value for field index {field_idx}"
+                    )));
+                };
+                Ok(partition_value.into())
+            }
+            TransformExpr::Static(field_expr) => Ok(field_expr.clone()),
+        })
+        .try_collect()?;
+    Ok(Arc::new(Expression::Struct(transforms)))
+}
+
 /// A visitor that deduplicates a stream of add and remove actions into a stream of valid adds. Log
 /// replay visits actions newest-first, so once we've seen a file action for a given (path, dvId)
 /// pair, we should ignore all subsequent (older) actions for that same (path, dvId) pair. If the
@@ -122,80 +201,6 @@ impl AddRemoveDedupVisitor<'_> {
         }
     }
 
-    fn parse_partition_value(
-        &self,
-        field_idx: usize,
-        partition_values: &HashMap<String, String>,
-    ) -> DeltaResult<(usize, (String, Scalar))> {
-        let field = self.logical_schema.fields.get_index(field_idx);
-        let Some((_, field)) = field else {
-            return Err(Error::InternalError(format!(
-                "out of bounds partition column field index {field_idx}"
-            )));
-        };
-        let name = field.physical_name();
-        let partition_value =
-            super::parse_partition_value(partition_values.get(name), field.data_type())?;
-        Ok((field_idx, (name.to_string(), partition_value)))
-    }
-
-    fn parse_partition_values(
-        &self,
-        transform: &Transform,
-        partition_values: &HashMap<String, String>,
-    ) -> DeltaResult<HashMap<usize, (String, Scalar)>> {
-        transform
-            .iter()
-            .filter_map(|transform_expr| match transform_expr {
-                TransformExpr::Partition(field_idx) => {
-                    Some(self.parse_partition_value(*field_idx, partition_values))
-                }
-                TransformExpr::Static(_) => None,
-            })
-            .try_collect()
-    }
-
-    /// Compute an expression that will transform from physical to logical for a given Add file action
-    fn get_transform_expr(
-        &self,
-        transform: &Transform,
-        mut partition_values: HashMap<usize, (String, Scalar)>,
-    ) -> DeltaResult<ExpressionRef> {
-        let transforms = transform
-            .iter()
-            .map(|transform_expr| match transform_expr {
-                TransformExpr::Partition(field_idx) => {
-                    let Some((_, partition_value)) = partition_values.remove(field_idx) else {
-                        return Err(Error::InternalError(format!(
-                            "missing partition value for field index {field_idx}"
-                        )));
-                    };
-                    Ok(partition_value.into())
-                }
-                TransformExpr::Static(field_expr) => Ok(field_expr.clone()),
-            })
-            .try_collect()?;
-        Ok(Arc::new(Expression::Struct(transforms)))
-    }
-
-    fn is_file_partition_pruned(
-        &self,
-        partition_values: &HashMap<usize, (String, Scalar)>,
-    ) -> bool {
-        if partition_values.is_empty() {
-            return false;
-        }
-        let Some(partition_filter) = &self.partition_filter else {
-            return false;
-        };
-        let partition_values: HashMap<_, _> = partition_values
-            .values()
-            .map(|(k, v)| (ColumnName::new([k]), v.clone()))
-            .collect();
-        let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
-        evaluator.eval_sql_where(partition_filter) == Some(false)
-    }
-
     /// True if this row contains an Add action that should survive log replay. Skip it if the row
     /// is not an Add action, or the file has already been seen previously.
     fn is_valid_add<'a>(&mut self, i: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<bool> {
@@ -224,8 +229,12 @@ impl AddRemoveDedupVisitor<'_> {
             Some(transform) if is_add => {
                 let partition_values =
                     getters[Self::ADD_PARTITION_VALUES_INDEX].get(i, "add.partitionValues")?;
-                let partition_values = self.parse_partition_values(transform, &partition_values)?;
-                if self.is_file_partition_pruned(&partition_values) {
+                let partition_values = parse_partition_values(
+                    &self.logical_schema,
+                    partition_field_indices(transform),
+                    &partition_values,
+                )?;
+                if is_file_partition_pruned(self.partition_filter.as_deref(), &partition_values) {
                     return Ok(false);
                 }
                 partition_values
@@ -240,7 +249,7 @@ impl AddRemoveDedupVisitor<'_> {
         let transform = self
             .transform
             .as_ref()
-            .map(|transform| self.get_transform_expr(transform, partition_values))
+            .map(|transform| get_transform_expr(transform, partition_values))
             .transpose()?;
         if transform.is_some() {
             // fill in any needed `None`s for previous rows
@@ -302,6 +311,78 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
     }
 }
 
+/// A visitor that selects the adds of a batch of actions from a single commit that survive
+/// partition pruning. Unlike [`AddRemoveDedupVisitor`], it does not reconcile the adds with any
+/// other file actions: a commit may both add and remove the same file, so that is up to the caller.
+struct CommitAddsVisitor {
+    selection_vector: Vec<bool>,
+    logical_schema: SchemaRef,
+    transform: Option<Arc<Transform>>,
+    partition_filter: Option<PredicateRef>,
+    row_transform_exprs: Vec<Option<ExpressionRef>>,
+}
+
+impl CommitAddsVisitor {
+    /// True if this row contains an Add action that survives partition pruning.
+    fn is_selected_add<'a>(
+        &mut self,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<bool> {
+        if getters[0].get_str(i, "add.path")?.is_none() {
+            return Ok(false);
+        }
+        let Some(transform) = &self.transform else {
+            return Ok(true);
+        };
+        let partition_values = getters[1].get(i, "add.partitionValues")?;
+        let partition_values = parse_partition_values(
+            &self.logical_schema,
+            partition_field_indices(transform),
+            &partition_values,
+        )?;
+        if is_file_partition_pruned(self.partition_filter.as_deref(), &partition_values) {
+            return Ok(false);
+        }
+        let transform = get_transform_expr(transform, partition_values)?;
+        // fill in any needed `None`s for previous rows
+        self.row_transform_exprs.resize_with(i, Default::default);
+        self.row_transform_exprs.push(Some(transform));
+        Ok(true)
+    }
+}
+
+impl RowVisitor for CommitAddsVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let ss_map: DataType = MapType::new(DataType::STRING, DataType::STRING, true).into();
+            let types_and_names = vec![
+                (DataType::STRING, column_name!("add.path")),
+                (ss_map, column_name!("add.partitionValues")),
+            ];
+            let (types, names) = types_and_names.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 2,
+            Error::InternalError(format!(
+                "Wrong number of CommitAddsVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if self.selection_vector[i] {
+                self.selection_vector[i] = self.is_selected_add(i, getters)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 // NB: If you update this schema, ensure you update the comment describing it in the doc comment
 // for `scan_row_schema` in scan/mod.rs! You'll also need to update ScanFileVisitor as the
 // indexes will be off, and [`get_add_transform_expr`] below to match it.
@@ -330,7 +411,7 @@ pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(||
 pub(crate) static SCAN_ROW_DATATYPE: LazyLock<DataType> =
     LazyLock::new(|| SCAN_ROW_SCHEMA.clone().into());
 
-fn get_add_transform_expr() -> Expression {
+pub(crate) fn get_add_transform_expr() -> Expression {
     Expression::Struct(vec![
         column_expr!("add.path"),
         column_expr!("add.size"),
@@ -341,18 +422,15 @@ fn get_add_transform_expr() -> Expression {
     ])
 }
 
-impl LogReplayProcessor for ScanLogReplayProcessor {
-    type Output = ScanMetadata;
-
-    fn process_actions_batch(
+impl ScanLogReplayProcessor {
+    /// Deduplicates and transforms the rows of the batch that are selected by the initial
+    /// `selection_vector`, producing the [`ScanMetadata`] of the batch.
+    fn process_selected_actions(
         &mut self,
-        actions_batch: Box<dyn EngineData>,
+        actions_batch: &dyn EngineData,
+        selection_vector: Vec<bool>,
         is_log_batch: bool,
-    ) -> DeltaResult<Self::Output> {
-        // Build an initial selection vector for the batch which has had the data skipping filter
-        // applied. The selection vector is further updated by the deduplication visitor to remove
-        // rows that are not valid adds.
-        let selection_vector = self.build_selection_vector(actions_batch.as_ref())?;
+    ) -> DeltaResult<ScanMetadata> {
         assert_eq!(selection_vector.len(), actions_batch.len());
 
         let mut visitor = AddRemoveDedupVisitor::new(
@@ -363,10 +441,10 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
             self.partition_filter.clone(),
             is_log_batch,
         );
-        visitor.visit_rows_of(actions_batch.as_ref())?;
+        visitor.visit_rows_of(actions_batch)?;
 
         // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
-        let result = self.add_transform.evaluate(actions_batch.as_ref())?;
+        let result = self.add_transform.evaluate(actions_batch)?;
         Ok(ScanMetadata::new(
             result,
             visitor.selection_vector,
@@ -374,6 +452,51 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
         ))
     }
 
+    /// Selects the adds of a batch of actions read from a single commit that pass data skipping
+    /// and partition pruning, without reconciling them with any other file actions. Returns the
+    /// selection vector, along with the transforms of the selected adds.
+    pub(crate) fn select_commit_adds(
+        &self,
+        actions_batch: &dyn EngineData,
+    ) -> DeltaResult<(Vec<bool>, Vec<Option<ExpressionRef>>)> {
+        let mut visitor = CommitAddsVisitor {
+            selection_vector: self.build_selection_vector(actions_batch)?,
+            logical_schema: self.logical_schema.clone(),
+            transform: self.transform.clone(),
+            partition_filter: self.partition_filter.clone(),
+            row_transform_exprs: Vec::new(),
+        };
+        visitor.visit_rows_of(actions_batch)?;
+        Ok((visitor.selection_vector, visitor.row_transform_exprs))
+    }
+
+    /// Processes a batch of a [`FileState`], where the active adds are already reconciled. Only
+    /// the active adds that pass data skipping are selected.
+    fn process_file_state_batch(&mut self, batch: &FileStateBatch) -> DeltaResult<ScanMetadata> {
+        let mut selection_vector = self.build_selection_vector(batch.actions.as_ref())?;
+        for (selected, active) in selection_vector.iter_mut().zip(&batch.active_adds) {
+            *selected &= active;
+        }
+        // The adds are reconciled, so process them like a checkpoint batch
+        self.process_selected_actions(batch.actions.as_ref(), selection_vector, false)
+    }
+}
+
+impl LogReplayProcessor for ScanLogReplayProcessor {
+    type Output = ScanMetadata;
+
+    fn process_actions_batch(
+        &mut self,
+        actions_batch: Box<dyn EngineData>,
+        is_log_batch: bool,
+    ) -> DeltaResult<Self::Output> {
+        // Build an initial selection vector for the batch which has had the data skipping filter
+        // applied. The selection vector is further updated by the deduplication visitor to remove
+        // rows that are not valid adds.
+        let selection_vector = self.build_selection_vector(actions_batch.as_ref())?;
+        self.process_selected_actions(actions_batch.as_ref(), selection_vector, is_log_batch)
+    }
+
     fn data_skipping_filter(&self) -> Option<&DataSkippingFilter> {
         self.data_skipping_filter.as_ref()
     }
@@ -397,6 +520,48 @@ pub(crate) fn scan_action_iter(
         .process_actions_iter(action_iter)
 }
 
+/// Like [`scan_action_iter`], but serves the scan from the (already reconciled) active adds of a
+/// [`FileState`] instead of replaying the log.
+pub(crate) fn scan_file_state_iter(
+    engine: &dyn Engine,
+    file_state: Arc<FileState>,
+    logical_schema: SchemaRef,
+    transform: Option<Arc<Transform>>,
+    physical_predicate: Option<(PredicateRef, SchemaRef)>,
+) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
+    let mut processor =
+        ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform);
+    (0..file_state.batches().len())
+        .map(move |i| processor.process_file_state_batch(&file_state.batches()[i]))
+        .filter(|res| {
+            res.as_ref()
+                .map_or(true, |result| result.has_selected_rows())
+        })
+}
+
+/// Deselects every row of `actions_batch` that is not an add surviving log replay, given the file
+/// actions of newer batches in `seen_file_keys` (which is updated with the file actions of this
+/// batch if it is a log batch). Only rows selected by `selection_vector` are considered. Used to
+/// reconcile the active adds of a [`FileState`].
+pub(crate) fn select_active_adds(
+    seen_file_keys: &mut HashSet<FileActionKey>,
+    actions_batch: &dyn EngineData,
+    selection_vector: Vec<bool>,
+    is_log_batch: bool,
+) -> DeltaResult<Vec<bool>> {
+    // Without a transform, the visitor never consults the logical schema
+    let mut visitor = AddRemoveDedupVisitor::new(
+        seen_file_keys,
+        selection_vector,
+        Arc::new(StructType::new([])),
+        None,
+        None,
+        is_log_batch,
+    );
+    visitor.visit_rows_of(actions_batch)?;
+    Ok(visitor.selection_vector)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, sync::Arc};
@@ -4,19 +4,19 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use tracing::debug;
 use url::Url;
 
-use crate::actions::deletion_vector::{
-    deletion_treemap_to_bools, split_vector, DeletionVectorDescriptor,
-};
+use crate::actions::deletion_vector::{deletion_treemap_to_bools, DeletionVectorDescriptor};
+use crate::actions::visitors::SelectionVectorVisitor;
 use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
-use crate::engine_data::FilteredEngineData;
+use crate::engine_data::{FilteredEngineData, RowVisitor as _};
 use crate::expressions::transforms::ExpressionTransform;
 use crate::expressions::{ColumnName, Expression, ExpressionRef, Predicate, PredicateRef, Scalar};
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, EmptyColumnResolver};
 use crate::log_replay::HasSelectionVector;
+use crate::log_segment::LogSegment;
 use crate::scan::state::{DvInfo, Stats};
 use crate::schema::{
     ArrayType, DataType, MapType, PrimitiveType, Schema, SchemaRef, SchemaTransform, StructField,
@@ -24,13 +24,23 @@ use crate::schema::{
 };
 use crate::snapshot::Snapshot;
 use crate::table_features::ColumnMappingMode;
-use crate::{DeltaResult, Engine, EngineData, Error, FileMeta};
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, PredicateEvaluator};
 
-use self::log_replay::scan_action_iter;
+use self::limit::LimitFilter;
+use self::log_replay::{scan_action_iter, scan_file_state_iter};
 use self::state::GlobalScanState;
 
+pub use self::incremental::{
+    added_file_row_schema, removed_file_row_schema, IncrementalScanMetadata,
+};
+
+pub mod aggregates;
 pub(crate) mod data_skipping;
+pub(crate) mod file_state;
+mod incremental;
+mod limit;
 pub mod log_replay;
+pub(crate) mod row_index;
 pub mod state;
 
 /// Builder to scan a snapshot of a table.
@@ -38,6 +48,8 @@ pub struct ScanBuilder {
     snapshot: Arc<Snapshot>,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    filter_rows: bool,
+    limit: Option<u64>,
 }
 
 impl std::fmt::Debug for ScanBuilder {
@@ -45,6 +57,8 @@ impl std::fmt::Debug for ScanBuilder {
         f.debug_struct("ScanBuilder")
             .field("schema", &self.schema)
             .field("predicate", &self.predicate)
+            .field("filter_rows", &self.filter_rows)
+            .field("limit", &self.limit)
             .finish()
     }
 }
@@ -56,6 +70,8 @@ impl ScanBuilder {
             snapshot: snapshot.into(),
             schema: None,
             predicate: None,
+            filter_rows: false,
+            limit: None,
         }
     }
 
@@ -91,6 +107,33 @@ impl ScanBuilder {
         self
     }
 
+    /// Whether [`Scan::execute`] should evaluate the predicate against every row it reads, and
+    /// unselect the rows for which it is not TRUE (SQL WHERE semantics, so rows for which the
+    /// predicate is NULL are unselected). The result is folded into [`ScanResult::raw_mask`].
+    ///
+    /// By default, the predicate is only used to skip files and row groups, and callers of
+    /// [`Scan::execute`] must filter the returned rows themselves. This setting has no effect on
+    /// [`Scan::scan_metadata`].
+    pub fn with_row_filtering(mut self, filter_rows: bool) -> Self {
+        self.filter_rows = filter_rows;
+        self
+    }
+
+    /// Provide a hint that the query only needs `limit` rows. [`Scan::scan_metadata`] stops
+    /// returning files once the files it returned are guaranteed to contain at least `limit` rows
+    /// (according to their `numRecords` statistic, minus rows removed by deletion vectors).
+    ///
+    /// If the scan has a predicate, a file only counts towards the limit if its statistics prove
+    /// that every one of its rows satisfies the predicate. Files without statistics never count
+    /// towards the limit. If `limit` is `None`, this is a no-op.
+    ///
+    /// NOTE: The scan can still return more than `limit` rows, so the engine must apply the limit
+    /// itself.
+    pub fn with_limit(mut self, limit: impl Into<Option<u64>>) -> Self {
+        self.limit = limit.into();
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
@@ -106,15 +149,18 @@ impl ScanBuilder {
         )?;
 
         let physical_predicate = match self.predicate {
-            Some(predicate) => PhysicalPredicate::try_new(&predicate, &logical_schema)?,
+            Some(ref predicate) => PhysicalPredicate::try_new(predicate, &logical_schema)?,
             None => PhysicalPredicate::None,
         };
+        let row_filter = self.predicate.filter(|_| self.filter_rows);
 
         Ok(Scan {
             snapshot: self.snapshot,
             logical_schema,
             physical_schema: Arc::new(StructType::new(state_info.read_fields)),
             physical_predicate,
+            row_filter,
+            limit: self.limit,
             all_fields: Arc::new(state_info.all_fields),
             have_partition_cols: state_info.have_partition_cols,
         })
@@ -374,6 +420,9 @@ pub struct Scan {
     logical_schema: SchemaRef,
     physical_schema: SchemaRef,
     physical_predicate: PhysicalPredicate,
+    // The logical predicate to evaluate against every row read by `execute`, if requested
+    row_filter: Option<PredicateRef>,
+    limit: Option<u64>,
     all_fields: Arc<Vec<ColumnType>>,
     have_partition_cols: bool,
 }
@@ -383,6 +432,8 @@ impl std::fmt::Debug for Scan {
         f.debug_struct("Scan")
             .field("schema", &self.logical_schema)
             .field("predicate", &self.physical_predicate)
+            .field("row_filter", &self.row_filter)
+            .field("limit", &self.limit)
             .finish()
     }
 }
@@ -419,6 +470,14 @@ impl Scan {
             .collect()
     }
 
+    /// Compute the static part of the transformation. This is `None` if no transformation is
+    /// needed (currently just means no partition cols AND no column mapping but will be extended
+    /// for other transforms as we support them)
+    fn static_transform(&self) -> Option<Arc<Transform>> {
+        (self.have_partition_cols || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
+            .then(|| Arc::new(Scan::get_static_transform(&self.all_fields)))
+    }
+
     /// Get an iterator of [`ScanMetadata`]s that should be used to facilitate a scan. This handles
     /// log-replay, reconciling Add and Remove actions, and applying data skipping (if possible).
     /// Each item in the returned iterator is a struct of:
@@ -442,24 +501,44 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        // Compute the static part of the transformation. This is `None` if no transformation is
-        // needed (currently just means no partition cols AND no column mapping but will be extended
-        // for other transforms as we support them)
-        let static_transform = (self.have_partition_cols
-            || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
-            .then(|| Arc::new(Scan::get_static_transform(&self.all_fields)));
+        let static_transform = self.static_transform();
         let physical_predicate = match self.physical_predicate.clone() {
             PhysicalPredicate::StaticSkipAll => return Ok(None.into_iter().flatten()),
             PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
             PhysicalPredicate::None => None,
         };
-        let it = scan_action_iter(
-            engine,
-            self.replay_for_scan_metadata(engine)?,
-            self.logical_schema.clone(),
-            static_transform,
-            physical_predicate,
-        );
+        let mut limit_filter = self
+            .limit
+            .map(|limit| LimitFilter::new(limit, self.physical_predicate()));
+        // Serve the scan from the snapshot's cached file state, if it has one
+        let it = match self.snapshot.file_state() {
+            Some(file_state) => Either::Left(scan_file_state_iter(
+                engine,
+                file_state.clone(),
+                self.logical_schema.clone(),
+                static_transform,
+                physical_predicate,
+            )),
+            None => Either::Right(scan_action_iter(
+                engine,
+                self.replay_for_scan_metadata(engine)?,
+                self.logical_schema.clone(),
+                static_transform,
+                physical_predicate,
+            )),
+        };
+        let it = it.map_while(move |scan_metadata| {
+            let Some(ref mut limit_filter) = limit_filter else {
+                return Some(scan_metadata);
+            };
+            if limit_filter.is_satisfied() {
+                return None;
+            }
+            Some(scan_metadata.and_then(|mut scan_metadata| {
+                limit_filter.apply(&mut scan_metadata)?;
+                Ok(scan_metadata)
+            }))
+        });
         Ok(Some(it).into_iter().flatten())
     }
 
@@ -468,17 +547,7 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<(Box<dyn EngineData>, bool)>> + Send> {
-        let commit_read_schema = get_log_schema().project(&[ADD_NAME, REMOVE_NAME])?;
-        let checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
-
-        // NOTE: We don't pass any meta-predicate because we expect no meaningful row group skipping
-        // when ~every checkpoint file will contain the adds and removes we are looking for.
-        self.snapshot.log_segment().read_actions(
-            engine,
-            commit_read_schema,
-            checkpoint_read_schema,
-            None,
-        )
+        replay_file_actions(self.snapshot.log_segment(), engine)
     }
 
     /// Get global state that is valid for the entire scan. This is somewhat expensive so should
@@ -534,6 +603,17 @@ impl Scan {
 
         let global_state = Arc::new(self.global_scan_state());
         let table_root = self.snapshot.table_root().clone();
+        let physical_predicate = self.physical_predicate();
+        // Rows are kept only if the predicate is TRUE, so a NULL result must become FALSE.
+        let row_filter = self.row_filter.as_ref().map(|predicate| {
+            let predicate = Predicate::not(Predicate::distinct(
+                Expression::from(predicate.as_ref().clone()),
+                Expression::literal(true),
+            ));
+            engine
+                .evaluation_handler()
+                .new_predicate_evaluator(self.logical_schema.clone(), predicate)
+        });
 
         let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
         let scan_files_iter = scan_metadata_iter
@@ -549,7 +629,7 @@ impl Scan {
             .map(move |scan_file| -> DeltaResult<_> {
                 let scan_file = scan_file?;
                 let file_path = table_root.join(&scan_file.path)?;
-                let mut selection_vector = scan_file
+                let selection_vector = scan_file
                     .dv_info
                     .get_selection_vector(engine.as_ref(), &table_root)?;
                 let meta = FileMeta {
@@ -565,18 +645,23 @@ impl Scan {
                 // columns. So we cannot safely assume that all column references are valid. See
                 // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
                 //
-                // TODO(#860): we disable predicate pushdown until we support row indexes.
-                let read_result_iter = engine.parquet_handler().read_parquet_files(
-                    &[meta],
-                    global_state.physical_schema.clone(),
-                    None,
+                // Row group skipping means we can't line up the selection vector with the rows we
+                // read by position, so files with a deletion vector are read with row indexes.
+                let read_result_iter = row_index::read_with_selection_vector(
+                    engine.as_ref(),
+                    meta,
+                    &global_state.physical_schema,
+                    physical_predicate.clone(),
+                    selection_vector,
+                    true,
                 )?;
 
                 // Arc clones
                 let engine = engine.clone();
                 let global_state = global_state.clone();
+                let row_filter = row_filter.clone();
                 Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
-                    let read_result = read_result?;
+                    let (read_result, mut raw_mask) = read_result?;
                     // transform the physical data into the correct logical form
                     let logical = state::transform_to_logical(
                         engine.as_ref(),
@@ -585,19 +670,17 @@ impl Scan {
                         &global_state.logical_schema,
                         &scan_file.transform,
                     );
-                    let len = logical.as_ref().map_or(0, |res| res.len());
-                    // need to split the dv_mask. what's left in dv_mask covers this result, and rest
-                    // will cover the following results. we `take()` out of `selection_vector` to avoid
-                    // trying to return a captured variable. We're going to reassign `selection_vector`
-                    // to `rest` in a moment anyway
-                    let mut sv = selection_vector.take();
-                    let rest = split_vector(sv.as_mut(), len, None);
-                    let result = ScanResult {
+                    if let (Some(row_filter), Ok(logical)) = (&row_filter, &logical) {
+                        raw_mask = Some(apply_row_filter(
+                            row_filter.as_ref(),
+                            logical.as_ref(),
+                            raw_mask,
+                        )?);
+                    }
+                    Ok(ScanResult {
                         raw_data: logical,
-                        raw_mask: sv,
-                    };
-                    selection_vector = rest;
-                    Ok(result)
+                        raw_mask,
+                    })
                 }))
             })
             // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
@@ -608,6 +691,40 @@ impl Scan {
     }
 }
 
+/// Evaluates `row_filter` on `data` and combines the result with `raw_mask`. A row is selected
+/// only if both the mask (if any) and the filter select it.
+fn apply_row_filter(
+    row_filter: &dyn PredicateEvaluator,
+    data: &dyn EngineData,
+    raw_mask: Option<Vec<bool>>,
+) -> DeltaResult<Vec<bool>> {
+    let mut visitor = SelectionVectorVisitor::default();
+    visitor.visit_rows_of(row_filter.evaluate(data)?.as_ref())?;
+    let mut selection_vector = visitor.selection_vector;
+    if let Some(raw_mask) = raw_mask {
+        // Rows past the end of the mask are selected
+        for (selected, mask) in selection_vector.iter_mut().zip(raw_mask) {
+            *selected &= mask;
+        }
+    }
+    Ok(selection_vector)
+}
+
+/// Read the add and remove actions of the log segment (newest first) for log replay of its file
+/// actions. Each item is a batch of actions, and whether it was read from a commit (as opposed to a
+/// checkpoint).
+pub(crate) fn replay_file_actions(
+    log_segment: &LogSegment,
+    engine: &dyn Engine,
+) -> DeltaResult<impl Iterator<Item = DeltaResult<(Box<dyn EngineData>, bool)>> + Send> {
+    let commit_read_schema = get_log_schema().project(&[ADD_NAME, REMOVE_NAME])?;
+    let checkpoint_read_schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
+
+    // NOTE: We don't pass any meta-predicate because we expect no meaningful row group skipping
+    // when ~every checkpoint file will contain the adds and removes we are looking for.
+    log_segment.read_actions(engine, commit_read_schema, checkpoint_read_schema, None)
+}
+
 /// Get the schema that scan rows (from [`Scan::scan_metadata`]) will be returned with.
 ///
 /// It is:
@@ -1163,11 +1280,7 @@ mod tests {
         let data: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
         assert_eq!(data.len(), 1);
 
-        // TODO(#860): we disable predicate pushdown until we support row indexes. Update this test
-        // accordingly after support is reintroduced.
-        //
-        // Effective predicate pushdown, so no data files should be returned. BUT since we disabled
-        // predicate pushdown, the one data file is still returned.
+        // Effective predicate pushdown, so no data files should be returned.
         let predicate = Arc::new(int_col.lt(value));
         let scan = snapshot
             .scan_builder()
@@ -1175,7 +1288,7 @@ mod tests {
             .build()
             .unwrap();
         let data: Vec<_> = scan.execute(engine).unwrap().try_collect().unwrap();
-        assert_eq!(data.len(), 1);
+        assert_eq!(data.len(), 0);
     }
 
     #[test]
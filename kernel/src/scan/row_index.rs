@@ -0,0 +1,125 @@
+//! Reading data files with predicate pushdown while applying deletion vectors. Row group skipping
+//! means rows can no longer be matched to selection vector entries by their position in the read
+//! data, so files with a deletion vector are read with an extra row index metadata column.
+
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::deletion_vector::split_vector;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{ColumnName, Expression, PredicateRef};
+use crate::schema::{
+    ColumnNamesAndTypes, DataType, MetadataColumnSpec, SchemaRef, StructField, StructType,
+};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta};
+
+/// Name of the row index metadata column appended to the read schema of files with a deletion
+/// vector.
+pub(crate) const ROW_INDEX_COLUMN_NAME: &str = "_delta_kernel_row_index";
+
+/// A batch of physical data, along with the selection vector entries covering its rows (if any).
+pub(crate) type SelectedBatch = (Box<dyn EngineData>, Option<Vec<bool>>);
+
+/// Reads `file` with `physical_schema`, pushing `predicate` down to the engine's
+/// [`crate::ParquetHandler`], and pairs each batch it returns with the entries of
+/// `selection_vector` covering the batch's rows. Rows past the end of `selection_vector` are
+/// treated as `default_selected`.
+pub(crate) fn read_with_selection_vector(
+    engine: &dyn Engine,
+    file: FileMeta,
+    physical_schema: &SchemaRef,
+    predicate: Option<PredicateRef>,
+    selection_vector: Option<Vec<bool>>,
+    default_selected: bool,
+) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<SelectedBatch>> + Send>> {
+    let parquet_handler = engine.parquet_handler();
+    let Some(selection_vector) = selection_vector else {
+        let read_result_iter =
+            parquet_handler.read_parquet_files(&[file], physical_schema.clone(), predicate)?;
+        return Ok(Box::new(read_result_iter.map(|data| Ok((data?, None)))));
+    };
+
+    if physical_schema.fields_len() == 0 {
+        // Nothing to project the row index column away from without losing the row count, so
+        // read every row and split the selection vector positionally instead.
+        let read_result_iter =
+            parquet_handler.read_parquet_files(&[file], physical_schema.clone(), None)?;
+        let mut selection_vector = Some(selection_vector);
+        return Ok(Box::new(read_result_iter.map(move |data| {
+            let data = data?;
+            let mut sv = selection_vector.take();
+            selection_vector = split_vector(sv.as_mut(), data.len(), Some(default_selected));
+            Ok((data, sv))
+        })));
+    }
+
+    let read_schema = Arc::new(StructType::new(physical_schema.fields().cloned().chain([
+        StructField::create_metadata_column(ROW_INDEX_COLUMN_NAME, MetadataColumnSpec::RowIndex),
+    ])));
+    // Projects the row index column back out of the read data
+    let output_expr = Expression::struct_from(
+        physical_schema
+            .fields()
+            .map(|field| Expression::column([field.name()])),
+    );
+    let evaluator = engine.evaluation_handler().new_expression_evaluator(
+        read_schema.clone(),
+        output_expr,
+        DataType::from(physical_schema.clone()),
+    );
+    let read_result_iter = parquet_handler.read_parquet_files(&[file], read_schema, predicate)?;
+    Ok(Box::new(read_result_iter.map(move |data| {
+        let data = data?;
+        let mut visitor = RowIndexVisitor::default();
+        visitor.visit_rows_of(data.as_ref())?;
+        let mask = visitor
+            .row_indexes
+            .into_iter()
+            .map(|row_index| -> DeltaResult<_> {
+                let row_index = usize::try_from(row_index)
+                    .map_err(|_| Error::generic(format!("Invalid row index: {row_index}")))?;
+                Ok(selection_vector
+                    .get(row_index)
+                    .copied()
+                    .unwrap_or(default_selected))
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        let data = evaluator.evaluate(data.as_ref())?;
+        Ok((data, Some(mask)))
+    })))
+}
+
+/// Collects the values of the row index column of a batch.
+#[derive(Default)]
+struct RowIndexVisitor {
+    row_indexes: Vec<i64>,
+}
+
+impl RowVisitor for RowIndexVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![ColumnName::new([ROW_INDEX_COLUMN_NAME])],
+                vec![DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of RowIndexVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        self.row_indexes.reserve(row_count);
+        for i in 0..row_count {
+            let row_index: i64 = getters[0].get(i, ROW_INDEX_COLUMN_NAME)?;
+            self.row_indexes.push(row_index);
+        }
+        Ok(())
+    }
+}
@@ -24,11 +24,12 @@ use std::collections::{HashMap, HashSet};
 
 use crate::utils::require;
 
-use super::{DataType, StructField, StructType};
+use super::{
+    ColumnMetadataKey, ColumnName, DataType, MetadataValue, PrimitiveType, StructField, StructType,
+};
 
 /// The nullability flag of a schema's field. This can be compared with a read schema field's
 /// nullability flag using [`Nullable::can_read_as`].
-#[allow(unused)]
 #[derive(Clone, Copy)]
 pub(crate) struct Nullable(bool);
 
@@ -50,14 +51,10 @@ pub(crate) enum Error {
 }
 
 /// A [`std::result::Result`] that has the schema comparison [`Error`] as the error variant.
-#[allow(unused)]
 pub(crate) type SchemaComparisonResult = Result<(), Error>;
 
 /// Represents a schema compatibility check for the type. If `self` can be read as `read_type`,
 /// this function returns `Ok(())`. Otherwise, this function returns `Err`.
-///
-/// TODO (Oussama): Remove the `allow(unused)` once this is used in CDF.
-#[allow(unused)]
 pub(crate) trait SchemaComparison {
     fn can_read_as(&self, read_type: &Self) -> SchemaComparisonResult;
 }
@@ -173,9 +170,204 @@ impl SchemaComparison for DataType {
     }
 }
 
+/// A single difference between two versions of a table schema, as reported by
+/// [`Snapshot::diff`].
+///
+/// Fields are matched across the two schemas by their column mapping id when both sides have
+/// one, and by case-insensitive name otherwise. Without column mapping a rename is therefore
+/// indistinguishable from dropping a column and adding another. Changes to a matched field are
+/// reported against its path in the new schema.
+///
+/// [`Snapshot::diff`]: crate::snapshot::Snapshot::diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A column was added.
+    Added { path: ColumnName, nullable: bool },
+    /// A column was dropped. The path refers to the old schema.
+    Dropped { path: ColumnName },
+    /// A column kept its identity (column mapping id) but changed its name.
+    Renamed { from: ColumnName, to: ColumnName },
+    /// A column's type changed in a way that can still read every value of the old type, e.g.
+    /// `integer` to `long` or `decimal(10,2)` to `decimal(12,2)`.
+    Widened {
+        path: ColumnName,
+        from: DataType,
+        to: DataType,
+    },
+    /// A column's type changed in a way that cannot read every value of the old type.
+    TypeChanged {
+        path: ColumnName,
+        from: DataType,
+        to: DataType,
+    },
+    /// A non-nullable column became nullable.
+    NullabilityRelaxed { path: ColumnName },
+    /// A nullable column became non-nullable.
+    NullabilityTightened { path: ColumnName },
+}
+
+impl SchemaChange {
+    /// Returns `true` if data written with the old schema can still be read with the new one,
+    /// i.e. this change only adds a nullable column, widens a type or relaxes nullability.
+    pub fn is_compatible(&self) -> bool {
+        match self {
+            Self::Added { nullable, .. } => *nullable,
+            Self::Widened { .. } | Self::NullabilityRelaxed { .. } => true,
+            Self::Dropped { .. }
+            | Self::Renamed { .. }
+            | Self::TypeChanged { .. }
+            | Self::NullabilityTightened { .. } => false,
+        }
+    }
+}
+
+/// Returns `true` if `from` can be widened to `to` according to the [type widening] rules of
+/// the Delta protocol.
+///
+/// [type widening]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#type-widening
+pub(crate) fn is_widening(from: &PrimitiveType, to: &PrimitiveType) -> bool {
+    use PrimitiveType::*;
+    match (from, to) {
+        (Byte, Short | Integer | Long | Double) => true,
+        (Short, Integer | Long | Double) => true,
+        (Integer, Long | Double) => true,
+        (Float, Double) => true,
+        (Date, TimestampNtz) => true,
+        (Byte | Short | Integer, Decimal(to)) => to.precision() - to.scale() >= 10,
+        (Long, Decimal(to)) => to.precision() - to.scale() >= 20,
+        (Decimal(from), Decimal(to)) => {
+            to.scale() >= from.scale()
+                && to.precision() - to.scale() >= from.precision() - from.scale()
+        }
+        _ => false,
+    }
+}
+
+/// Computes the [`SchemaChange`]s that turn `old` into `new`. Nested structs are compared field
+/// by field; other nested types (arrays and maps) are compared as a whole using
+/// [`SchemaComparison::can_read_as`].
+pub(crate) fn diff_schemas(old: &StructType, new: &StructType) -> Vec<SchemaChange> {
+    let mut changes = vec![];
+    diff_struct(
+        &ColumnName::new::<&str>([]),
+        &ColumnName::new::<&str>([]),
+        old,
+        new,
+        &mut changes,
+    );
+    changes
+}
+
+/// The identity of a field used to match it across two schemas.
+fn field_key(field: &StructField, use_ids: bool) -> String {
+    match field.get_config_value(&ColumnMetadataKey::ColumnMappingId) {
+        Some(MetadataValue::Number(id)) if use_ids => id.to_string(),
+        _ => field.name().to_lowercase(),
+    }
+}
+
+fn has_column_mapping_ids(schema: &StructType) -> bool {
+    schema.fields().next().is_some()
+        && schema.fields().all(|field| {
+            field
+                .get_config_value(&ColumnMetadataKey::ColumnMappingId)
+                .is_some()
+        })
+}
+
+fn diff_struct(
+    old_path: &ColumnName,
+    new_path: &ColumnName,
+    old: &StructType,
+    new: &StructType,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let use_ids = has_column_mapping_ids(old) && has_column_mapping_ids(new);
+    let new_fields: HashMap<String, &StructField> = new
+        .fields()
+        .map(|field| (field_key(field, use_ids), field))
+        .collect();
+    let old_keys: HashSet<String> = old
+        .fields()
+        .map(|field| field_key(field, use_ids))
+        .collect();
+
+    for old_field in old.fields() {
+        let old_field_path = old_path.join(&ColumnName::new([old_field.name()]));
+        let Some(new_field) = new_fields.get(&field_key(old_field, use_ids)) else {
+            changes.push(SchemaChange::Dropped {
+                path: old_field_path,
+            });
+            continue;
+        };
+        let new_field_path = new_path.join(&ColumnName::new([new_field.name()]));
+        if old_field.name() != new_field.name() {
+            changes.push(SchemaChange::Renamed {
+                from: old_field_path.clone(),
+                to: new_field_path.clone(),
+            });
+        }
+        diff_field(
+            old_field_path,
+            new_field_path,
+            old_field,
+            new_field,
+            changes,
+        );
+    }
+    for new_field in new.fields() {
+        if !old_keys.contains(&field_key(new_field, use_ids)) {
+            changes.push(SchemaChange::Added {
+                path: new_path.join(&ColumnName::new([new_field.name()])),
+                nullable: new_field.is_nullable(),
+            });
+        }
+    }
+}
+
+fn diff_field(
+    old_path: ColumnName,
+    new_path: ColumnName,
+    old: &StructField,
+    new: &StructField,
+    changes: &mut Vec<SchemaChange>,
+) {
+    match (old.is_nullable(), new.is_nullable()) {
+        (false, true) => changes.push(SchemaChange::NullabilityRelaxed {
+            path: new_path.clone(),
+        }),
+        (true, false) => changes.push(SchemaChange::NullabilityTightened {
+            path: new_path.clone(),
+        }),
+        _ => {}
+    }
+    let (from, to) = (old.data_type(), new.data_type());
+    let widened = match (from, to) {
+        (DataType::Struct(from), DataType::Struct(to)) => {
+            return diff_struct(&old_path, &new_path, from, to, changes);
+        }
+        _ if from == to => return,
+        (DataType::Primitive(a), DataType::Primitive(b)) => is_widening(a, b),
+        _ => from.can_read_as(to).is_ok(),
+    };
+    let (from, to) = (from.clone(), to.clone());
+    changes.push(match widened {
+        true => SchemaChange::Widened {
+            path: new_path,
+            from,
+            to,
+        },
+        false => SchemaChange::TypeChanged {
+            path: new_path,
+            from,
+            to,
+        },
+    });
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::schema::compare::{Error, SchemaComparison};
+    use crate::schema::compare::{is_widening, Error, SchemaComparison};
     use crate::schema::{ArrayType, DataType, MapType, StructField, StructType};
 
     #[test]
@@ -398,4 +590,21 @@ mod tests {
             Err(Error::InvalidSchema)
         ));
     }
+
+    #[test]
+    fn test_is_widening() {
+        use crate::schema::{DecimalType, PrimitiveType::*};
+        let decimal = |p, s| Decimal(DecimalType::try_new(p, s).unwrap());
+        assert!(is_widening(&Byte, &Integer));
+        assert!(is_widening(&Integer, &Double));
+        assert!(is_widening(&Date, &TimestampNtz));
+        assert!(is_widening(&Integer, &decimal(10, 0)));
+        assert!(!is_widening(&Integer, &decimal(10, 1)));
+        assert!(is_widening(&Long, &decimal(22, 2)));
+        assert!(is_widening(&decimal(10, 2), &decimal(12, 4)));
+        assert!(!is_widening(&decimal(10, 2), &decimal(10, 4)));
+        assert!(!is_widening(&Long, &Integer));
+        assert!(!is_widening(&Long, &Double));
+        assert!(!is_widening(&Date, &Timestamp));
+    }
 }
@@ -17,6 +17,8 @@ use delta_kernel_derive::internal_api;
 
 pub(crate) mod compare;
 
+pub use compare::SchemaChange;
+
 pub type Schema = StructType;
 pub type SchemaRef = Arc<StructType>;
 
@@ -84,6 +86,7 @@ pub enum ColumnMetadataKey {
     IdentityHighWaterMark,
     IdentityAllowExplicitInsert,
     Invariants,
+    MetadataSpec,
 }
 
 impl AsRef<str> for ColumnMetadataKey {
@@ -97,6 +100,52 @@ impl AsRef<str> for ColumnMetadataKey {
             Self::IdentityStart => "delta.identity.start",
             Self::IdentityStep => "delta.identity.step",
             Self::Invariants => "delta.invariants",
+            Self::MetadataSpec => "delta.metadataSpec",
+        }
+    }
+}
+
+/// Kernel-defined metadata columns that a [`crate::ParquetHandler`] must populate itself, rather
+/// than reading them from the file. Such columns are requested by adding a field created with
+/// [`StructField::create_metadata_column`] to the read schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataColumnSpec {
+    /// The 0-based physical position of each row within the file it was read from.
+    RowIndex,
+}
+
+impl MetadataColumnSpec {
+    /// The value stored under [`ColumnMetadataKey::MetadataSpec`] to identify this column.
+    pub fn text_value(&self) -> &'static str {
+        match self {
+            Self::RowIndex => "row_index",
+        }
+    }
+
+    /// The data type of this metadata column.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::RowIndex => DataType::LONG,
+        }
+    }
+
+    /// Whether this metadata column can contain nulls.
+    pub fn nullable(&self) -> bool {
+        match self {
+            Self::RowIndex => false,
+        }
+    }
+}
+
+impl TryFrom<&str> for MetadataColumnSpec {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "row_index" => Ok(Self::RowIndex),
+            _ => Err(Error::generic(format!(
+                "Unknown metadata column spec: {value}"
+            ))),
         }
     }
 }
@@ -146,10 +195,30 @@ impl StructField {
         self
     }
 
+    /// Creates a new metadata column of the given kind. The column is populated by the
+    /// [`crate::ParquetHandler`] instead of being read from the file.
+    pub fn create_metadata_column(name: impl Into<String>, spec: MetadataColumnSpec) -> Self {
+        Self::new(name, spec.data_type(), spec.nullable())
+            .with_metadata([(ColumnMetadataKey::MetadataSpec.as_ref(), spec.text_value())])
+    }
+
     pub fn get_config_value(&self, key: &ColumnMetadataKey) -> Option<&MetadataValue> {
         self.metadata.get(key.as_ref())
     }
 
+    /// Returns the kind of metadata column this field represents, if any.
+    pub fn get_metadata_column_spec(&self) -> Option<MetadataColumnSpec> {
+        match self.get_config_value(&ColumnMetadataKey::MetadataSpec) {
+            Some(MetadataValue::String(spec)) => MetadataColumnSpec::try_from(spec.as_str()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether this field is a kernel-defined metadata column.
+    pub fn is_metadata_column(&self) -> bool {
+        self.get_metadata_column_spec().is_some()
+    }
+
     /// Get the physical name for this field as it should be read from parquet.
     ///
     /// NOTE: Caller affirms that the schema was already validated by
@@ -1316,6 +1385,23 @@ mod tests {
         assert_eq!(schema.fields_len(), 2);
     }
 
+    #[test]
+    fn test_metadata_column() {
+        let field = StructField::create_metadata_column("row_index", MetadataColumnSpec::RowIndex);
+        assert_eq!(field.data_type(), &DataType::LONG);
+        assert!(!field.is_nullable());
+        assert!(field.is_metadata_column());
+        assert_eq!(
+            field.get_metadata_column_spec(),
+            Some(MetadataColumnSpec::RowIndex)
+        );
+
+        let field = StructField::nullable("row_index", DataType::LONG);
+        assert!(!field.is_metadata_column());
+        let field = field.with_metadata([(ColumnMetadataKey::MetadataSpec.as_ref(), "unknown")]);
+        assert_eq!(field.get_metadata_column_spec(), None);
+    }
+
     #[test]
     fn test_has_invariants() {
         // Schema with no invariants
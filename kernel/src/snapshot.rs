@@ -1,13 +1,20 @@
 //! In-memory representation of snapshots of tables (snapshot is a table at given point in time, it
 //! has schema etc.)
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
-use crate::actions::domain_metadata::domain_metadata_configuration;
-use crate::actions::set_transaction::SetTransactionScanner;
+use crate::actions::domain_metadata::{
+    clustering_columns, domain_metadata_configuration, user_domain_metadata_configurations,
+};
+use crate::actions::set_transaction::{txn_expiration_timestamp, SetTransactionScanner};
 use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
 use crate::checkpoint::CheckpointWriter;
-use crate::log_segment::{self, LogSegment};
+use crate::crc::read_version_checksum;
+use crate::expressions::ColumnName;
+use crate::log_compaction::LogCompactionWriter;
+use crate::log_segment::{self, ListedLogFiles, LogSegment};
+use crate::scan::file_state::FileState;
 use crate::scan::ScanBuilder;
 use crate::schema::{Schema, SchemaRef};
 use crate::table_configuration::TableConfiguration;
@@ -16,26 +23,52 @@ use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Engine, Error, StorageHandler, Version};
 use delta_kernel_derive::internal_api;
 
+use itertools::Itertools;
+use md5::{Digest, Md5};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 use url::Url;
 
+mod diff;
+
+pub use diff::{PartitionColumnsChange, PropertyChange, ProtocolChange, SnapshotDiff};
+
 /// Name of the _last_checkpoint file that provides metadata about the last checkpoint
 /// created for the table. This file is used as a hint for the engine to quickly locate
 /// the latest checkpoint without a full directory listing.
 pub(crate) const LAST_CHECKPOINT_FILE_NAME: &str = "_last_checkpoint";
 
+/// The size of a table at a given version. See [`Snapshot::table_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSize {
+    /// The sum of the sizes of all active files in the table, in bytes
+    pub size_in_bytes: i64,
+    /// The number of active files in the table
+    pub num_files: i64,
+}
+
 // TODO expose methods for accessing the files of a table (with file pruning).
 /// In-memory representation of a specific snapshot of a Delta table. While a `DeltaTable` exists
 /// throughout time, `Snapshot`s represent a view of a table at a specific point in time; they
 /// have a defined schema (which may change over time for any given table), specific version, and
 /// frozen log segment.
-#[derive(PartialEq, Eq)]
 pub struct Snapshot {
     log_segment: LogSegment,
     table_configuration: TableConfiguration,
+    /// The cached file state of the snapshot, if any. See [`Snapshot::cache_file_state`].
+    file_state: OnceLock<Arc<FileState>>,
 }
 
+// The cached file state is derived from the log segment, so it is not part of snapshot equality
+impl PartialEq for Snapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.log_segment == other.log_segment
+            && self.table_configuration == other.table_configuration
+    }
+}
+
+impl Eq for Snapshot {}
+
 impl Drop for Snapshot {
     fn drop(&mut self) {
         debug!("Dropping snapshot");
@@ -57,6 +90,7 @@ impl Snapshot {
         Self {
             log_segment,
             table_configuration,
+            file_state: OnceLock::new(),
         }
     }
 
@@ -105,10 +139,31 @@ impl Snapshot {
     /// - `engine`: Implementation of [`Engine`] apis.
     /// - `version`: target version of the [`Snapshot`]. None will create a snapshot at the latest
     ///   version of the table.
+    ///
+    /// If the existing snapshot has a cached file state (see [`Snapshot::cache_file_state`]), the
+    /// new snapshot gets one too, computed by applying only the new commits to the existing one.
     pub fn try_new_from(
         existing_snapshot: Arc<Snapshot>,
         engine: &dyn Engine,
         version: impl Into<Option<Version>>,
+    ) -> DeltaResult<Arc<Self>> {
+        let file_state = existing_snapshot.file_state().cloned();
+        let snapshot = Self::try_new_from_impl(existing_snapshot, engine, version)?;
+        if let Some(file_state) = file_state {
+            if snapshot.file_state().is_none() {
+                if let Some(new_file_state) = file_state.try_refresh(&snapshot, engine)? {
+                    // Another thread may have cached the file state in the meantime, which is fine
+                    let _ = snapshot.file_state.set(Arc::new(new_file_state));
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    fn try_new_from_impl(
+        existing_snapshot: Arc<Snapshot>,
+        engine: &dyn Engine,
+        version: impl Into<Option<Version>>,
     ) -> DeltaResult<Arc<Self>> {
         let old_log_segment = &existing_snapshot.log_segment;
         let old_version = existing_snapshot.version();
@@ -134,18 +189,17 @@ impl Snapshot {
         let listing_start = old_log_segment.checkpoint_version.unwrap_or(0) + 1;
 
         // Check for new commits
-        let (new_ascending_commit_files, checkpoint_parts) =
-            log_segment::list_log_files_with_version(
-                storage.as_ref(),
-                &log_root,
-                Some(listing_start),
-                new_version,
-            )?;
+        let listed_files = log_segment::list_log_files_with_version(
+            storage.as_ref(),
+            &log_root,
+            Some(listing_start),
+            new_version,
+        )?;
 
         // NB: we need to check both checkpoints and commits since we filter commits at and below
         // the checkpoint version. Example: if we have a checkpoint + commit at version 1, the log
         // listing above will only return the checkpoint and not the commit.
-        if new_ascending_commit_files.is_empty() && checkpoint_parts.is_empty() {
+        if listed_files.is_empty() {
             match new_version {
                 Some(new_version) if new_version != old_version => {
                     // No new commits, but we are looking for a new version
@@ -163,12 +217,7 @@ impl Snapshot {
 
         // create a log segment just from existing_checkpoint.version -> new_version
         // OR could be from 1 -> new_version
-        let mut new_log_segment = LogSegment::try_new(
-            new_ascending_commit_files,
-            checkpoint_parts,
-            log_root.clone(),
-            new_version,
-        )?;
+        let mut new_log_segment = LogSegment::try_new(listed_files, log_root.clone(), new_version)?;
 
         let new_end_version = new_log_segment.end_version;
         if new_end_version < old_version {
@@ -205,9 +254,35 @@ impl Snapshot {
         // 1. new logsegment             [empty] -> caught above
         // 2. new logsegment             [commit4]
         // 3. new logsegment             [checkpoint2-commit3] -> caught above
+        //
+        // The combined log segment keeps every newly listed compaction file, plus those of the old
+        // log segment the new listing could not see. The listing starts just after the old
+        // checkpoint, so that is only ever a compaction starting at version 0 of a log segment
+        // without a checkpoint. Only compactions entirely after the old version are relevant to
+        // the P+M replay, though.
+        let ascending_compaction_files: Vec<_> = old_log_segment
+            .ascending_compaction_files
+            .iter()
+            .take_while(|log_path| log_path.version < listing_start)
+            .chain(&new_log_segment.ascending_compaction_files)
+            .cloned()
+            .collect();
+        //
+        // Similarly, the newest checksum file of either log segment is usable by the combined one,
+        // but the P+M replay can only start from a checksum at or after the old version.
+        let latest_crc_file = new_log_segment
+            .latest_crc_file
+            .clone()
+            .or_else(|| old_log_segment.latest_crc_file.clone());
         new_log_segment
             .ascending_commit_files
             .retain(|log_path| old_version < log_path.version);
+        new_log_segment
+            .ascending_compaction_files
+            .retain(|log_path| old_version < log_path.version);
+        new_log_segment
+            .latest_crc_file
+            .take_if(|log_path| log_path.version < old_version);
 
         // we have new commits and no new checkpoint: we replay new commits for P+M and then
         // create a new snapshot by combining LogSegments and building a new TableConfiguration
@@ -223,12 +298,13 @@ impl Snapshot {
         ascending_commit_files.extend(new_log_segment.ascending_commit_files);
         // we can pass in just the old checkpoint parts since by the time we reach this line, we
         // know there are no checkpoints in the new log segment.
-        let combined_log_segment = LogSegment::try_new(
+        let listed_files = ListedLogFiles {
             ascending_commit_files,
-            old_log_segment.checkpoint_parts.clone(),
-            log_root,
-            new_version,
-        )?;
+            ascending_compaction_files,
+            checkpoint_parts: old_log_segment.checkpoint_parts.clone(),
+            latest_crc_file,
+        };
+        let combined_log_segment = LogSegment::try_new(listed_files, log_root, new_version)?;
         Ok(Arc::new(Snapshot::new(
             combined_log_segment,
             table_configuration,
@@ -244,10 +320,38 @@ impl Snapshot {
         let (metadata, protocol) = log_segment.read_metadata(engine)?;
         let table_configuration =
             TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
-        Ok(Self {
-            log_segment,
-            table_configuration,
-        })
+        Ok(Self::new(log_segment, table_configuration))
+    }
+
+    /// Materializes the active files of this snapshot (with their deletion vectors and stats) into
+    /// a cache attached to the snapshot, so that later scans of the snapshot are served from the
+    /// cache instead of replaying the log. Snapshots created from this one with
+    /// [`Snapshot::try_new_from`] refresh the cache incrementally, by applying only the new commits.
+    ///
+    /// The cache holds the batches of log actions that contain active files, so its memory use is
+    /// bounded by `max_rows`, the maximum number of action rows it may hold. Returns `false` (and
+    /// caches nothing) if the file state would exceed that bound. Returns `true` if the file state
+    /// is cached, including if it was already cached.
+    pub fn cache_file_state(&self, engine: &dyn Engine, max_rows: usize) -> DeltaResult<bool> {
+        if self.file_state.get().is_some() {
+            return Ok(true);
+        }
+        let Some(file_state) = FileState::try_new(self, engine, max_rows)? else {
+            return Ok(false);
+        };
+        // Another thread may have cached the file state in the meantime, which is fine
+        let _ = self.file_state.set(Arc::new(file_state));
+        Ok(true)
+    }
+
+    /// Whether this snapshot has a cached file state. See [`Snapshot::cache_file_state`].
+    pub fn has_cached_file_state(&self) -> bool {
+        self.file_state.get().is_some()
+    }
+
+    /// The cached file state of this snapshot, if any
+    pub(crate) fn file_state(&self) -> Option<&Arc<FileState>> {
+        self.file_state.get()
     }
 
     /// Creates a [`CheckpointWriter`] for generating a checkpoint from this snapshot.
@@ -258,6 +362,30 @@ impl Snapshot {
         CheckpointWriter::try_new(self)
     }
 
+    /// Creates a [`LogCompactionWriter`] for compacting the commits in the (inclusive) version
+    /// range `[start_version, end_version]` of this snapshot into a single log compaction file.
+    ///
+    /// See the [`crate::log_compaction`] module documentation for more details.
+    pub fn log_compaction_writer(
+        self: Arc<Self>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<LogCompactionWriter> {
+        LogCompactionWriter::try_new(self, start_version, end_version)
+    }
+
+    /// Returns the total size and number of active files of the table at this snapshot's version,
+    /// as recorded in the version checksum (`<version>.crc`) file. This does not require log
+    /// replay. Returns `None` if the table has no valid checksum file for this version.
+    pub fn table_size(&self, engine: &dyn Engine) -> DeltaResult<Option<TableSize>> {
+        let storage = engine.storage_handler();
+        let checksum = read_version_checksum(storage.as_ref(), self.table_root(), self.version())?;
+        Ok(checksum.map(|checksum| TableSize {
+            size_in_bytes: checksum.table_size_bytes,
+            num_files: checksum.num_files,
+        }))
+    }
+
     /// Log segment this snapshot uses
     #[internal_api]
     pub(crate) fn log_segment(&self) -> &LogSegment {
@@ -285,7 +413,6 @@ impl Snapshot {
     }
 
     /// Table [`Protocol`] at this `Snapshot`s version.
-    #[allow(dead_code)]
     #[internal_api]
     pub(crate) fn protocol(&self) -> &Protocol {
         self.table_configuration.protocol()
@@ -309,6 +436,13 @@ impl Snapshot {
         self.table_configuration.column_mapping_mode()
     }
 
+    /// Compare this `Snapshot` with `other`, reporting the schema, table property, protocol and
+    /// partition column changes needed to go from this snapshot to `other`. This does not read
+    /// the log, so `other` may be older or newer than `self`.
+    pub fn diff(&self, other: &Snapshot) -> SnapshotDiff {
+        SnapshotDiff::new(self, other)
+    }
+
     /// Create a [`ScanBuilder`] for an `Arc<Snapshot>`.
     pub fn scan_builder(self: Arc<Self>) -> ScanBuilder {
         ScanBuilder::new(self)
@@ -319,19 +453,43 @@ impl Snapshot {
         ScanBuilder::new(self)
     }
 
-    /// Fetch the latest version of the provided `application_id` for this snapshot.
+    /// Fetch the latest version of the provided `application_id` for this snapshot. Returns `None`
+    /// if the application id has no transaction, or if its latest transaction expired according
+    /// to the table's `delta.setTransactionRetentionDuration`.
     ///
     /// Note that this method performs log replay (fetches and processes metadata from storage).
-    // TODO: add a get_app_id_versions to fetch all at once using SetTransactionScanner::get_all
+    /// To look up multiple application ids, use [`Snapshot::get_app_id_versions`] instead.
     pub fn get_app_id_version(
         self: Arc<Self>,
         application_id: &str,
         engine: &dyn Engine,
     ) -> DeltaResult<Option<i64>> {
-        let txn = SetTransactionScanner::get_one(self.log_segment(), application_id, engine)?;
+        let expiration_timestamp = txn_expiration_timestamp(self.table_properties())?;
+        let txn = SetTransactionScanner::get_one(
+            self.log_segment(),
+            application_id,
+            engine,
+            expiration_timestamp,
+        )?;
         Ok(txn.map(|t| t.version))
     }
 
+    /// Fetch the latest version of every application id in this snapshot, as a map from
+    /// application id to version. Application ids whose latest transaction expired according to
+    /// the table's `delta.setTransactionRetentionDuration` are left out.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage),
+    /// but only once for all application ids.
+    pub fn get_app_id_versions(&self, engine: &dyn Engine) -> DeltaResult<HashMap<String, i64>> {
+        let expiration_timestamp = txn_expiration_timestamp(self.table_properties())?;
+        let txns =
+            SetTransactionScanner::get_all(self.log_segment(), engine, expiration_timestamp)?;
+        Ok(txns
+            .into_iter()
+            .map(|(app_id, txn)| (app_id, txn.version))
+            .collect())
+    }
+
     /// Fetch the domainMetadata for a specific domain in this snapshot. This returns the latest
     /// configuration for the domain, or None if the domain does not exist.
     ///
@@ -349,6 +507,35 @@ impl Snapshot {
 
         domain_metadata_configuration(self.log_segment(), domain, engine)
     }
+
+    /// Fetch the domainMetadata for every domain in this snapshot, as a map from domain to its
+    /// latest configuration. Removed domains and system-controlled 'delta.*' domains are not
+    /// included.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    pub fn get_all_domain_metadata(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<HashMap<String, String>> {
+        user_domain_metadata_configurations(self.log_segment(), engine)
+    }
+
+    /// Fetch the clustering columns of this snapshot, for tables that use liquid clustering.
+    /// Returns `None` if the table is not clustered. The columns are logical column names, in
+    /// clustering order.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    pub fn get_clustering_columns(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<Vec<ColumnName>>> {
+        clustering_columns(
+            self.log_segment(),
+            &self.schema(),
+            self.table_configuration().column_mapping_mode(),
+            engine,
+        )
+    }
 }
 
 // Note: Schema can not be derived because the checkpoint schema is only known at runtime.
@@ -373,29 +560,130 @@ pub(crate) struct LastCheckpointHint {
     pub(crate) checksum: Option<String>,
 }
 
+impl LastCheckpointHint {
+    /// Compute the checksum of a `_last_checkpoint` JSON value, as defined by the Delta protocol:
+    /// the MD5 digest (as 32 lowercase hex characters) of the canonicalized JSON, ignoring the
+    /// top-level `checksum` field.
+    ///
+    /// [More info]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#json-checksum
+    pub(crate) fn compute_checksum(value: &serde_json::Value) -> String {
+        format!("{:x}", Md5::digest(canonicalize_json(value)))
+    }
+
+    /// Parse a `_last_checkpoint` file, validating its checksum if it has one. Returns `None` if
+    /// the file is not valid JSON, does not match the expected schema or fails checksum
+    /// validation.
+    fn try_from_slice(data: &[u8]) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_slice(data)
+            .inspect_err(|e| warn!("invalid _last_checkpoint JSON: {e}"))
+            .ok()?;
+        if let Some(expected) = value.get("checksum").and_then(|c| c.as_str()) {
+            let actual = Self::compute_checksum(&value);
+            if actual != expected {
+                warn!("_last_checkpoint checksum mismatch: expected {expected}, computed {actual}");
+                return None;
+            }
+        }
+        serde_json::from_value(value)
+            .inspect_err(|e| warn!("invalid _last_checkpoint JSON: {e}"))
+            .ok()
+    }
+}
+
+/// Canonicalize a JSON value for checksum computation. Every leaf value becomes a `path=value`
+/// pair, where the path is made of the (encoded) names of the enclosing objects' fields and the
+/// positions in the enclosing arrays, joined by `+`. The pairs are sorted by path and joined by
+/// `,`. The top-level `checksum` field is ignored.
+fn canonicalize_json(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+    fn collect_pairs(path: Option<String>, value: &Value, pairs: &mut Vec<(String, String)>) {
+        let child_path = |segment: String| match &path {
+            Some(path) => format!("{path}+{segment}"),
+            None => segment,
+        };
+        match value {
+            Value::Object(fields) => {
+                for (name, field) in fields {
+                    if path.is_none() && name == "checksum" {
+                        continue;
+                    }
+                    collect_pairs(Some(child_path(encode_json_string(name))), field, pairs);
+                }
+            }
+            Value::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    collect_pairs(Some(child_path(i.to_string())), element, pairs);
+                }
+            }
+            Value::String(s) => pairs.push((path.unwrap_or_default(), encode_json_string(s))),
+            Value::Null | Value::Bool(_) | Value::Number(_) => {
+                pairs.push((path.unwrap_or_default(), value.to_string()))
+            }
+        }
+    }
+
+    let mut pairs = vec![];
+    collect_pairs(None, value, &mut pairs);
+    // Paths are unique, so a byte-order sort on the whole pair is a sort on the paths
+    pairs.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+    pairs
+        .into_iter()
+        .map(|(path, value)| format!("{path}={value}"))
+        .join(",")
+}
+
+/// Encode a string for JSON canonicalization: its content is URL-encoded the same way as Java's
+/// `URLEncoder` (but with spaces as `%20`), and it is surrounded by double quotes.
+fn encode_json_string(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len() + 2);
+    encoded.push('"');
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'-' | b'*' | b'_' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+/// The number of attempts made to read the `_last_checkpoint` file before giving up on it.
+const LAST_CHECKPOINT_READ_ATTEMPTS: usize = 3;
+
 /// Try reading the `_last_checkpoint` file.
 ///
 /// Note that we typically want to ignore a missing/invalid `_last_checkpoint` file without failing
-/// the read. Thus, the semantics of this function are to return `None` if the file is not found or
-/// is invalid JSON. Unexpected/unrecoverable errors are returned as `Err` case and are assumed to
-/// cause failure.
-///
-/// TODO: java kernel retries three times before failing, should we do the same?
+/// the read, since the table can always be loaded by listing the log instead. Thus, the semantics
+/// of this function are to return `None` if the file is not found, is invalid JSON or fails
+/// checksum validation. Other (possibly transient) read failures are retried, like the java kernel
+/// does, and the hint is ignored if every attempt fails.
 fn read_last_checkpoint(
     storage: &dyn StorageHandler,
     log_root: &Url,
 ) -> DeltaResult<Option<LastCheckpointHint>> {
     let file_path = log_root.join(LAST_CHECKPOINT_FILE_NAME)?;
-    match storage
-        .read_files(vec![(file_path, None)])
-        .and_then(|mut data| data.next().expect("read_files should return one file"))
-    {
-        Ok(data) => Ok(serde_json::from_slice(&data)
-            .inspect_err(|e| warn!("invalid _last_checkpoint JSON: {e}"))
-            .ok()),
-        Err(Error::FileNotFound(_)) => Ok(None),
-        Err(err) => Err(err),
+    for attempt in 1..=LAST_CHECKPOINT_READ_ATTEMPTS {
+        match storage
+            .read_files(vec![(file_path.clone(), None)])
+            .and_then(|mut data| {
+                data.next().unwrap_or_else(|| {
+                    Err(Error::internal_error(format!(
+                        "read_files returned no data for {file_path}"
+                    )))
+                })
+            }) {
+            Ok(data) => return Ok(LastCheckpointHint::try_from_slice(&data)),
+            Err(Error::FileNotFound(_)) => return Ok(None),
+            Err(err) => warn!(
+                "failed to read _last_checkpoint (attempt {attempt} of \
+                 {LAST_CHECKPOINT_READ_ATTEMPTS}): {err}"
+            ),
+        }
     }
+    warn!("ignoring _last_checkpoint after {LAST_CHECKPOINT_READ_ATTEMPTS} failed reads");
+    Ok(None)
 }
 
 #[cfg(test)]
@@ -403,7 +691,11 @@ mod tests {
     use super::*;
 
     use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use bytes::Bytes;
 
     use crate::object_store::local::LocalFileSystem;
     use crate::object_store::memory::InMemory;
@@ -422,6 +714,7 @@ mod tests {
     use crate::engine::sync::SyncEngine;
     use crate::path::ParsedLogPath;
     use crate::utils::test_utils::string_array_to_engine_data;
+    use crate::{FileMeta, FileSlice};
     use test_utils::{add_commit, delta_path_for_version};
 
     #[test]
@@ -665,6 +958,121 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_snapshot_new_from_with_crc_files() -> DeltaResult<()> {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let protocol = json!({"minReaderVersion": 1, "minWriterVersion": 2});
+        let metadata = |id: &str| {
+            json!({
+                "id": id,
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[]}",
+                "partitionColumns": [],
+                "configuration": {}
+            })
+        };
+        let crc = |id: &str| {
+            json!({
+                "tableSizeBytes": 0,
+                "numFiles": 0,
+                "numMetadata": 1,
+                "numProtocol": 1,
+                "metadata": metadata(id),
+                "protocol": protocol,
+            })
+            .to_string()
+        };
+        let commit_info = json!({"commitInfo": {"timestamp": 1}}).to_string();
+
+        // 0: P&M, 1: no P&M (with a checksum), 2: new metadata
+        let commit0 = json!({"protocol": protocol}).to_string()
+            + "\n"
+            + &json!({"metaData": metadata("v0")}).to_string();
+        add_commit(store.as_ref(), 0, commit0).await.unwrap();
+        add_commit(store.as_ref(), 1, commit_info.clone())
+            .await
+            .unwrap();
+        store
+            .put(&delta_path_for_version(1, "crc"), crc("crc1").into())
+            .await?;
+        let commit2 = json!({"metaData": metadata("v2")}).to_string();
+        add_commit(store.as_ref(), 2, commit2).await.unwrap();
+
+        // a checksum newer than the existing snapshot is used for the new P&M
+        let base_snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, Some(0))?);
+        let snapshot = Snapshot::try_new_from(base_snapshot, &engine, Some(1))?;
+        assert_eq!(snapshot.metadata().id, "crc1");
+        assert_eq!(
+            snapshot
+                .log_segment()
+                .latest_crc_file
+                .as_ref()
+                .unwrap()
+                .version,
+            1
+        );
+
+        // a checksum older than the existing snapshot is not (only the new commits are replayed)
+        add_commit(store.as_ref(), 3, commit_info).await.unwrap();
+        let base_snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, Some(2))?);
+        assert_eq!(base_snapshot.metadata().id, "v2");
+        let snapshot = Snapshot::try_new_from(base_snapshot, &engine, None)?;
+        assert_eq!(snapshot.metadata().id, "v2");
+        assert_eq!(snapshot, Snapshot::try_new(url, &engine, None)?.into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_new_from_with_compaction_files() -> DeltaResult<()> {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let commit0 = [
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+            json!({"metaData": {
+                "id": "id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[]}",
+                "partitionColumns": [],
+                "configuration": {}
+            }}),
+        ]
+        .map(|action| action.to_string())
+        .join("\n");
+        let commit_info = json!({"commitInfo": {"timestamp": 1}}).to_string();
+        add_commit(store.as_ref(), 0, commit0.clone())
+            .await
+            .unwrap();
+        for version in 1..=3 {
+            add_commit(store.as_ref(), version, commit_info.clone())
+                .await
+                .unwrap();
+        }
+        let compaction = |lo: Version, hi: Version| {
+            delta_path_for_version(lo, &format!("{hi:020}.compacted.json"))
+        };
+        store.put(&compaction(0, 1), commit0.into()).await?;
+        store.put(&compaction(2, 3), commit_info.into()).await?;
+
+        // the compaction starting at version 0 is before the new listing, so it must be carried
+        // over from the existing snapshot's log segment
+        let base_snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, Some(1))?);
+        let snapshot = Snapshot::try_new_from(base_snapshot, &engine, None)?;
+        let compaction_versions = |snapshot: &Snapshot| {
+            snapshot
+                .log_segment()
+                .ascending_compaction_files
+                .iter()
+                .map(|log_path| log_path.version)
+                .collect_vec()
+        };
+        assert_eq!(compaction_versions(&snapshot), [0, 2]);
+        assert_eq!(snapshot, Snapshot::try_new(url, &engine, None)?.into());
+        Ok(())
+    }
+
     #[test]
     fn test_read_table_with_last_checkpoint() {
         let path = std::fs::canonicalize(PathBuf::from(
@@ -718,6 +1126,119 @@ mod tests {
         assert!(invalid.is_none())
     }
 
+    #[test]
+    fn test_canonicalize_json() {
+        // examples from the protocol
+        let value = json!({"a": 10, "b": {"y": null, "x": "https://delta.io"}, "checksum": "XXX"});
+        assert_eq!(
+            canonicalize_json(&value),
+            r#""a"=10,"b"+"x"="https%3A%2F%2Fdelta.io","b"+"y"=null"#
+        );
+        let value = json!({"c": [null, "hi ho", 2.71], "d": {"checksum": true}});
+        assert_eq!(
+            canonicalize_json(&value),
+            r#""c"+0=null,"c"+1="hi%20ho","c"+2=2.71,"d"+"checksum"=true"#
+        );
+    }
+
+    #[test]
+    fn test_read_last_checkpoint_with_checksum() {
+        let store = Arc::new(InMemory::new());
+        let mut value = json!({"version": 3, "size": 8, "parts": 1, "tags": {"k": "a b"}});
+        value["checksum"] = LastCheckpointHint::compute_checksum(&value).into();
+        let mut corrupt = value.clone();
+        corrupt["version"] = 4.into();
+        tokio::runtime::Runtime::new()
+            .expect("create tokio runtime")
+            .block_on(async {
+                for (dir, value) in [("valid", &value), ("corrupt", &corrupt)] {
+                    store
+                        .put(
+                            &Path::from(format!("{dir}/_last_checkpoint")),
+                            value.to_string().into(),
+                        )
+                        .await
+                        .expect("put _last_checkpoint");
+                }
+            });
+
+        let storage =
+            ObjectStoreStorageHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let url = Url::parse("memory:///valid/").unwrap();
+        let hint = read_last_checkpoint(&storage, &url).unwrap().unwrap();
+        assert_eq!(hint.version, 3);
+        assert_eq!(hint.checksum, value["checksum"].as_str().map(Into::into));
+        let url = Url::parse("memory:///corrupt/").unwrap();
+        assert!(read_last_checkpoint(&storage, &url).unwrap().is_none());
+    }
+
+    /// A storage handler whose first `failures` reads fail, or return no data if `empty`
+    struct FlakyStorageHandler {
+        inner: ObjectStoreStorageHandler<TokioBackgroundExecutor>,
+        failures: AtomicUsize,
+        empty: bool,
+    }
+
+    impl StorageHandler for FlakyStorageHandler {
+        fn list_from(
+            &self,
+            path: &Url,
+        ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+            self.inner.list_from(path)
+        }
+
+        fn read_files(
+            &self,
+            files: Vec<FileSlice>,
+        ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+            let failed = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            match (failed, self.empty) {
+                (true, true) => return Ok(Box::new(std::iter::empty())),
+                (true, false) => return Err(Error::generic("transient failure")),
+                (false, _) => {}
+            }
+            self.inner.read_files(files)
+        }
+    }
+
+    #[test]
+    fn test_read_last_checkpoint_retries() {
+        let store = Arc::new(InMemory::new());
+        tokio::runtime::Runtime::new()
+            .expect("create tokio runtime")
+            .block_on(async {
+                store
+                    .put(
+                        &Path::from("_last_checkpoint"),
+                        valid_last_checkpoint().into(),
+                    )
+                    .await
+                    .expect("put _last_checkpoint");
+            });
+        let url = Url::parse("memory:///").unwrap();
+        let storage = |failures, empty| FlakyStorageHandler {
+            inner: ObjectStoreStorageHandler::new(
+                store.clone(),
+                Arc::new(TokioBackgroundExecutor::new()),
+            ),
+            failures: AtomicUsize::new(failures),
+            empty,
+        };
+
+        for empty in [false, true] {
+            // transient failures are retried
+            let hint = read_last_checkpoint(&storage(2, empty), &url).unwrap();
+            assert_eq!(hint.unwrap().version, 1);
+
+            // the hint is ignored if every attempt fails
+            let hint = read_last_checkpoint(&storage(3, empty), &url).unwrap();
+            assert!(hint.is_none());
+        }
+    }
+
     #[test_log::test]
     fn test_read_table_with_checkpoint() {
         let path = std::fs::canonicalize(PathBuf::from(
@@ -851,4 +1372,174 @@ mod tests {
                 msg == "User DomainMetadata are not allowed to use system-controlled 'delta.*' domain"));
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_all_domain_metadata_and_clustering_columns() -> DeltaResult<()> {
+        let url = Url::parse("memory:///")?;
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+        // column `a` has physical name `col-a`, and `s.b` has physical name `col-s.col-b`
+        let schema_string = json!({
+            "type": "struct",
+            "fields": [
+                {
+                    "name": "a",
+                    "type": "integer",
+                    "nullable": true,
+                    "metadata": {
+                        "delta.columnMapping.id": 1,
+                        "delta.columnMapping.physicalName": "col-a"
+                    }
+                },
+                {
+                    "name": "s",
+                    "type": {
+                        "type": "struct",
+                        "fields": [{
+                            "name": "b",
+                            "type": "string",
+                            "nullable": true,
+                            "metadata": {
+                                "delta.columnMapping.id": 3,
+                                "delta.columnMapping.physicalName": "col-b"
+                            }
+                        }]
+                    },
+                    "nullable": true,
+                    "metadata": {
+                        "delta.columnMapping.id": 2,
+                        "delta.columnMapping.physicalName": "col-s"
+                    }
+                }
+            ]
+        });
+        let commit = [
+            json!({
+                "protocol": {
+                    "minReaderVersion": 2,
+                    "minWriterVersion": 5
+                }
+            }),
+            json!({
+                "metaData": {
+                    "id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": { "provider": "parquet", "options": {} },
+                    "schemaString": schema_string.to_string(),
+                    "partitionColumns": [],
+                    "configuration": {
+                        "delta.columnMapping.mode": "name",
+                        "delta.columnMapping.maxColumnId": "3"
+                    },
+                    "createdTime": 1587968585495i64
+                }
+            }),
+            json!({
+                "domainMetadata": {
+                    "domain": "delta.clustering",
+                    "configuration": r#"{"clusteringColumns":[["col-s","col-b"],["col-a"]]}"#,
+                    "removed": false
+                }
+            }),
+            json!({
+                "domainMetadata": {
+                    "domain": "domain1",
+                    "configuration": "domain1_commit0",
+                    "removed": false
+                }
+            }),
+            json!({
+                "domainMetadata": {
+                    "domain": "domain2",
+                    "configuration": "domain2_commit0",
+                    "removed": false
+                }
+            }),
+        ]
+        .map(|json| json.to_string())
+        .join("\n");
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+        let commit = json!({
+            "domainMetadata": {
+                "domain": "domain2",
+                "configuration": "domain2_commit1",
+                "removed": true
+            }
+        })
+        .to_string();
+        add_commit(store.as_ref(), 1, commit).await.unwrap();
+
+        let snapshot = Snapshot::try_new(url, &engine, None)?;
+        assert_eq!(
+            snapshot.get_all_domain_metadata(&engine)?,
+            HashMap::from([("domain1".to_string(), "domain1_commit0".to_string())])
+        );
+        assert_eq!(
+            snapshot.get_clustering_columns(&engine)?,
+            Some(vec![ColumnName::new(["s", "b"]), ColumnName::new(["a"])])
+        );
+
+        // an unclustered table has no clustering columns
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::try_new(url, &engine, None)?;
+        assert_eq!(snapshot.get_clustering_columns(&engine)?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_app_id_versions_with_expiration() -> DeltaResult<()> {
+        let url = Url::parse("memory:///")?;
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        // app1 expired, app2 was updated recently, and app3 has no lastUpdated so never expires
+        let commit = [
+            json!({
+                "protocol": {
+                    "minReaderVersion": 1,
+                    "minWriterVersion": 1
+                }
+            }),
+            json!({
+                "metaData": {
+                    "id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": { "provider": "parquet", "options": {} },
+                    "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                    "partitionColumns": [],
+                    "configuration": {
+                        "delta.setTransactionRetentionDuration": "interval 1 days"
+                    },
+                    "createdTime": 1587968585495i64
+                }
+            }),
+            json!({ "txn": { "appId": "app1", "version": 1, "lastUpdated": 0 } }),
+            json!({ "txn": { "appId": "app2", "version": 2, "lastUpdated": now } }),
+            json!({ "txn": { "appId": "app3", "version": 3 } }),
+        ]
+        .map(|json| json.to_string())
+        .join("\n");
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        assert_eq!(snapshot.clone().get_app_id_version("app1", &engine)?, None);
+        assert_eq!(
+            snapshot.clone().get_app_id_version("app2", &engine)?,
+            Some(2)
+        );
+        assert_eq!(
+            snapshot.clone().get_app_id_version("app3", &engine)?,
+            Some(3)
+        );
+        assert_eq!(
+            snapshot.get_app_id_versions(&engine)?,
+            HashMap::from([("app2".to_string(), 2), ("app3".to_string(), 3)])
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,395 @@
+//! Reports how a table evolved between two [`Snapshot`]s. See [`Snapshot::diff`].
+
+use std::collections::BTreeSet;
+
+use crate::schema::compare::diff_schemas;
+use crate::schema::SchemaChange;
+use crate::table_features::{ReaderFeature, WriterFeature};
+use crate::Version;
+
+use super::Snapshot;
+
+/// The differences between two versions of a table, as computed by [`Snapshot::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// The version of the snapshot `diff` was called on
+    pub from_version: Version,
+    /// The version of the snapshot passed to `diff`
+    pub to_version: Version,
+    /// Changes to the table schema. See [`SchemaChange`] for how fields are matched.
+    pub schema_changes: Vec<SchemaChange>,
+    /// Table properties that were set, unset or changed, sorted by key
+    pub property_changes: Vec<PropertyChange>,
+    /// Changes to the protocol versions or table features, if any
+    pub protocol_change: Option<ProtocolChange>,
+    /// Changes to the partition columns, if any
+    pub partition_columns_change: Option<PartitionColumnsChange>,
+}
+
+/// A table property that differs between two snapshots. A `None` value means the property is not
+/// set in that snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyChange {
+    /// The property's key
+    pub key: String,
+    /// The property's value in the snapshot `diff` was called on
+    pub from: Option<String>,
+    /// The property's value in the snapshot passed to `diff`
+    pub to: Option<String>,
+}
+
+/// A change to a table's protocol. Features are listed in the order they appear in the new (for
+/// added features) or old (for removed features) protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolChange {
+    /// The `(from, to)` minimum reader version
+    pub min_reader_version: (i32, i32),
+    /// The `(from, to)` minimum writer version
+    pub min_writer_version: (i32, i32),
+    /// Reader features supported by the new protocol but not the old one
+    pub added_reader_features: Vec<ReaderFeature>,
+    /// Reader features supported by the old protocol but not the new one
+    pub removed_reader_features: Vec<ReaderFeature>,
+    /// Writer features supported by the new protocol but not the old one
+    pub added_writer_features: Vec<WriterFeature>,
+    /// Writer features supported by the old protocol but not the new one
+    pub removed_writer_features: Vec<WriterFeature>,
+}
+
+/// A change to a table's partition columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionColumnsChange {
+    /// The partition columns of the snapshot `diff` was called on
+    pub from: Vec<String>,
+    /// The partition columns of the snapshot passed to `diff`
+    pub to: Vec<String>,
+}
+
+impl SnapshotDiff {
+    pub(crate) fn new(from: &Snapshot, to: &Snapshot) -> Self {
+        let (from_metadata, to_metadata) = (from.metadata(), to.metadata());
+
+        let keys: BTreeSet<&String> = from_metadata
+            .configuration()
+            .keys()
+            .chain(to_metadata.configuration().keys())
+            .collect();
+        let property_changes = keys
+            .into_iter()
+            .filter_map(|key| {
+                let from = from_metadata.configuration().get(key);
+                let to = to_metadata.configuration().get(key);
+                (from != to).then(|| PropertyChange {
+                    key: key.clone(),
+                    from: from.cloned(),
+                    to: to.cloned(),
+                })
+            })
+            .collect();
+
+        let partition_columns_change = (from_metadata.partition_columns()
+            != to_metadata.partition_columns())
+        .then(|| PartitionColumnsChange {
+            from: from_metadata.partition_columns().clone(),
+            to: to_metadata.partition_columns().clone(),
+        });
+
+        Self {
+            from_version: from.version(),
+            to_version: to.version(),
+            schema_changes: diff_schemas(&from.schema(), &to.schema()),
+            property_changes,
+            protocol_change: ProtocolChange::new(from, to),
+            partition_columns_change,
+        }
+    }
+
+    /// Returns `true` if nothing this diff tracks changed between the two snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.schema_changes.is_empty()
+            && self.property_changes.is_empty()
+            && self.protocol_change.is_none()
+            && self.partition_columns_change.is_none()
+    }
+
+    /// Returns `true` if every schema change is compatible with data written using the old schema.
+    /// See [`SchemaChange::is_compatible`].
+    pub fn is_schema_compatible(&self) -> bool {
+        self.schema_changes.iter().all(SchemaChange::is_compatible)
+    }
+}
+
+impl ProtocolChange {
+    fn new(from: &Snapshot, to: &Snapshot) -> Option<Self> {
+        let (from, to) = (from.protocol(), to.protocol());
+        if from == to {
+            return None;
+        }
+        Some(Self {
+            min_reader_version: (from.min_reader_version(), to.min_reader_version()),
+            min_writer_version: (from.min_writer_version(), to.min_writer_version()),
+            added_reader_features: features_difference(
+                to.reader_features(),
+                from.reader_features(),
+            ),
+            removed_reader_features: features_difference(
+                from.reader_features(),
+                to.reader_features(),
+            ),
+            added_writer_features: features_difference(
+                to.writer_features(),
+                from.writer_features(),
+            ),
+            removed_writer_features: features_difference(
+                from.writer_features(),
+                to.writer_features(),
+            ),
+        })
+    }
+}
+
+/// The features in `a` that are not in `b`.
+fn features_difference<T: Clone + PartialEq>(a: Option<&[T]>, b: Option<&[T]>) -> Vec<T> {
+    let b = b.unwrap_or_default();
+    a.unwrap_or_default()
+        .iter()
+        .filter(|feature| !b.contains(feature))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::actions::{Metadata, Protocol};
+    use crate::schema::{ColumnName, DataType};
+    use crate::utils::test_utils::{in_memory_table, Action};
+
+    fn metadata(
+        fields: &str,
+        partition_columns: &[&str],
+        configuration: &[(&str, &str)],
+    ) -> Action {
+        Action::Metadata(Metadata {
+            id: "test-table".into(),
+            schema_string: format!(r#"{{"type":"struct","fields":[{fields}]}}"#),
+            partition_columns: partition_columns.iter().map(|c| c.to_string()).collect(),
+            configuration: configuration
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            ..Default::default()
+        })
+    }
+
+    fn field(name: &str, data_type: &str, nullable: bool, id: i64) -> String {
+        format!(
+            r#"{{"name":"{name}","type":{data_type},"nullable":{nullable},"metadata":{{"delta.columnMapping.id":{id},"delta.columnMapping.physicalName":"col-{id}"}}}}"#
+        )
+    }
+
+    fn snapshots(commits: Vec<Vec<Action>>) -> Vec<Snapshot> {
+        let num_commits = commits.len() as u64;
+        let (_store, engine, table) = in_memory_table(commits);
+        (0..num_commits)
+            .map(|version| table.snapshot(&engine, Some(version)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_same_snapshot_is_empty() {
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let fields = r#"{"name":"value","type":"integer","nullable":true,"metadata":{}}"#;
+        let snapshots = snapshots(vec![vec![
+            Action::Protocol(protocol),
+            metadata(fields, &[], &[]),
+        ]]);
+        let diff = snapshots[0].diff(&snapshots[0]);
+        assert!(diff.is_empty());
+        assert!(diff.is_schema_compatible());
+    }
+
+    #[test]
+    fn test_diff_schema_properties_and_partitioning() {
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let old_fields = [
+            r#"{"name":"id","type":"integer","nullable":false,"metadata":{}}"#,
+            r#"{"name":"part","type":"string","nullable":true,"metadata":{}}"#,
+            r#"{"name":"dropped","type":"string","nullable":true,"metadata":{}}"#,
+            r#"{"name":"nested","type":{"type":"struct","fields":[{"name":"a","type":"float","nullable":true,"metadata":{}}]},"nullable":true,"metadata":{}}"#,
+        ]
+        .join(",");
+        let new_fields = [
+            r#"{"name":"id","type":"long","nullable":true,"metadata":{}}"#,
+            r#"{"name":"part","type":"integer","nullable":false,"metadata":{}}"#,
+            r#"{"name":"nested","type":{"type":"struct","fields":[{"name":"a","type":"double","nullable":true,"metadata":{}},{"name":"b","type":"string","nullable":true,"metadata":{}}]},"nullable":true,"metadata":{}}"#,
+        ]
+        .join(",");
+        let snapshots = snapshots(vec![
+            vec![
+                Action::Protocol(protocol),
+                metadata(
+                    &old_fields,
+                    &[],
+                    &[("delta.appendOnly", "true"), ("k", "v")],
+                ),
+            ],
+            vec![metadata(
+                &new_fields,
+                &["part"],
+                &[("delta.appendOnly", "false"), ("new", "x")],
+            )],
+        ]);
+        let diff = snapshots[0].diff(&snapshots[1]);
+        assert_eq!(diff.from_version, 0);
+        assert_eq!(diff.to_version, 1);
+        assert_eq!(
+            diff.schema_changes,
+            vec![
+                SchemaChange::NullabilityRelaxed {
+                    path: ColumnName::new(["id"])
+                },
+                SchemaChange::Widened {
+                    path: ColumnName::new(["id"]),
+                    from: DataType::INTEGER,
+                    to: DataType::LONG,
+                },
+                SchemaChange::NullabilityTightened {
+                    path: ColumnName::new(["part"])
+                },
+                SchemaChange::TypeChanged {
+                    path: ColumnName::new(["part"]),
+                    from: DataType::STRING,
+                    to: DataType::INTEGER,
+                },
+                SchemaChange::Dropped {
+                    path: ColumnName::new(["dropped"])
+                },
+                SchemaChange::Widened {
+                    path: ColumnName::new(["nested", "a"]),
+                    from: DataType::FLOAT,
+                    to: DataType::DOUBLE,
+                },
+                SchemaChange::Added {
+                    path: ColumnName::new(["nested", "b"]),
+                    nullable: true,
+                },
+            ]
+        );
+        assert!(!diff.is_schema_compatible());
+        assert_eq!(
+            diff.property_changes,
+            vec![
+                PropertyChange {
+                    key: "delta.appendOnly".into(),
+                    from: Some("true".into()),
+                    to: Some("false".into()),
+                },
+                PropertyChange {
+                    key: "k".into(),
+                    from: Some("v".into()),
+                    to: None,
+                },
+                PropertyChange {
+                    key: "new".into(),
+                    from: None,
+                    to: Some("x".into()),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.partition_columns_change,
+            Some(PartitionColumnsChange {
+                from: vec![],
+                to: vec!["part".into()],
+            })
+        );
+        assert_eq!(diff.protocol_change, None);
+    }
+
+    #[test]
+    fn test_diff_column_mapping_rename_and_protocol() {
+        let old_protocol =
+            Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let new_protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::ColumnMapping]),
+            Some([WriterFeature::ColumnMapping, WriterFeature::AppendOnly]),
+        )
+        .unwrap();
+        let mode = [("delta.columnMapping.mode", "name")];
+        let old_fields = [
+            field("a", r#""integer""#, true, 1),
+            field("b", r#""string""#, true, 2),
+        ]
+        .join(",");
+        let new_fields = [
+            field("renamed", r#""integer""#, true, 1),
+            field("b", r#""string""#, true, 2),
+            field("c", r#""string""#, true, 3),
+        ]
+        .join(",");
+        let snapshots = snapshots(vec![
+            vec![
+                Action::Protocol(old_protocol),
+                metadata(&old_fields, &[], &mode),
+            ],
+            vec![
+                Action::Protocol(new_protocol),
+                metadata(&new_fields, &[], &mode),
+            ],
+        ]);
+        let diff = snapshots[0].diff(&snapshots[1]);
+        assert_eq!(
+            diff.schema_changes,
+            vec![
+                SchemaChange::Renamed {
+                    from: ColumnName::new(["a"]),
+                    to: ColumnName::new(["renamed"]),
+                },
+                SchemaChange::Added {
+                    path: ColumnName::new(["c"]),
+                    nullable: true,
+                },
+            ]
+        );
+        assert_eq!(
+            diff.protocol_change,
+            Some(ProtocolChange {
+                min_reader_version: (2, 3),
+                min_writer_version: (5, 7),
+                added_reader_features: vec![ReaderFeature::ColumnMapping],
+                removed_reader_features: vec![],
+                added_writer_features: vec![
+                    WriterFeature::ColumnMapping,
+                    WriterFeature::AppendOnly
+                ],
+                removed_writer_features: vec![],
+            })
+        );
+        assert!(diff.property_changes.is_empty());
+
+        // the reverse diff reports the rename back and the dropped column
+        let diff = snapshots[1].diff(&snapshots[0]);
+        assert_eq!(
+            diff.schema_changes,
+            vec![
+                SchemaChange::Renamed {
+                    from: ColumnName::new(["renamed"]),
+                    to: ColumnName::new(["a"]),
+                },
+                SchemaChange::Dropped {
+                    path: ColumnName::new(["c"])
+                },
+            ]
+        );
+        let protocol_change = diff.protocol_change.unwrap();
+        assert_eq!(
+            protocol_change.removed_writer_features,
+            vec![WriterFeature::ColumnMapping, WriterFeature::AppendOnly]
+        );
+    }
+}
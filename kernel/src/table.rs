@@ -9,7 +9,10 @@ use std::sync::Arc;
 use url::Url;
 
 use crate::checkpoint::CheckpointWriter;
+use crate::log_compaction::LogCompactionWriter;
+use crate::scan::IncrementalScanMetadata;
 use crate::snapshot::Snapshot;
+use crate::table_changes::follower::TableChangesFollower;
 use crate::table_changes::TableChanges;
 use crate::transaction::Transaction;
 use crate::{DeltaResult, Engine, Error, Version};
@@ -100,6 +103,56 @@ impl Table {
         )
     }
 
+    /// Create a [`TableChangesFollower`] to continuously read the change data feed of the table
+    /// starting at `start_version`. If no `start_version` is supplied, the follower only reads
+    /// changes committed after this call. See [`TableChangesFollower::try_new`] for details.
+    pub fn table_changes_follower(
+        &self,
+        engine: &dyn Engine,
+        start_version: impl Into<Option<Version>>,
+    ) -> DeltaResult<TableChangesFollower> {
+        TableChangesFollower::try_new(self.location.clone(), engine, start_version)
+    }
+
+    /// Create a [`TableChanges`] to get a change data feed for the table between `start_timestamp`
+    /// and `end_timestamp` (in milliseconds since the Unix epoch). If no `end_timestamp` is
+    /// supplied, the latest version will be used as the end version. See
+    /// [`TableChanges::try_new_from_timestamps`] for how timestamps are resolved to versions.
+    pub fn table_changes_by_timestamp(
+        &self,
+        engine: &dyn Engine,
+        start_timestamp: i64,
+        end_timestamp: impl Into<Option<i64>>,
+    ) -> DeltaResult<TableChanges> {
+        TableChanges::try_new_from_timestamps(
+            self.location.clone(),
+            engine,
+            start_timestamp,
+            end_timestamp.into(),
+        )
+    }
+
+    /// Get the files added and removed by each commit in the version range (`start_version`,
+    /// `end_version`], without requiring the change data feed to be enabled. If no `end_version`
+    /// is supplied, the latest version will be used as the `end_version`.
+    ///
+    /// This scans the whole table; see [`Scan::incremental_scan_metadata`] for details, and to
+    /// scan with a schema or predicate.
+    ///
+    /// [`Scan::incremental_scan_metadata`]: crate::scan::Scan::incremental_scan_metadata
+    pub fn incremental_scan_metadata(
+        &self,
+        engine: &dyn Engine,
+        start_version: Version,
+        end_version: impl Into<Option<Version>>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<IncrementalScanMetadata>>> {
+        let snapshot = self.snapshot(engine, end_version.into())?;
+        snapshot
+            .into_scan_builder()
+            .build()?
+            .incremental_scan_metadata(engine, start_version)
+    }
+
     /// Creates a [`CheckpointWriter`] for generating checkpoints at the specified table version.
     ///
     /// See the [`crate::checkpoint`] module documentation for more details on checkpoint types
@@ -118,6 +171,20 @@ impl Table {
         CheckpointWriter::try_new(snapshot)
     }
 
+    /// Creates a [`LogCompactionWriter`] for compacting the commits in the (inclusive) version
+    /// range `[start_version, end_version]` into a single log compaction file.
+    ///
+    /// See the [`crate::log_compaction`] module documentation for more details.
+    pub fn log_compaction_writer(
+        &self,
+        engine: &dyn Engine,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<LogCompactionWriter> {
+        let snapshot = Arc::new(self.snapshot(engine, Some(end_version))?);
+        LogCompactionWriter::try_new(snapshot, start_version, end_version)
+    }
+
     /// Create a new write transaction for this table.
     pub fn new_transaction(&self, engine: &dyn Engine) -> DeltaResult<Transaction> {
         Transaction::try_new(self.snapshot(engine, None)?)
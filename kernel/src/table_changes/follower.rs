@@ -0,0 +1,456 @@
+//! Provides [`TableChangesFollower`], which continuously reads the change data feed of a table as
+//! new commits are added to it.
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use url::Url;
+
+use super::{is_cdf_schema_compatible, TableChanges, CDF_FIELDS};
+use crate::log_segment::{ListedLogFiles, LogSegment};
+use crate::path::ParsedLogPath;
+use crate::scan::ScanResult;
+use crate::schema::{SchemaRef, StructType};
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, PredicateRef, Version};
+
+type ScanResultIter = Box<dyn Iterator<Item = DeltaResult<ScanResult>>>;
+
+/// The position of a [`TableChangesFollower`] in the change data feed. The offset points at the
+/// next batch to be returned: the batch at `index` among the batches produced for the commit at
+/// `version`.
+///
+/// Offsets can be persisted and used to resume following the table with
+/// [`TableChangesFollower::try_new_from_offset`]. Batch indices are only stable for the same
+/// engine configuration, since the batches produced for a commit depend on how the engine reads
+/// its data files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChangeFeedOffset {
+    /// The version of the commit the offset points into.
+    pub version: Version,
+    /// The index of the next batch among the batches produced for the commit at `version`.
+    pub index: usize,
+}
+
+impl ChangeFeedOffset {
+    /// Create an offset pointing at the first batch of the commit at `version`.
+    pub fn new(version: Version) -> Self {
+        Self { version, index: 0 }
+    }
+}
+
+/// A batch of changes returned by [`TableChangesFollower::next_batch`].
+pub struct TableChangesBatch {
+    /// The offset of this batch. Resuming from this offset returns this batch again.
+    pub offset: ChangeFeedOffset,
+    /// The change data read for this batch.
+    pub result: ScanResult,
+}
+
+/// Follows the change data feed of a table, one commit at a time. Each call to
+/// [`TableChangesFollower::next_batch`] returns the next batch of changes, or `None` once all
+/// commits currently in the table have been read. Calling it again later picks up any commits
+/// that were added in the meantime.
+///
+/// Every batch has the schema selected at construction time, which is the schema of the table at
+/// the start version with the change data feed columns described in [`TableChanges`], unless
+/// projected using [`TableChangesFollower::with_schema`]. Columns added to the table after the
+/// start version are not read.
+///
+/// The follower keeps a snapshot of the newest table version it has seen, and reads the commits
+/// added since then like a [`TableChanges`] ending at that version. If they cannot be read as part
+/// of the change data feed, `next_batch` returns an error and the follower's offset stays at the
+/// first unread commit. This happens when:
+/// - the commit upgrades the protocol to features that change data feed does not support.
+/// - the commit disables change data feed.
+/// - the commit changes the schema in a way that data can no longer be read with the followed
+///   schema. See [`TableChanges`] for the schema changes that are allowed.
+///
+/// # Example
+/// ```rust
+/// # use std::sync::Arc;
+/// # use delta_kernel::engine::sync::SyncEngine;
+/// # use delta_kernel::{Table, Error};
+/// # let path = "./tests/data/table-with-cdf";
+/// # let engine = Arc::new(SyncEngine::new());
+/// let table = Table::try_from_uri(path)?;
+/// // Follow the changes committed from now on
+/// let mut follower = table.table_changes_follower(engine.as_ref(), None)?;
+/// while let Some(batch) = follower.next_batch(engine.clone())? {
+///     // process `batch.result`, and persist `follower.offset()` to resume later
+/// }
+/// // no more changes for now: poll again later
+/// # Ok::<(), Error>(())
+/// ```
+pub struct TableChangesFollower {
+    table_root: Url,
+    /// The table schema at the start version. Every followed commit must be readable with it.
+    table_schema: SchemaRef,
+    schema: SchemaRef,
+    predicate: Option<PredicateRef>,
+    offset: ChangeFeedOffset,
+    /// The newest snapshot of the table seen by the follower.
+    snapshot: Arc<Snapshot>,
+    /// The commits from `offset.version` up to the version of `snapshot`, which are yet to be read.
+    pending_commits: VecDeque<ParsedLogPath>,
+    /// The remaining batches of the commit at `offset.version`, if its scan has been started.
+    current_batches: Option<ScanResultIter>,
+}
+
+impl std::fmt::Debug for TableChangesFollower {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableChangesFollower")
+            .field("table_root", &self.table_root)
+            .field("schema", &self.schema)
+            .field("predicate", &self.predicate)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl TableChangesFollower {
+    /// Creates a new [`TableChangesFollower`] that starts reading at `start_version`. If no
+    /// `start_version` is given, the follower starts after the newest table version, and only
+    /// returns changes committed after this call.
+    ///
+    /// The start version may be newer than the newest table version, in which case the follower
+    /// waits for that version to be committed.
+    ///
+    /// # Parameters
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `start_version`: The first version to read changes from.
+    pub fn try_new(
+        table_root: Url,
+        engine: &dyn Engine,
+        start_version: impl Into<Option<Version>>,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Arc::new(Snapshot::try_new(table_root.clone(), engine, None)?);
+        let start_version = start_version
+            .into()
+            .unwrap_or(latest_snapshot.version() + 1);
+
+        // Follow the schema at the start version, or the newest schema if that is not committed
+        let start_snapshot = if start_version < latest_snapshot.version() {
+            Arc::new(Snapshot::try_new(
+                table_root.clone(),
+                engine,
+                Some(start_version),
+            )?)
+        } else {
+            latest_snapshot
+        };
+        if !start_snapshot.table_configuration().is_cdf_read_supported() {
+            return Err(Error::change_data_feed_unsupported(
+                start_snapshot.version(),
+            ));
+        }
+
+        let table_schema = start_snapshot.schema();
+        let schema = StructType::new(table_schema.fields().cloned().chain(CDF_FIELDS.clone()));
+        Ok(Self {
+            table_root,
+            table_schema,
+            schema: schema.into(),
+            predicate: None,
+            offset: ChangeFeedOffset::new(start_version),
+            snapshot: start_snapshot,
+            pending_commits: VecDeque::new(),
+            current_batches: None,
+        })
+    }
+
+    /// Creates a new [`TableChangesFollower`] that resumes reading at `offset`, which was
+    /// previously obtained from [`TableChangesFollower::offset`] or [`TableChangesBatch::offset`].
+    pub fn try_new_from_offset(
+        table_root: Url,
+        engine: &dyn Engine,
+        offset: ChangeFeedOffset,
+    ) -> DeltaResult<Self> {
+        let mut follower = Self::try_new(table_root, engine, offset.version)?;
+        follower.offset = offset;
+        Ok(follower)
+    }
+
+    /// Provide a schema to select a subset of the followed columns. Every field must be in
+    /// [`TableChangesFollower::schema`].
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Optionally provide a predicate to filter the changes with. See
+    /// [`TableChangesScanBuilder::with_predicate`] for details.
+    ///
+    /// [`TableChangesScanBuilder::with_predicate`]: super::scan::TableChangesScanBuilder::with_predicate
+    pub fn with_predicate(mut self, predicate: impl Into<Option<PredicateRef>>) -> Self {
+        self.predicate = predicate.into();
+        self
+    }
+
+    /// Path to the root of the table that is being followed.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// The logical schema of the batches returned by the follower.
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// The offset of the next batch to be returned.
+    pub fn offset(&self) -> ChangeFeedOffset {
+        self.offset
+    }
+
+    /// Returns the next batch of changes, moving on to newer commits as the current one is
+    /// exhausted. Returns `None` if all commits currently in the table have been read.
+    ///
+    /// If an error is returned, the offset is not advanced, and the next call retries reading the
+    /// commit at the current offset.
+    pub fn next_batch(
+        &mut self,
+        engine: Arc<dyn Engine>,
+    ) -> DeltaResult<Option<TableChangesBatch>> {
+        loop {
+            let batches = match self.current_batches {
+                Some(ref mut batches) => batches,
+                None => {
+                    let Some(commit) = self.next_commit(engine.as_ref())? else {
+                        return Ok(None);
+                    };
+                    let mut batches = self.scan_commit(engine.clone(), commit)?;
+                    for result in batches.by_ref().take(self.offset.index) {
+                        result?;
+                    }
+                    self.current_batches.insert(batches)
+                }
+            };
+            match batches.next() {
+                Some(Ok(result)) => {
+                    let offset = self.offset;
+                    self.offset.index += 1;
+                    return Ok(Some(TableChangesBatch { offset, result }));
+                }
+                Some(Err(err)) => {
+                    self.current_batches = None;
+                    return Err(err);
+                }
+                None => {
+                    self.current_batches = None;
+                    self.pending_commits.pop_front();
+                    self.offset = ChangeFeedOffset::new(self.offset.version + 1);
+                }
+            }
+        }
+    }
+
+    /// Returns the commit at the follower's offset, or `None` if it has not been committed yet.
+    ///
+    /// Once the pending commits are read, this advances the follower's snapshot to the newest
+    /// table version and lists the commits added since. The new snapshot must support reading
+    /// change data feed, and its schema must be compatible with the followed schema.
+    fn next_commit(&mut self, engine: &dyn Engine) -> DeltaResult<Option<ParsedLogPath>> {
+        if self.pending_commits.is_empty() {
+            let snapshot = Snapshot::try_new_from(self.snapshot.clone(), engine, None)?;
+            if snapshot.version() < self.offset.version {
+                self.snapshot = snapshot;
+                return Ok(None);
+            }
+            if !snapshot.table_configuration().is_cdf_read_supported() {
+                return Err(Error::change_data_feed_unsupported(snapshot.version()));
+            }
+            let snapshot_schema = snapshot.schema();
+            if !is_cdf_schema_compatible(&self.table_schema, &snapshot_schema) {
+                return Err(Error::change_data_feed_incompatible_schema(
+                    &self.table_schema,
+                    &snapshot_schema,
+                ));
+            }
+            let log_segment = LogSegment::for_table_changes(
+                engine.storage_handler().as_ref(),
+                snapshot.log_segment().log_root.clone(),
+                self.offset.version,
+                snapshot.version(),
+            )?;
+            self.pending_commits = log_segment.ascending_commit_files.into();
+            self.snapshot = snapshot;
+        }
+        Ok(self.pending_commits.front().cloned())
+    }
+
+    /// Starts a change data feed scan of `commit`, projected to the followed schema. The commit
+    /// is read with the schema and table configuration of the follower's snapshot.
+    fn scan_commit(
+        &self,
+        engine: Arc<dyn Engine>,
+        commit: ParsedLogPath,
+    ) -> DeltaResult<ScanResultIter> {
+        let version = commit.version;
+        let listed_files = ListedLogFiles {
+            ascending_commit_files: vec![commit],
+            ..Default::default()
+        };
+        let log_root = self.snapshot.log_segment().log_root.clone();
+        let schema = StructType::new(
+            self.snapshot
+                .schema()
+                .fields()
+                .cloned()
+                .chain(CDF_FIELDS.clone()),
+        );
+        let table_changes = TableChanges {
+            log_segment: LogSegment::try_new(listed_files, log_root, Some(version))?,
+            table_root: self.table_root.clone(),
+            end_snapshot: self.snapshot.clone(),
+            start_version: version,
+            schema,
+        };
+        let names: Vec<_> = self.schema.fields().map(|field| field.name()).collect();
+        let schema = table_changes.schema().project(&names)?;
+        let scan = table_changes
+            .into_scan_builder()
+            .with_schema(schema)
+            .with_predicate(self.predicate.clone())
+            .build()?;
+        Ok(Box::new(scan.execute(engine)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::{ChangeFeedOffset, TableChangesFollower};
+    use crate::actions::{Metadata, Protocol};
+    use crate::arrow::array::{AsArray as _, Int32Array, RecordBatch, StringArray};
+    use crate::arrow::datatypes::Int32Type;
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::object_store::memory::InMemory;
+    use crate::table_changes::tests::write_data_file;
+    use crate::utils::test_utils::{write_commit_to_store, Action};
+    use crate::{Engine, Error, Table};
+
+    fn metadata(schema_string: &str) -> Action {
+        Action::Metadata(Metadata {
+            id: "test-table".into(),
+            schema_string: schema_string.into(),
+            configuration: HashMap::from([(
+                "delta.enableChangeDataFeed".to_string(),
+                "true".to_string(),
+            )]),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the offset, the number of columns, and the `id`s of the next batch of `follower`.
+    fn next_ids(
+        follower: &mut TableChangesFollower,
+        engine: Arc<dyn Engine>,
+    ) -> Option<(ChangeFeedOffset, usize, Vec<i32>)> {
+        let batch = follower.next_batch(engine).unwrap()?;
+        let data: RecordBatch =
+            ArrowEngineData::try_from_engine_data(batch.result.raw_data.unwrap())
+                .unwrap()
+                .into();
+        let ids = data.column(0).as_primitive::<Int32Type>().values().to_vec();
+        Some((batch.offset, data.num_columns(), ids))
+    }
+
+    #[test]
+    fn follow_table_changes() {
+        let store = Arc::new(InMemory::new());
+        let engine: Arc<dyn Engine> = Arc::new(DefaultEngine::new(
+            store.clone(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let protocol = Protocol::try_new(1, 4, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let id = Arc::new(Int32Array::from(vec![1, 2]));
+        let batch = RecordBatch::try_from_iter([("id", id as _)]).unwrap();
+        let add_a = write_data_file(&store, "a.parquet", batch);
+        write_commit_to_store(&store, vec![
+                Action::Protocol(protocol),
+                metadata(
+                    r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                ),
+                add_a,
+            ], 0).unwrap();
+
+        let table = Table::try_from_uri("memory:///").unwrap();
+        let mut follower = table.table_changes_follower(engine.as_ref(), 0).unwrap();
+        let offset = |version, index| ChangeFeedOffset { version, index };
+        assert_eq!(
+            next_ids(&mut follower, engine.clone()),
+            Some((offset(0, 0), 4, vec![1, 2]))
+        );
+        assert!(next_ids(&mut follower, engine.clone()).is_none());
+        assert_eq!(follower.offset(), offset(1, 0));
+
+        // a follower starting at the latest version only sees later commits
+        let mut latest_follower = table.table_changes_follower(engine.as_ref(), None).unwrap();
+        assert_eq!(latest_follower.offset(), offset(1, 0));
+
+        // version 1 adds a column, which is not read by the followers
+        let id = Arc::new(Int32Array::from(vec![3]));
+        let name = Arc::new(StringArray::from(vec!["c"]));
+        let batch = RecordBatch::try_from_iter([("id", id as _), ("name", name as _)]).unwrap();
+        let add_b = write_data_file(&store, "b.parquet", batch);
+        write_commit_to_store(&store, vec![
+                metadata(
+                    r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}},{"name":"name","type":"string","nullable":true,"metadata":{}}]}"#,
+                ),
+                add_b,
+            ], 1).unwrap();
+        for follower in [&mut follower, &mut latest_follower] {
+            assert_eq!(
+                next_ids(follower, engine.clone()),
+                Some((offset(1, 0), 4, vec![3]))
+            );
+            assert!(next_ids(follower, engine.clone()).is_none());
+            assert_eq!(follower.offset(), offset(2, 0));
+        }
+
+        // resuming from an offset returns the batches from that offset onwards
+        let mut resumed = TableChangesFollower::try_new_from_offset(
+            table.location().clone(),
+            engine.as_ref(),
+            offset(0, 1),
+        )
+        .unwrap();
+        assert_eq!(
+            next_ids(&mut resumed, engine.clone()),
+            Some((offset(1, 0), 4, vec![3]))
+        );
+
+        // versions 2 and 3 are committed before the next poll, which reads both of them
+        for (version, path, id) in [(2, "c.parquet", 4), (3, "d.parquet", 5)] {
+            let id = Arc::new(Int32Array::from(vec![id]));
+            let name = Arc::new(StringArray::from(vec![path]));
+            let batch = RecordBatch::try_from_iter([("id", id as _), ("name", name as _)]).unwrap();
+            let add = write_data_file(&store, path, batch);
+            write_commit_to_store(&store, vec![add], version).unwrap();
+        }
+        assert_eq!(
+            next_ids(&mut follower, engine.clone()),
+            Some((offset(2, 0), 4, vec![4]))
+        );
+        assert_eq!(
+            next_ids(&mut follower, engine.clone()),
+            Some((offset(3, 0), 4, vec![5]))
+        );
+        assert!(next_ids(&mut follower, engine.clone()).is_none());
+
+        // version 4 upgrades the protocol to a reader feature not supported by change data feed
+        let protocol =
+            Protocol::try_new(3, 7, Some(vec!["typeWidening"]), Some(vec!["typeWidening"]))
+                .unwrap();
+        write_commit_to_store(&store, vec![Action::Protocol(protocol)], 4).unwrap();
+        assert!(matches!(
+            follower.next_batch(engine.clone()),
+            Err(Error::ChangeDataFeedUnsupported(4))
+        ));
+        assert_eq!(follower.offset(), offset(4, 0));
+    }
+}
@@ -14,10 +14,13 @@ use crate::engine_data::{GetData, TypedGetData};
 use crate::expressions::{column_name, ColumnName};
 use crate::path::ParsedLogPath;
 use crate::scan::data_skipping::DataSkippingFilter;
+use crate::scan::log_replay::{is_file_partition_pruned, parse_partition_values};
 use crate::scan::state::DvInfo;
 use crate::schema::{ArrayType, ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructType};
 use crate::table_changes::scan_file::{cdf_scan_row_expression, cdf_scan_row_schema};
-use crate::table_changes::{check_cdf_table_properties, ensure_cdf_read_supported};
+use crate::table_changes::{
+    check_cdf_table_properties, ensure_cdf_read_supported, is_cdf_schema_compatible,
+};
 use crate::table_properties::TableProperties;
 use crate::utils::require;
 use crate::{DeltaResult, Engine, EngineData, Error, PredicateRef, RowVisitor};
@@ -51,14 +54,22 @@ pub(crate) fn table_changes_action_iter(
     engine: Arc<dyn Engine>,
     commit_files: impl IntoIterator<Item = ParsedLogPath>,
     table_schema: SchemaRef,
+    partition_columns: &[String],
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
+    let partition_filter = physical_predicate.as_ref().map(|(predicate, _)| {
+        Arc::new(PartitionFilter::new(
+            predicate.clone(),
+            table_schema.clone(),
+            partition_columns,
+        ))
+    });
     let filter = DataSkippingFilter::new(engine.as_ref(), physical_predicate).map(Arc::new);
     let result = commit_files
         .into_iter()
         .map(move |commit_file| -> DeltaResult<_> {
             let scanner = LogReplayScanner::try_new(engine.as_ref(), commit_file, &table_schema)?;
-            scanner.into_scan_batches(engine.clone(), filter.clone())
+            scanner.into_scan_batches(engine.clone(), filter.clone(), partition_filter.clone())
         }) //Iterator-Result-Iterator-Result
         .flatten_ok() // Iterator-Result-Result
         .map(|x| x?); // Iterator-Result
@@ -80,9 +91,8 @@ pub(crate) fn table_changes_action_iter(
 ///       phase, so we must perform it ahead of time in phase 1.
 ///     - Ensure that reading is supported on any protocol updates.
 ///     - Ensure that Change Data Feed is enabled for any metadata update. See  [`TableProperties`]
-///     - Ensure that any schema update is compatible with the provided `schema`. Currently, schema
-///       compatibility is checked through schema equality. This will be expanded in the future to
-///       allow limited schema evolution.
+///     - Ensure that any schema update is compatible with the provided `schema`. See
+///       [`TableChanges`] for the schema changes that are allowed.
 ///
 /// Note: We check the protocol, change data feed enablement, and schema compatibility in phase 1
 /// in order to detect errors and fail early.
@@ -106,6 +116,10 @@ pub(crate) fn table_changes_action_iter(
 ///     - If a `cdc` action was found in the prepare phase, only `cdc` actions are selected
 ///     - Otherwise, select `add` and `remove` actions. Note that only `remove` actions that do not
 ///       share a path with an `add` action are selected.
+///     - Actions are skipped if the query predicate is false for their partition values (partition
+///       pruning), and `add` actions are also skipped using their file statistics (data skipping).
+///       Pruning never breaks up an `add`/`remove` pair that shares a path, since both actions have
+///       the same partition values, and the paired `remove` is never selected.
 ///
 /// Note: As a consequence of the two phases, LogReplayScanner will iterate over each action in the
 /// commit twice. It also may use an unbounded amount of memory, proportional to the number of
@@ -182,11 +196,11 @@ impl LogReplayScanner {
             }
             if let Some((schema, configuration)) = visitor.metadata_info {
                 let schema: StructType = serde_json::from_str(&schema)?;
-                // Currently, schema compatibility is defined as having equal schema types. In the
-                // future, more permisive schema evolution will be supported.
+                // Data written with this schema is read with the table (end) schema, so the commit
+                // schema must be readable as the table schema, without renamed or dropped columns.
                 // See: https://github.com/delta-io/delta-kernel-rs/issues/523
                 require!(
-                    table_schema.as_ref() == &schema,
+                    is_cdf_schema_compatible(&schema, table_schema),
                     Error::change_data_feed_incompatible_schema(table_schema, &schema)
                 );
                 let table_properties = TableProperties::from(configuration);
@@ -216,6 +230,7 @@ impl LogReplayScanner {
         self,
         engine: Arc<dyn Engine>,
         filter: Option<Arc<DataSkippingFilter>>,
+        partition_filter: Option<Arc<PartitionFilter>>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
         let Self {
             has_cdc_action,
@@ -252,8 +267,12 @@ impl LogReplayScanner {
                 None => vec![true; actions.len()],
             };
 
-            let mut visitor =
-                FileActionSelectionVisitor::new(&remove_dvs, selection_vector, has_cdc_action);
+            let mut visitor = FileActionSelectionVisitor::new(
+                &remove_dvs,
+                selection_vector,
+                has_cdc_action,
+                partition_filter.as_deref(),
+            );
             visitor.visit_rows_of(actions.as_ref())?;
             let scan_metadata = evaluator.evaluate(actions.as_ref())?;
             Ok(TableChangesScanMetadata {
@@ -366,6 +385,7 @@ struct FileActionSelectionVisitor<'a> {
     selection_vector: Vec<bool>,
     has_cdc_action: bool,
     remove_dvs: &'a HashMap<String, DvInfo>,
+    partition_filter: Option<&'a PartitionFilter>,
 }
 
 impl<'a> FileActionSelectionVisitor<'a> {
@@ -373,11 +393,31 @@ impl<'a> FileActionSelectionVisitor<'a> {
         remove_dvs: &'a HashMap<String, DvInfo>,
         selection_vector: Vec<bool>,
         has_cdc_action: bool,
+        partition_filter: Option<&'a PartitionFilter>,
     ) -> Self {
         FileActionSelectionVisitor {
             selection_vector,
             has_cdc_action,
             remove_dvs,
+            partition_filter,
+        }
+    }
+
+    /// Returns `true` if the action at row `i` with its partition values in `getter` is pruned
+    /// by the partition filter.
+    fn is_partition_pruned<'b>(
+        &self,
+        i: usize,
+        getter: &'b dyn GetData<'b>,
+        field_name: &str,
+    ) -> DeltaResult<bool> {
+        let Some(partition_filter) = self.partition_filter else {
+            return Ok(false);
+        };
+        let partition_values: Option<HashMap<String, String>> = getter.get_opt(i, field_name)?;
+        match partition_values {
+            Some(partition_values) => partition_filter.is_pruned(&partition_values),
+            None => Ok(false),
         }
     }
     fn schema() -> Arc<StructType> {
@@ -395,12 +435,22 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const BOOLEAN: DataType = DataType::BOOLEAN;
+            let string_string_map: DataType = MapType::new(STRING, STRING, true).into();
             let types_and_names = vec![
                 (STRING, column_name!("cdc.path")),
+                (
+                    string_string_map.clone(),
+                    column_name!("cdc.partitionValues"),
+                ),
                 (STRING, column_name!("add.path")),
+                (
+                    string_string_map.clone(),
+                    column_name!("add.partitionValues"),
+                ),
                 (BOOLEAN, column_name!("add.dataChange")),
                 (STRING, column_name!("remove.path")),
                 (BOOLEAN, column_name!("remove.dataChange")),
+                (string_string_map, column_name!("remove.partitionValues")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -410,7 +460,7 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
 
     fn visit<'b>(&mut self, row_count: usize, getters: &[&'b dyn GetData<'b>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 5,
+            getters.len() == 8,
             Error::InternalError(format!(
                 "Wrong number of FileActionSelectionVisitor getters: {}",
                 getters.len()
@@ -422,17 +472,58 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
                 continue;
             }
 
-            if self.has_cdc_action {
-                self.selection_vector[i] = getters[0].get_str(i, "cdc.path")?.is_some()
-            } else if getters[1].get_str(i, "add.path")?.is_some() {
-                self.selection_vector[i] = getters[2].get(i, "add.dataChange")?;
-            } else if let Some(path) = getters[3].get_str(i, "remove.path")? {
-                let data_change: bool = getters[4].get(i, "remove.dataChange")?;
-                self.selection_vector[i] = data_change && !self.remove_dvs.contains_key(path)
+            self.selection_vector[i] = if self.has_cdc_action {
+                getters[0].get_str(i, "cdc.path")?.is_some()
+                    && !self.is_partition_pruned(i, getters[1], "cdc.partitionValues")?
+            } else if getters[2].get_str(i, "add.path")?.is_some() {
+                getters[4].get(i, "add.dataChange")?
+                    && !self.is_partition_pruned(i, getters[3], "add.partitionValues")?
+            } else if let Some(path) = getters[5].get_str(i, "remove.path")? {
+                let data_change: bool = getters[6].get(i, "remove.dataChange")?;
+                data_change
+                    && !self.remove_dvs.contains_key(path)
+                    && !self.is_partition_pruned(i, getters[7], "remove.partitionValues")?
             } else {
-                self.selection_vector[i] = false
-            }
+                false
+            };
         }
         Ok(())
     }
 }
+
+/// Prunes file actions whose partition values prove that none of their rows can satisfy the scan
+/// predicate, the same way [`Scan`] does.
+///
+/// [`Scan`]: crate::scan::Scan
+pub(crate) struct PartitionFilter {
+    predicate: PredicateRef,
+    table_schema: SchemaRef,
+    /// The indexes of the partition columns in `table_schema`
+    partition_field_indices: Vec<usize>,
+}
+
+impl PartitionFilter {
+    fn new(predicate: PredicateRef, table_schema: SchemaRef, partition_columns: &[String]) -> Self {
+        let partition_field_indices = table_schema
+            .fields()
+            .positions(|field| partition_columns.contains(field.name()))
+            .collect();
+        Self {
+            predicate,
+            table_schema,
+            partition_field_indices,
+        }
+    }
+
+    fn is_pruned(&self, partition_values: &HashMap<String, String>) -> DeltaResult<bool> {
+        let partition_values = parse_partition_values(
+            &self.table_schema,
+            self.partition_field_indices.iter().copied(),
+            partition_values,
+        )?;
+        Ok(is_file_partition_pruned(
+            Some(&self.predicate),
+            &partition_values,
+        ))
+    }
+}
@@ -8,7 +8,7 @@ use crate::log_segment::LogSegment;
 use crate::path::ParsedLogPath;
 use crate::scan::state::DvInfo;
 use crate::scan::PhysicalPredicate;
-use crate::schema::{DataType, StructField, StructType};
+use crate::schema::{DataType, MetadataValue, StructField, StructType};
 use crate::table_changes::log_replay::LogReplayScanner;
 use crate::table_features::ReaderFeature;
 use crate::utils::test_utils::{Action, LocalMockTable};
@@ -87,7 +87,7 @@ async fn metadata_protocol() {
         .into_iter();
 
     let scan_batches =
-        table_changes_action_iter(engine, commits, get_schema().into(), None).unwrap();
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None).unwrap();
     let sv = result_to_sv(scan_batches);
     assert_eq!(sv, &[false, false]);
 }
@@ -112,7 +112,7 @@ async fn cdf_not_enabled() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
             .unwrap()
             .try_collect();
 
@@ -128,7 +128,7 @@ async fn unsupported_reader_feature() {
             Protocol::try_new(
                 3,
                 7,
-                Some([ReaderFeature::DeletionVectors, ReaderFeature::ColumnMapping]),
+                Some([ReaderFeature::DeletionVectors, ReaderFeature::TypeWidening]),
                 Some([""; 0]),
             )
             .unwrap(),
@@ -140,30 +140,37 @@ async fn unsupported_reader_feature() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
             .unwrap()
             .try_collect();
 
     assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(_))));
 }
 #[tokio::test]
-async fn column_mapping_should_fail() {
+async fn column_mapping_is_supported() {
     let engine = Arc::new(SyncEngine::new());
     let mut mock_table = LocalMockTable::new();
     let schema_string = serde_json::to_string(&get_schema()).unwrap();
     mock_table
-        .commit([Action::Metadata(Metadata {
-            schema_string,
-            configuration: HashMap::from([
-                (
-                    "delta.enableDeletionVectors".to_string(),
-                    "true".to_string(),
-                ),
-                ("delta.enableChangeDataFeed".to_string(), "true".to_string()),
-                ("delta.columnMapping.mode".to_string(), "id".to_string()),
-            ]),
-            ..Default::default()
-        })])
+        .commit([
+            Action::Metadata(Metadata {
+                schema_string,
+                configuration: HashMap::from([
+                    ("delta.enableChangeDataFeed".to_string(), "true".to_string()),
+                    ("delta.columnMapping.mode".to_string(), "id".to_string()),
+                ]),
+                ..Default::default()
+            }),
+            Action::Protocol(
+                Protocol::try_new(
+                    3,
+                    7,
+                    Some([ReaderFeature::ColumnMapping]),
+                    Some([ReaderFeature::ColumnMapping]),
+                )
+                .unwrap(),
+            ),
+        ])
         .await;
 
     let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
@@ -171,14 +178,62 @@ async fn column_mapping_should_fail() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
             .unwrap()
             .try_collect();
+    assert!(res.is_ok());
+}
 
-    assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(_))));
+#[tokio::test]
+async fn column_mapping_rename_or_drop_fails() {
+    fn field(name: &str, id: i64) -> StructField {
+        StructField::nullable(name, DataType::INTEGER).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(id)),
+            (
+                "delta.columnMapping.physicalName",
+                MetadataValue::String(format!("col-{id}")),
+            ),
+        ])
+    }
+    async fn assert_incompatible_schema(commit_schema: StructType, cdf_schema: StructType) {
+        let engine = Arc::new(SyncEngine::new());
+        let mut mock_table = LocalMockTable::new();
+        let schema_string = serde_json::to_string(&commit_schema).unwrap();
+        mock_table
+            .commit([Action::Metadata(Metadata {
+                schema_string,
+                configuration: HashMap::from([
+                    ("delta.enableChangeDataFeed".to_string(), "true".to_string()),
+                    ("delta.columnMapping.mode".to_string(), "name".to_string()),
+                ]),
+                ..Default::default()
+            })])
+            .await;
+
+        let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
+            .unwrap()
+            .into_iter();
+        let res: DeltaResult<Vec<_>> =
+            table_changes_action_iter(engine, commits, cdf_schema.into(), &[], None)
+                .unwrap()
+                .try_collect();
+        assert!(matches!(
+            res,
+            Err(Error::ChangeDataFeedIncompatibleSchema(_, _))
+        ));
+    }
+
+    // `id` was renamed to `renamed`
+    let commit_schema = StructType::new([field("id", 1), field("value", 2)]);
+    let cdf_schema = StructType::new([field("renamed", 1), field("value", 2)]);
+    assert_incompatible_schema(commit_schema, cdf_schema).await;
+
+    // `value` was dropped and a new column with the same name was added
+    let commit_schema = StructType::new([field("id", 1), field("value", 2)]);
+    let cdf_schema = StructType::new([field("id", 1), field("value", 3)]);
+    assert_incompatible_schema(commit_schema, cdf_schema).await;
 }
 
-// Note: This should be removed once type widening support is added for CDF
 #[tokio::test]
 async fn incompatible_schemas_fail() {
     async fn assert_incompatible_schema(commit_schema: StructType, cdf_schema: StructType) {
@@ -202,7 +257,7 @@ async fn incompatible_schemas_fail() {
             .into_iter();
 
         let res: DeltaResult<Vec<_>> =
-            table_changes_action_iter(engine, commits, cdf_schema.into(), None)
+            table_changes_action_iter(engine, commits, cdf_schema.into(), &[], None)
                 .unwrap()
                 .try_collect();
 
@@ -243,8 +298,6 @@ async fn incompatible_schemas_fail() {
     ]);
     assert_incompatible_schema(cdf_schema, commit_schema).await;
 
-    // Note: Once schema evolution is supported, this should not return an error.
-    //
     // The CDF schema has fields: nullable `id`  and nullable `value`.
     // This commit has schema with fields: non-nullable `id` and nullable `value`.
     let schema = StructType::new([
@@ -260,12 +313,37 @@ async fn incompatible_schemas_fail() {
         StructField::nullable("value", DataType::STRING),
     ]);
     assert_incompatible_schema(schema, get_schema()).await;
+}
+
+#[tokio::test]
+async fn additive_schema_change_succeeds() {
+    let engine = Arc::new(SyncEngine::new());
+    let mut mock_table = LocalMockTable::new();
 
-    // Note: Once schema evolution is supported, this should not return an error.
     // The CDF schema has fields: `id` (nullable) and `value` (nullable).
-    // This commit has schema with fields: `id` (nullable).
-    let schema = get_schema().project_as_struct(&["id"]).unwrap();
-    assert_incompatible_schema(schema, get_schema()).await;
+    // This commit has schema with fields: `id` (non-nullable).
+    let commit_schema = StructType::new([StructField::not_null("id", DataType::INTEGER)]);
+    let schema_string = serde_json::to_string(&commit_schema).unwrap();
+    mock_table
+        .commit([Action::Metadata(Metadata {
+            schema_string,
+            configuration: HashMap::from([(
+                "delta.enableChangeDataFeed".to_string(),
+                "true".to_string(),
+            )]),
+            ..Default::default()
+        })])
+        .await;
+
+    let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
+        .unwrap()
+        .into_iter();
+
+    let res: DeltaResult<Vec<_>> =
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
+            .unwrap()
+            .try_collect();
+    assert!(res.is_ok());
 }
 
 #[tokio::test]
@@ -291,7 +369,7 @@ async fn add_remove() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -341,7 +419,7 @@ async fn filter_data_change() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -387,7 +465,7 @@ async fn cdc_selection() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -453,7 +531,7 @@ async fn dv() {
         },
     )])
     .into();
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -530,7 +608,7 @@ async fn data_skipping_filter() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, logical_schema.into(), predicate)
+    let sv = table_changes_action_iter(engine, commits, logical_schema.into(), &[], predicate)
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -542,6 +620,98 @@ async fn data_skipping_filter() {
     assert_eq!(sv, &[false, true, false, false, true]);
 }
 
+#[tokio::test]
+async fn partition_pruning() {
+    let engine = Arc::new(SyncEngine::new());
+    let mut mock_table = LocalMockTable::new();
+    let partition_values = |id: &str| HashMap::from([("id".to_string(), id.to_string())]);
+    mock_table
+        .commit([
+            Action::Add(Add {
+                path: "fake_path_1".into(),
+                partition_values: partition_values("4"),
+                data_change: true,
+                ..Default::default()
+            }),
+            Action::Add(Add {
+                path: "fake_path_2".into(),
+                partition_values: partition_values("5"),
+                data_change: true,
+                ..Default::default()
+            }),
+        ])
+        .await;
+    mock_table
+        .commit([
+            Action::Remove(Remove {
+                path: "fake_path_1".into(),
+                partition_values: Some(partition_values("4")),
+                data_change: true,
+                ..Default::default()
+            }),
+            Action::Remove(Remove {
+                path: "fake_path_2".into(),
+                partition_values: Some(partition_values("5")),
+                data_change: true,
+                ..Default::default()
+            }),
+        ])
+        .await;
+    mock_table
+        .commit([
+            Action::Cdc(Cdc {
+                path: "fake_path_3".into(),
+                partition_values: partition_values("4"),
+                ..Default::default()
+            }),
+            Action::Cdc(Cdc {
+                path: "fake_path_4".into(),
+                partition_values: partition_values("6"),
+                ..Default::default()
+            }),
+        ])
+        .await;
+
+    mock_table
+        .commit([Action::Add(Add {
+            path: "fake_path_5".into(),
+            data_change: true,
+            ..Default::default()
+        })])
+        .await;
+
+    // Look for actions with id > 4
+    // Look for actions with id > 4
+    let predicate = Predicate::binary(
+        BinaryPredicateOp::GreaterThan,
+        column_expr!("id"),
+        Scalar::from(4),
+    );
+    let logical_schema = get_schema();
+    let predicate = match PhysicalPredicate::try_new(&predicate, &logical_schema) {
+        Ok(PhysicalPredicate::Some(p, s)) => Some((p, s)),
+        other => panic!("Unexpected result: {:?}", other),
+    };
+    let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
+        .unwrap()
+        .into_iter();
+
+    let partition_columns = ["id".to_string()];
+    let sv = table_changes_action_iter(
+        engine,
+        commits,
+        logical_schema.into(),
+        &partition_columns,
+        predicate,
+    )
+    .unwrap()
+    .flat_map(|scan_metadata| scan_metadata.unwrap().selection_vector)
+    .collect_vec();
+
+    // Like in a scan, a missing partition value is null, so it can't satisfy the predicate
+    assert_eq!(sv, &[false, true, false, true, false, true, false]);
+}
+
 #[tokio::test]
 async fn failing_protocol() {
     let engine = Arc::new(SyncEngine::new());
@@ -576,7 +746,7 @@ async fn failing_protocol() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, get_schema().into(), &[], None)
             .unwrap()
             .try_collect();
 
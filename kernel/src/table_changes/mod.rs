@@ -1,4 +1,4 @@
-//! Provides an API to read the table's change data feed between two versions.
+//! Provides an API to read the table's change data feed between two versions or timestamps.
 //!
 //! # Example
 //! ```rust
@@ -37,15 +37,18 @@ use scan::TableChangesScanBuilder;
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Protocol};
+use crate::history_manager::resolve_timestamp_range;
 use crate::log_segment::LogSegment;
 use crate::path::AsUrl;
-use crate::schema::{DataType, Schema, StructField, StructType};
+use crate::schema::compare::{diff_schemas, SchemaComparison as _};
+use crate::schema::{DataType, Schema, SchemaChange, StructField, StructType};
 use crate::snapshot::Snapshot;
-use crate::table_features::{ColumnMappingMode, ReaderFeature};
+use crate::table_features::ReaderFeature;
 use crate::table_properties::TableProperties;
 use crate::utils::require;
 use crate::{DeltaResult, Engine, Error, Version};
 
+pub mod follower;
 mod log_replay;
 mod physical_to_logical;
 mod resolve_dvs;
@@ -80,16 +83,18 @@ static CDF_FIELDS: LazyLock<[StructField; 3]> = LazyLock::new(|| {
 ///
 ///
 /// Three properties must hold for the entire CDF range:
-/// - Reading must be supported for every commit in the range. Currently the only read feature allowed
-///   is deletion vectors. This will be expanded in the future to support more delta table features.
-///   Because only deletion vectors are supported, reader version 2 will not be allowed. That is
-//    because version 2 requires that column mapping is enabled. Reader versions 1 and 3 are allowed.
+/// - Reading must be supported for every commit in the range. Currently the only read features
+///   allowed are deletion vectors and column mapping. This will be expanded in the future to
+///   support more delta table features. Reader versions 1, 2 (which implies column mapping) and 3
+///   are allowed.
 /// - Change Data Feed must be enabled for the entire range with the `delta.enableChangeDataFeed`
-///   table property set to `true`. Performing change data feed on  tables with column mapping is
-///   currently disallowed. We check that column mapping is disabled, or the column mapping mode is `None`.
-/// - The schema for each commit must be compatible with the end schema. This means that all the
-///   same fields and their nullability are the same. Schema compatibility will be expanded in the
-///   future to allow compatible schemas that are not the exact same.
+///   table property set to `true`.
+/// - The schema for each commit must be readable as the end schema. This allows additive schema
+///   changes: the end schema may add nullable columns and relax the nullability of existing ones.
+///   Dropping, renaming or changing the type of a column is not allowed. With column mapping,
+///   columns are matched by their column mapping id, so dropping a column and adding another with
+///   the same name is not allowed either. Data written with an older schema is projected into the
+///   end schema, with `null`s for the columns it lacks.
 ///   See issue [#523](https://github.com/delta-io/delta-kernel-rs/issues/523)
 ///
 ///  # Examples
@@ -120,10 +125,10 @@ impl TableChanges {
     /// these properties:
     /// - The change data feed table feature must be enabled in both the start or end versions.
     /// - Other than the deletion vector reader feature, no other reader features are enabled for the table.
-    /// - The schemas at the start and end versions are the same.
+    /// - The schema at the start version can be read as the schema at the end version.
     ///
     /// Note that this does not check that change data feed is enabled for every commit in the
-    /// range. It also does not check that the schema remains the same for the entire range.
+    /// range. It also does not check the schema of every commit in the range.
     ///
     /// # Parameters
     /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
@@ -156,8 +161,7 @@ impl TableChanges {
         let end_snapshot = Snapshot::try_new_from(start_snapshot.clone(), engine, end_version)?;
 
         // Verify CDF is enabled at the beginning and end of the interval using
-        // [`check_cdf_table_properties`] to fail early. This also ensures that column mapping is
-        // disabled.
+        // [`check_cdf_table_properties`] to fail early.
         //
         // We also check the [`Protocol`] using [`ensure_cdf_read_supported`] to verify that
         // we support CDF with those features enabled.
@@ -173,13 +177,11 @@ impl TableChanges {
         check_table_config(&start_snapshot)?;
         check_table_config(&end_snapshot)?;
 
-        // Verify that the start and end schemas are compatible. We must still check schema
+        // Verify that the start schema can be read as the end schema. We must still check schema
         // compatibility for each schema update in the CDF range.
-        // Note: Schema compatibility check will be changed in the future to be more flexible.
-        // See issue [#523](https://github.com/delta-io/delta-kernel-rs/issues/523)
-        if start_snapshot.schema() != end_snapshot.schema() {
+        if !is_cdf_schema_compatible(&start_snapshot.schema(), &end_snapshot.schema()) {
             return Err(Error::generic(format!(
-                "Failed to build TableChanges: Start and end version schemas are different. Found start version schema {:?} and end version schema {:?}", start_snapshot.schema(), end_snapshot.schema(),
+                "Failed to build TableChanges: Start version schema cannot be read as the end version schema. Found start version schema {:?} and end version schema {:?}", start_snapshot.schema(), end_snapshot.schema(),
             )));
         }
 
@@ -200,6 +202,26 @@ impl TableChanges {
         })
     }
 
+    /// Creates a new [`TableChanges`] instance for the commits between two timestamps (in
+    /// milliseconds since the Unix epoch). The start version is the earliest commit at or after
+    /// `start_timestamp`, and the end version is the latest commit at or before `end_timestamp`,
+    /// or the newest table version if no `end_timestamp` is given.
+    ///
+    /// Commit timestamps are in-commit timestamps where those are enabled and commit file
+    /// modification times otherwise. See [`TableChanges::try_new`] for the checks performed on
+    /// the resulting version range.
+    pub fn try_new_from_timestamps(
+        table_root: Url,
+        engine: &dyn Engine,
+        start_timestamp: i64,
+        end_timestamp: Option<i64>,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Snapshot::try_new(table_root.clone(), engine, None)?;
+        let (start_version, end_version) =
+            resolve_timestamp_range(engine, &latest_snapshot, start_timestamp, end_timestamp)?;
+        Self::try_new(table_root, engine, start_version, Some(end_version))
+    }
+
     /// The start version of the `TableChanges`.
     pub fn start_version(&self) -> Version {
         self.start_version
@@ -241,28 +263,33 @@ fn check_cdf_table_properties(table_properties: &TableProperties) -> DeltaResult
         table_properties.enable_change_data_feed.unwrap_or(false),
         Error::unsupported("Change data feed is not enabled")
     );
-    require!(
-        matches!(
-            table_properties.column_mapping_mode,
-            None | Some(ColumnMappingMode::None)
-        ),
-        Error::unsupported("Change data feed not supported when column mapping is enabled")
-    );
     Ok(())
 }
 
+/// Returns `true` if data written with `schema` can be read as part of a change data feed with
+/// the `end_schema`. See the documentation of [`TableChanges`] for more details.
+fn is_cdf_schema_compatible(schema: &StructType, end_schema: &StructType) -> bool {
+    schema.can_read_as(end_schema).is_ok()
+        && !diff_schemas(schema, end_schema).iter().any(|change| {
+            matches!(
+                change,
+                SchemaChange::Dropped { .. } | SchemaChange::Renamed { .. }
+            )
+        })
+}
+
 /// Ensures that Change Data Feed is supported for a table with this [`Protocol`] .
 /// See the documentation of [`TableChanges`] for more details.
 fn ensure_cdf_read_supported(protocol: &Protocol) -> DeltaResult<()> {
     static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> =
-        LazyLock::new(|| vec![ReaderFeature::DeletionVectors]);
+        LazyLock::new(|| vec![ReaderFeature::DeletionVectors, ReaderFeature::ColumnMapping]);
     match &protocol.reader_features() {
         // if min_reader_version = 3 and all reader features are subset of supported => OK
         Some(reader_features) if protocol.min_reader_version() == 3 => {
             ensure_supported_features(reader_features, &CDF_SUPPORTED_READER_FEATURES)
         }
-        // if min_reader_version = 1 and there are no reader features => OK
-        None if protocol.min_reader_version() == 1 => Ok(()),
+        // if min_reader_version = 1 or 2 (column mapping) and there are no reader features => OK
+        None if matches!(protocol.min_reader_version(), 1 | 2) => Ok(()),
         // any other protocol is not supported
         _ => Err(Error::unsupported(
             "Change data feed not supported on this protocol",
@@ -272,11 +299,27 @@ fn ensure_cdf_read_supported(protocol: &Protocol) -> DeltaResult<()> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::actions::{Add, Cdc, Metadata, Protocol, Remove};
+    use crate::arrow::array::{Array as _, AsArray as _, Int32Array, RecordBatch, StringArray};
+    use crate::arrow::compute::{concat_batches, filter_record_batch};
+    use crate::arrow::datatypes::{Int32Type, Int64Type};
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
     use crate::engine::sync::SyncEngine;
+    use crate::object_store::memory::InMemory;
+    use crate::object_store::path::Path;
+    use crate::object_store::ObjectStore;
+    use crate::parquet::arrow::ArrowWriter;
     use crate::schema::{DataType, StructField};
+    use crate::table_changes::scan::TableChangesScan;
     use crate::table_changes::CDF_FIELDS;
-    use crate::{Error, Table};
-    use itertools::assert_equal;
+    use crate::utils::test_utils::{write_commit_to_store, Action};
+    use crate::{Engine, Error, Table};
+    use itertools::{assert_equal, Itertools};
 
     #[test]
     fn table_changes_checks_enable_cdf_flag() {
@@ -305,7 +348,7 @@ mod tests {
         let path = "./tests/data/table-with-cdf";
         let engine = Box::new(SyncEngine::new());
         let table = Table::try_from_uri(path).unwrap();
-        let expected_msg = "Failed to build TableChanges: Start and end version schemas are different. Found start version schema StructType { type_name: \"struct\", fields: {\"part\": StructField { name: \"part\", data_type: Primitive(Integer), nullable: true, metadata: {} }, \"id\": StructField { name: \"id\", data_type: Primitive(Integer), nullable: true, metadata: {} }} } and end version schema StructType { type_name: \"struct\", fields: {\"part\": StructField { name: \"part\", data_type: Primitive(Integer), nullable: true, metadata: {} }, \"id\": StructField { name: \"id\", data_type: Primitive(Integer), nullable: false, metadata: {} }} }";
+        let expected_msg = "Failed to build TableChanges: Start version schema cannot be read as the end version schema. Found start version schema StructType { type_name: \"struct\", fields: {\"part\": StructField { name: \"part\", data_type: Primitive(Integer), nullable: true, metadata: {} }, \"id\": StructField { name: \"id\", data_type: Primitive(Integer), nullable: true, metadata: {} }} } and end version schema StructType { type_name: \"struct\", fields: {\"part\": StructField { name: \"part\", data_type: Primitive(Integer), nullable: true, metadata: {} }, \"id\": StructField { name: \"id\", data_type: Primitive(Integer), nullable: false, metadata: {} }} }";
 
         // A field in the schema goes from being nullable to non-nullable
         let table_changes_res = table.table_changes(engine.as_ref(), 3, 4);
@@ -327,4 +370,219 @@ mod tests {
         let table_changes = table.table_changes(engine.as_ref(), 0, 0).unwrap();
         assert_equal(expected_schema, table_changes.schema().fields().cloned());
     }
+
+    fn put(store: &InMemory, path: Path, bytes: Vec<u8>) {
+        futures::executor::block_on(store.put(&path, bytes.into())).unwrap();
+    }
+
+    /// Writes `batch` to `path` in `store` as parquet, returning an add action for the file.
+    pub(super) fn write_data_file(store: &InMemory, path: &str, batch: RecordBatch) -> Action {
+        let mut buffer = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = buffer.len() as i64;
+        put(store, Path::from(path), buffer);
+        Action::Add(Add {
+            path: path.into(),
+            size,
+            data_change: true,
+            ..Default::default()
+        })
+    }
+
+    /// Executes `scan`, returning all of its (unmasked) rows in a single batch.
+    fn read_changes(scan: TableChangesScan, engine: Arc<dyn Engine>) -> RecordBatch {
+        let batches: Vec<RecordBatch> = scan
+            .execute(engine)
+            .unwrap()
+            .map(|scan_result| {
+                let scan_result = scan_result.unwrap();
+                let mask = scan_result.full_mask();
+                let data = ArrowEngineData::try_from_engine_data(scan_result.raw_data.unwrap())
+                    .unwrap()
+                    .into();
+                match mask {
+                    Some(mask) => filter_record_batch(&data, &mask.into()).unwrap(),
+                    None => data,
+                }
+            })
+            .collect();
+        concat_batches(&batches[0].schema(), &batches).unwrap()
+    }
+
+    #[test]
+    fn table_changes_across_additive_schema_change() {
+        let store = Arc::new(InMemory::new());
+        let engine = Arc::new(DefaultEngine::new(
+            store.clone(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let metadata = |schema_string: &str| {
+            Action::Metadata(Metadata {
+                id: "test-table".into(),
+                schema_string: schema_string.into(),
+                configuration: HashMap::from([(
+                    "delta.enableChangeDataFeed".to_string(),
+                    "true".to_string(),
+                )]),
+                ..Default::default()
+            })
+        };
+        let protocol = Protocol::try_new(1, 4, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+
+        // version 0 has an `id` column, and version 1 adds a nullable `name` column
+        let id = Arc::new(Int32Array::from(vec![1, 2]));
+        let batch = RecordBatch::try_from_iter([("id", id as _)]).unwrap();
+        let add_a = write_data_file(&store, "a.parquet", batch);
+        write_commit_to_store(&store, vec![
+                Action::Protocol(protocol),
+                metadata(
+                    r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                ),
+                add_a,
+            ], 0).unwrap();
+        let id = Arc::new(Int32Array::from(vec![3]));
+        let name = Arc::new(StringArray::from(vec!["c"]));
+        let batch = RecordBatch::try_from_iter([("id", id as _), ("name", name as _)]).unwrap();
+        let add_b = write_data_file(&store, "b.parquet", batch);
+        write_commit_to_store(&store, vec![
+                metadata(
+                    r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}},{"name":"name","type":"string","nullable":true,"metadata":{}}]}"#,
+                ),
+                add_b,
+            ], 1).unwrap();
+
+        let table = Table::try_from_uri("memory:///").unwrap();
+        let table_changes = table.table_changes(engine.as_ref(), 0, 1).unwrap();
+        assert!(table_changes.schema().field("name").is_some());
+
+        let schema = table_changes
+            .schema()
+            .project(&["id", "name", "_commit_version"])
+            .unwrap();
+        let scan = table_changes
+            .into_scan_builder()
+            .with_schema(schema)
+            .build()
+            .unwrap();
+        let batch = read_changes(scan, engine.clone());
+        let ids = batch.column(0).as_primitive::<Int32Type>();
+        let names = batch.column(1).as_string::<i32>();
+        let rows = (0..batch.num_rows())
+            .map(|i| (ids.value(i), names.is_valid(i).then(|| names.value(i))))
+            .sorted()
+            .collect_vec();
+        assert_eq!(rows, vec![(1, None), (2, None), (3, Some("c"))]);
+
+        // the same range can be addressed by timestamps
+        let table_changes = table
+            .table_changes_by_timestamp(engine.as_ref(), 0, None)
+            .unwrap();
+        assert_eq!(table_changes.start_version(), 0);
+        assert_eq!(table_changes.end_version(), 1);
+    }
+
+    #[test]
+    fn table_changes_with_column_mapping() {
+        let store = Arc::new(InMemory::new());
+        let engine = Arc::new(DefaultEngine::new(
+            store.clone(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let metadata = |name: &str| {
+            Action::Metadata(Metadata {
+                id: "test-table".into(),
+                schema_string: format!(
+                    r#"{{"type":"struct","fields":[{{"name":"{name}","type":"integer","nullable":true,"metadata":{{"delta.columnMapping.id":1,"delta.columnMapping.physicalName":"col-1"}}}}]}}"#
+                ),
+                configuration: HashMap::from([
+                    ("delta.enableChangeDataFeed".to_string(), "true".to_string()),
+                    ("delta.columnMapping.mode".to_string(), "name".to_string()),
+                    (
+                        "delta.columnMapping.maxColumnId".to_string(),
+                        "1".to_string(),
+                    ),
+                ]),
+                ..Default::default()
+            })
+        };
+        let protocol = Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+
+        // data and cdc files are written with the physical column name
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_from_iter([("col-1", Arc::new(Int32Array::from(values)) as _)])
+                .unwrap()
+        };
+        let add_a = write_data_file(&store, "a.parquet", batch(vec![1, 2]));
+        write_commit_to_store(
+            &store,
+            vec![Action::Protocol(protocol), metadata("id"), add_a],
+            0,
+        )
+        .unwrap();
+
+        // version 1 deletes the row with id 1, recording the change in a cdc file
+        let Action::Add(add_b) = write_data_file(&store, "b.parquet", batch(vec![2])) else {
+            unreachable!()
+        };
+        let ids = Arc::new(Int32Array::from(vec![1]));
+        let change_types = Arc::new(StringArray::from(vec!["delete"]));
+        let cdc_batch =
+            RecordBatch::try_from_iter([("col-1", ids as _), ("_change_type", change_types as _)])
+                .unwrap();
+        let Action::Add(cdc) = write_data_file(&store, "cdc.parquet", cdc_batch) else {
+            unreachable!()
+        };
+        write_commit_to_store(
+            &store,
+            vec![
+                Action::Remove(Remove {
+                    path: "a.parquet".into(),
+                    data_change: true,
+                    ..Default::default()
+                }),
+                Action::Add(add_b),
+                Action::Cdc(Cdc {
+                    path: cdc.path,
+                    size: cdc.size,
+                    ..Default::default()
+                }),
+            ],
+            1,
+        )
+        .unwrap();
+
+        // version 2 renames `id` to `renamed`
+        write_commit_to_store(&store, vec![metadata("renamed")], 2).unwrap();
+
+        let table = Table::try_from_uri("memory:///").unwrap();
+        let table_changes = table.table_changes(engine.as_ref(), 0, 1).unwrap();
+        let schema = table_changes
+            .schema()
+            .project(&["id", "_change_type", "_commit_version"])
+            .unwrap();
+        let scan = table_changes
+            .into_scan_builder()
+            .with_schema(schema)
+            .build()
+            .unwrap();
+        let batch = read_changes(scan, engine.clone());
+        assert_eq!(batch.schema().field(0).name(), "id");
+        let ids = batch.column(0).as_primitive::<Int32Type>();
+        let change_types = batch.column(1).as_string::<i32>();
+        let versions = batch.column(2).as_primitive::<Int64Type>();
+        let rows = (0..batch.num_rows())
+            .map(|i| (ids.value(i), change_types.value(i), versions.value(i)))
+            .collect_vec();
+        assert_eq!(
+            rows,
+            vec![(1, "insert", 0), (2, "insert", 0), (1, "delete", 1)]
+        );
+
+        // the rename is only an error if it happens inside the requested range
+        assert!(table.table_changes(engine.as_ref(), 2, 2).is_ok());
+        let res = table.table_changes(engine.as_ref(), 1, 2);
+        assert!(matches!(res, Err(Error::Generic(msg)) if msg.contains("cannot be read as")));
+    }
 }
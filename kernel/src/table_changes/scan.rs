@@ -6,7 +6,7 @@ use itertools::Itertools;
 use tracing::debug;
 use url::Url;
 
-use crate::actions::deletion_vector::split_vector;
+use crate::scan::row_index::read_with_selection_vector;
 use crate::scan::state::GlobalScanState;
 use crate::scan::{ColumnType, PhysicalPredicate, ScanResult};
 use crate::schema::{SchemaRef, StructType};
@@ -199,7 +199,14 @@ impl TableChangesScan {
             PhysicalPredicate::None => None,
         };
         let schema = self.table_changes.end_snapshot.schema();
-        let it = table_changes_action_iter(engine, commits, schema, physical_predicate)?;
+        let partition_columns = self.table_changes.partition_columns();
+        let it = table_changes_action_iter(
+            engine,
+            commits,
+            schema,
+            partition_columns,
+            physical_predicate,
+        )?;
         Ok(Some(it).into_iter().flatten())
     }
 
@@ -276,11 +283,11 @@ fn read_scan_file(
     resolved_scan_file: ResolvedCdfScanFile,
     global_state: &GlobalScanState,
     all_fields: &[ColumnType],
-    _physical_predicate: Option<PredicateRef>,
+    physical_predicate: Option<PredicateRef>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>>> {
     let ResolvedCdfScanFile {
         scan_file,
-        mut selection_vector,
+        selection_vector,
     } = resolved_scan_file;
 
     let physical_to_logical_expr =
@@ -302,53 +309,44 @@ fn read_scan_file(
         size: 0,
         location,
     };
-    // TODO(#860): we disable predicate pushdown until we support row indexes.
-    let read_result_iter =
-        engine
-            .parquet_handler()
-            .read_parquet_files(&[file], physical_schema, None)?;
+    // Gets the selection vector for a data batch. There are three cases to consider:
+    // 1. A scan file derived from a deletion vector pair getting resolved.
+    // 2. A scan file that was not the result of a resolved pair, and has a deletion vector.
+    // 3. A scan file that was not the result of a resolved pair, and has no deletion vector.
+    //
+    // # Case 1
+    // If the scan file is derived from a deletion vector pair, rows past the end of its selection
+    // vector are not selected. Consider a resolved selection vector `[0, 1]`. Only row 1 has
+    // changed. If there were more rows (for example 4 total), then none of them have changed.
+    // Hence, the selection vector is extended to become `[0, 1, 0, 0]`.
+    //
+    // # Case 2
+    // If the scan file has a deletion vector but is unpaired, rows past the end of its selection
+    // vector are selected. Consider a deletion vector with row 1 deleted. This generates a
+    // selection vector `[1, 0, 1]`. Only row 1 is deleted. Rows 0 and 2 are selected. If there
+    // are more rows (for example 4), then all the extra rows should be selected. The selection
+    // vector becomes `[1, 0, 1, 1]`.
+    //
+    // # Case 3
+    // These scan files are either simple adds, removes, or cdc files. This case is a noop because
+    // the selection vector is `None`.
+    let read_result_iter = read_with_selection_vector(
+        engine,
+        file,
+        &physical_schema,
+        physical_predicate,
+        selection_vector,
+        !is_dv_resolved_pair,
+    )?;
 
     let result = read_result_iter.map(move |batch| -> DeltaResult<_> {
-        let batch = batch?;
+        let (batch, raw_mask) = batch?;
         // to transform the physical data into the correct logical form
         let logical = phys_to_logical_eval.evaluate(batch.as_ref());
-        let len = logical.as_ref().map_or(0, |res| res.len());
-        // need to split the dv_mask. what's left in dv_mask covers this result, and rest
-        // will cover the following results. we `take()` out of `selection_vector` to avoid
-        // trying to return a captured variable. We're going to reassign `selection_vector`
-        // to `rest` in a moment anyway
-        let mut sv = selection_vector.take();
-
-        // Gets the selection vector for a data batch with length `len`. There are three cases to
-        // consider:
-        // 1. A scan file derived from a deletion vector pair getting resolved.
-        // 2. A scan file that was not the result of a resolved pair, and has a deletion vector.
-        // 3. A scan file that was not the result of a resolved pair, and has no deletion vector.
-        //
-        // # Case 1
-        // If the scan file is derived from a deletion vector pair, its selection vector should be
-        // extended with `false`. Consider a resolved selection vector `[0, 1]`. Only row 1 has
-        // changed. If there were more rows (for example 4 total), then none of them have changed.
-        // Hence, the selection vector is extended to become `[0, 1, 0, 0]`.
-        //
-        // # Case 2
-        // If the scan file has a deletion vector but is unpaired, its selection vector should be
-        // extended with `true`. Consider a deletion vector with row 1 deleted. This generates a
-        // selection vector `[1, 0, 1]`. Only row 1 is deleted. Rows 0 and 2 are selected. If there
-        // are more rows (for example 4), then all the extra rows should be selected. The selection
-        // vector becomes `[1, 0, 1, 1]`.
-        //
-        // # Case 3
-        // These scan files are either simple adds, removes, or cdc files. This case is a noop because
-        // the selection vector is `None`.
-        let extend = Some(!is_dv_resolved_pair);
-        let rest = split_vector(sv.as_mut(), len, extend);
-        let result = ScanResult {
+        Ok(ScanResult {
             raw_data: logical,
-            raw_mask: sv,
-        };
-        selection_vector = rest;
-        Ok(result)
+            raw_mask,
+        })
     });
     Ok(result)
 }
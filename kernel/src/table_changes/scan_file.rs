@@ -337,6 +337,7 @@ mod tests {
             Arc::new(engine),
             log_segment.ascending_commit_files.clone(),
             table_schema.into(),
+            &[],
             None,
         )
         .unwrap();
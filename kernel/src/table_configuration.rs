@@ -182,14 +182,14 @@ impl TableConfiguration {
     #[internal_api]
     pub(crate) fn is_cdf_read_supported(&self) -> bool {
         static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> =
-            LazyLock::new(|| vec![ReaderFeature::DeletionVectors]);
+            LazyLock::new(|| vec![ReaderFeature::DeletionVectors, ReaderFeature::ColumnMapping]);
         let protocol_supported = match self.protocol.reader_features() {
             // if min_reader_version = 3 and all reader features are subset of supported => OK
             Some(reader_features) if self.protocol.min_reader_version() == 3 => {
                 ensure_supported_features(reader_features, &CDF_SUPPORTED_READER_FEATURES).is_ok()
             }
-            // if min_reader_version = 1 and there are no reader features => OK
-            None => self.protocol.min_reader_version() == 1,
+            // if min_reader_version = 1 or 2 (column mapping) and there are no reader features => OK
+            None => matches!(self.protocol.min_reader_version(), 1 | 2),
             // any other protocol is not supported
             _ => false,
         };
@@ -197,11 +197,7 @@ impl TableConfiguration {
             .table_properties
             .enable_change_data_feed
             .unwrap_or(false);
-        let column_mapping_disabled = matches!(
-            self.table_properties.column_mapping_mode,
-            None | Some(ColumnMappingMode::None)
-        );
-        protocol_supported && cdf_enabled && column_mapping_disabled
+        protocol_supported && cdf_enabled
     }
 
     /// Returns `true` if deletion vectors is supported on this table. To support deletion vectors,
@@ -309,7 +305,6 @@ impl TableConfiguration {
     /// If in-commit timestamps is not supported, or not enabled, this returns `None`.
     /// If in-commit timestams is enabled, but the enablement version or timestamp is not present,
     /// this returns an error.
-    #[allow(unused)]
     pub(crate) fn in_commit_timestamp_enablement(&self) -> DeltaResult<Option<(Version, i64)>> {
         if !self.is_in_commit_timestamps_enabled() {
             return Ok(None);
@@ -7,6 +7,7 @@ use crate::actions::schemas::{GetNullableContainerStructField, GetStructField};
 use crate::actions::SetTransaction;
 use crate::actions::COMMIT_INFO_NAME;
 use crate::actions::{get_log_add_schema, get_log_commit_info_schema};
+use crate::crc::AddedFiles;
 use crate::error::Error;
 use crate::expressions::{column_expr, Scalar, StructData};
 use crate::path::ParsedLogPath;
@@ -16,6 +17,10 @@ use crate::{DataType, DeltaResult, Engine, EngineData, Expression, Version};
 
 use url::Url;
 
+mod post_commit_hook;
+
+pub use post_commit_hook::{CheckpointHook, LogCleanupHook, PostCommitHook, VersionChecksumHook};
+
 const KERNEL_VERSION: &str = env!("CARGO_PKG_VERSION");
 const UNKNOWN_OPERATION: &str = "UNKNOWN";
 
@@ -144,6 +149,8 @@ impl Transaction {
             engine_commit_info.as_ref(),
         );
         let add_actions = generate_adds(engine, self.write_metadata.iter().map(|a| a.as_ref()));
+        let added_files =
+            AddedFiles::try_from_write_metadata(self.write_metadata.iter().map(|a| a.as_ref()))?;
 
         let actions = iter::once(commit_info_actions)
             .chain(add_actions)
@@ -157,7 +164,20 @@ impl Transaction {
         // step three: commit the actions as a json file in the log
         let json_handler = engine.json_handler();
         match json_handler.write_json_file(&commit_path.location, Box::new(actions), false) {
-            Ok(()) => Ok(CommitResult::Committed(commit_version)),
+            Ok(()) => {
+                // step four: collect the post-commit hooks for the engine to run
+                let post_commit_hooks = PostCommitHook::for_commit(
+                    self.read_snapshot,
+                    commit_version,
+                    self.commit_timestamp,
+                    added_files,
+                    self.set_transactions,
+                );
+                Ok(CommitResult::Committed {
+                    version: commit_version,
+                    post_commit_hooks,
+                })
+            }
             Err(Error::FileAlreadyExists(_)) => Ok(CommitResult::Conflict(self, commit_version)),
             Err(e) => Err(e),
         }
@@ -292,14 +312,20 @@ impl WriteContext {
 }
 
 /// Result after committing a transaction. If 'committed', the version is the new version written
-/// to the log. If 'conflict', the transaction is returned so the caller can resolve the conflict
-/// (along with the version which conflicted).
+/// to the log, along with the [`PostCommitHook`]s to run. If 'conflict', the transaction is
+/// returned so the caller can resolve the conflict (along with the version which conflicted).
 // TODO(zach): in order to make the returning of a transaction useful, we need to add APIs to
 // update the transaction to a new version etc.
 #[derive(Debug)]
 pub enum CommitResult {
     /// The transaction was successfully committed at the version.
-    Committed(Version),
+    Committed {
+        /// The version of the new commit
+        version: Version,
+        /// The table maintenance tasks to run (or schedule) now that the commit succeeded, in
+        /// the order they should be run. See [`PostCommitHook`].
+        post_commit_hooks: Vec<PostCommitHook>,
+    },
     /// This transaction conflicted with an existing version (at the version given).
     Conflict(Transaction, Version),
 }
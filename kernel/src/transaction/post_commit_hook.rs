@@ -0,0 +1,202 @@
+//! Post-commit hooks: table maintenance tasks to perform after a transaction commits.
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::actions::SetTransaction;
+use crate::checkpoint::CheckpointWriter;
+use crate::crc::{read_version_checksum, AddedFiles, VersionChecksum};
+use crate::log_segment::list_expired_log_files;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, FileMeta, Version};
+
+/// The default number of commits between checkpoints, matching delta-spark.
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 10;
+/// The default retention period of log files (30 days), matching delta-spark.
+const DEFAULT_LOG_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Post-commit hooks are table maintenance tasks that should be performed after a transaction
+/// commits, but which are not part of the commit itself: writing the version checksum file,
+/// checkpointing the table and cleaning up expired log files.
+///
+/// A successful [`Transaction::commit`] returns the hooks that apply to the committed version, in
+/// the order they should be run. Hooks are self-contained, so the engine can either run them
+/// inline right after the commit, or schedule them on its own executor. Failing (or skipping) a
+/// hook never affects the correctness of the table: all of the files they write are optional.
+///
+/// The hooks are:
+/// - [`VersionChecksumHook`]: writes the `<version>.crc` file. Returned for every commit.
+/// - [`CheckpointHook`]: checkpoints the table at the committed version. Returned when the
+///   version is a multiple of the `delta.checkpointInterval` table property (default 10).
+/// - [`LogCleanupHook`]: finds the log files that have expired according to the
+///   `delta.logRetentionDuration` table property (default 30 days). Returned along with the
+///   [`CheckpointHook`], unless `delta.enableExpiredLogCleanup` is false. It should only be run
+///   after the checkpoint has been written.
+///
+/// ```no_run
+/// # use delta_kernel::transaction::{CommitResult, PostCommitHook, Transaction};
+/// # use delta_kernel::{DeltaResult, Engine};
+/// # fn run(txn: Transaction, engine: &dyn Engine) -> DeltaResult<()> {
+/// if let CommitResult::Committed { post_commit_hooks, .. } = txn.commit(engine)? {
+///     for hook in post_commit_hooks {
+///         match hook {
+///             PostCommitHook::VersionChecksum(hook) => hook.invoke(engine)?,
+///             PostCommitHook::Checkpoint(hook) => {
+///                 let writer = hook.checkpoint_writer(engine)?;
+///                 todo!() /* engine-specific logic to write the checkpoint */
+///             }
+///             PostCommitHook::LogCleanup(hook) => {
+///                 for file in hook.expired_log_files(engine)? {
+///                     todo!() /* engine-specific logic to delete the file */
+///                 }
+///             }
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Transaction::commit`]: crate::transaction::Transaction::commit
+#[derive(Debug)]
+pub enum PostCommitHook {
+    /// Write the version checksum file of the committed version
+    VersionChecksum(VersionChecksumHook),
+    /// Checkpoint the table at the committed version
+    Checkpoint(CheckpointHook),
+    /// Clean up the expired log files
+    LogCleanup(LogCleanupHook),
+}
+
+impl PostCommitHook {
+    /// The hooks that apply to a commit of `version` on top of `read_snapshot`, in the order they
+    /// should be run. This runs after the commit file is written, so it must not fail.
+    pub(crate) fn for_commit(
+        read_snapshot: Arc<Snapshot>,
+        version: Version,
+        commit_timestamp: i64,
+        added_files: AddedFiles,
+        set_transactions: Vec<SetTransaction>,
+    ) -> Vec<Self> {
+        let table_properties = read_snapshot.table_properties();
+        let checkpoint_interval = table_properties
+            .checkpoint_interval
+            .map_or(DEFAULT_CHECKPOINT_INTERVAL, |interval| interval.get());
+        let checkpoint = version % checkpoint_interval == 0;
+        let log_cleanup = checkpoint && table_properties.enable_expired_log_cleanup != Some(false);
+        let log_retention = table_properties
+            .log_retention_duration
+            .unwrap_or(DEFAULT_LOG_RETENTION);
+        // A retention too long to represent keeps every log file
+        let log_retention_ms = i64::try_from(log_retention.as_millis()).unwrap_or(i64::MAX);
+
+        let mut hooks = vec![Self::VersionChecksum(VersionChecksumHook {
+            read_snapshot: read_snapshot.clone(),
+            version,
+            added_files,
+            set_transactions,
+        })];
+        if checkpoint {
+            hooks.push(Self::Checkpoint(CheckpointHook {
+                read_snapshot: read_snapshot.clone(),
+                version,
+            }));
+        }
+        if log_cleanup {
+            hooks.push(Self::LogCleanup(LogCleanupHook {
+                read_snapshot,
+                version,
+                cutoff_timestamp: commit_timestamp.saturating_sub(log_retention_ms),
+            }));
+        }
+        hooks
+    }
+}
+
+/// Writes the version checksum (`<version>.crc`) file of the committed version.
+///
+/// The checksum is computed incrementally from the checksum of the version the transaction read,
+/// if it exists. Otherwise, it is computed from the full state of the table, which requires log
+/// replay.
+#[derive(Debug)]
+pub struct VersionChecksumHook {
+    read_snapshot: Arc<Snapshot>,
+    version: Version,
+    added_files: AddedFiles,
+    set_transactions: Vec<SetTransaction>,
+}
+
+impl VersionChecksumHook {
+    /// The committed version whose checksum file this hook writes
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Compute and write the checksum file. Fails with [`Error::FileAlreadyExists`] if the
+    /// checksum file was already written.
+    pub fn invoke(&self, engine: &dyn Engine) -> DeltaResult<()> {
+        let table_root = self.read_snapshot.table_root();
+        let storage = engine.storage_handler();
+        let read_checksum =
+            read_version_checksum(storage.as_ref(), table_root, self.read_snapshot.version())?;
+        let read_checksum = match read_checksum {
+            Some(checksum) => checksum,
+            None => VersionChecksum::try_new_from_snapshot(self.read_snapshot.clone(), engine)?,
+        };
+        read_checksum
+            .apply_commit(self.added_files, &self.set_transactions)
+            .write(engine, table_root, self.version)
+    }
+}
+
+/// Checkpoints the table at the committed version.
+#[derive(Debug)]
+pub struct CheckpointHook {
+    read_snapshot: Arc<Snapshot>,
+    version: Version,
+}
+
+impl CheckpointHook {
+    /// The committed version to checkpoint
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Creates a [`CheckpointWriter`] for the committed version. Writing the checkpoint is then
+    /// up to the engine, see the [`crate::checkpoint`] module documentation.
+    pub fn checkpoint_writer(&self, engine: &dyn Engine) -> DeltaResult<CheckpointWriter> {
+        Snapshot::try_new_from(self.read_snapshot.clone(), engine, self.version)?.checkpoint()
+    }
+}
+
+/// Finds the log files that have expired, so that the engine can delete them.
+#[derive(Debug)]
+pub struct LogCleanupHook {
+    read_snapshot: Arc<Snapshot>,
+    version: Version,
+    /// Log files written at or before this time (in milliseconds since the Unix epoch) may expire
+    cutoff_timestamp: i64,
+}
+
+impl LogCleanupHook {
+    /// The committed version. Only log files at or before this version are considered.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Lists the expired log files: those written before the log retention period that only
+    /// cover versions before the newest checkpoint that was also written before it. Deleting them
+    /// never prevents reading (or time traveling to) any version committed within the retention
+    /// period.
+    pub fn expired_log_files(&self, engine: &dyn Engine) -> DeltaResult<Vec<FileMeta>> {
+        let expired_files = list_expired_log_files(
+            engine.storage_handler().as_ref(),
+            &self.read_snapshot.log_segment().log_root,
+            self.version,
+            self.cutoff_timestamp,
+        )?;
+        Ok(expired_files
+            .into_iter()
+            .map(|log_path| log_path.location)
+            .collect())
+    }
+}
@@ -13,21 +13,26 @@ pub(crate) use require;
 
 #[cfg(test)]
 pub(crate) mod test_utils {
-    use crate::actions::{get_log_schema, Add, Cdc, CommitInfo, Metadata, Protocol, Remove};
+    use crate::actions::{
+        get_log_schema, Add, Cdc, CommitInfo, Metadata, Protocol, Remove, SetTransaction,
+    };
     use crate::arrow::array::{RecordBatch, StringArray};
     use crate::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
     use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
     use crate::engine::sync::SyncEngine;
-    use crate::Engine;
     use crate::EngineData;
+    use crate::{DeltaResult, Engine, Table};
 
     use crate::object_store::local::LocalFileSystem;
+    use crate::object_store::memory::InMemory;
     use crate::object_store::ObjectStore;
     use itertools::Itertools;
     use serde::Serialize;
     use std::{path::Path, sync::Arc};
     use tempfile::TempDir;
     use test_utils::delta_path_for_version;
+    use url::Url;
 
     #[derive(Serialize)]
     pub(crate) enum Action {
@@ -41,6 +46,8 @@ pub(crate) mod test_utils {
         Metadata(Metadata),
         #[serde(rename = "protocol")]
         Protocol(Protocol),
+        #[serde(rename = "txn")]
+        Txn(SetTransaction),
         #[allow(unused)]
         #[serde(rename = "commitInfo")]
         CommitInfo(CommitInfo),
@@ -86,6 +93,96 @@ pub(crate) mod test_utils {
         }
     }
 
+    /// Writes all actions to a _delta_log json commit file in the store.
+    pub(crate) fn write_commit_to_store(
+        store: &InMemory,
+        actions: Vec<Action>,
+        version: u64,
+    ) -> DeltaResult<()> {
+        let content = actions
+            .iter()
+            .map(|action| serde_json::to_string(action).expect("action to string"))
+            .join("\n");
+        let commit_path = delta_path_for_version(version, "json");
+        futures::executor::block_on(store.put(&commit_path, content.into()))?;
+        Ok(())
+    }
+
+    /// Writes `commits` (as versions 0, 1, ...) to a new in-memory table, returning its store, an
+    /// engine for it and the table.
+    pub(crate) fn in_memory_table(
+        commits: impl IntoIterator<Item = Vec<Action>>,
+    ) -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Table) {
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        for (version, actions) in (0..).zip(commits) {
+            write_commit_to_store(&store, actions, version).unwrap();
+        }
+        let table = Table::new(Url::parse("memory:///").unwrap());
+        (store, engine, table)
+    }
+
+    /// Create a Protocol action without any table features
+    pub(crate) fn create_basic_protocol_action() -> Action {
+        Action::Protocol(
+            Protocol::try_new(3, 7, Some(Vec::<String>::new()), Some(Vec::<String>::new()))
+                .unwrap(),
+        )
+    }
+
+    /// Create a Metadata action for a table with a single nullable integer column, `value`
+    pub(crate) fn create_metadata_action() -> Action {
+        Action::Metadata(Metadata {
+            id: "test-table".into(),
+            schema_string: "{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}".to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Create an Add action with the specified path
+    pub(crate) fn create_add_action(path: &str) -> Action {
+        Action::Add(Add {
+            path: path.into(),
+            data_change: true,
+            ..Default::default()
+        })
+    }
+
+    /// Create an Add action with the specified path, and stats for a single row whose (integer)
+    /// `value` column is `value`
+    pub(crate) fn create_add_action_with_stats(path: &str, value: i32) -> Action {
+        Action::Add(Add {
+            path: path.into(),
+            data_change: true,
+            stats: Some(format!(
+                r#"{{"numRecords":1,"minValues":{{"value":{value}}},"maxValues":{{"value":{value}}},"nullCount":{{"value":0}}}}"#
+            )),
+            ..Default::default()
+        })
+    }
+
+    /// Create a Remove action with the specified path
+    ///
+    /// The remove action has deletion_timestamp set to i64::MAX to ensure the
+    /// remove action is not considered expired during testing.
+    pub(crate) fn create_remove_action(path: &str) -> Action {
+        Action::Remove(Remove {
+            path: path.into(),
+            data_change: true,
+            deletion_timestamp: Some(i64::MAX), // Ensure the remove action is not expired
+            ..Default::default()
+        })
+    }
+
+    /// Create a SetTransaction action for the specified app id and version
+    pub(crate) fn create_txn_action(app_id: &str, version: i64) -> Action {
+        Action::Txn(SetTransaction {
+            app_id: app_id.into(),
+            version,
+            last_updated: None,
+        })
+    }
+
     /// Try to convert an `EngineData` into a `RecordBatch`. Panics if not using `ArrowEngineData` from
     /// the default module
     fn into_record_batch(engine_data: Box<dyn EngineData>) -> RecordBatch {
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use delta_kernel::engine::sync::SyncEngine;
+use delta_kernel::expressions::{column_expr, Expression as Expr};
 use delta_kernel::scan::ScanResult;
 use delta_kernel::{DeltaResult, Table};
 
@@ -42,6 +43,34 @@ fn dv_table() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn dv_table_with_predicate() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"))?;
+    let url = url::Url::from_directory_path(path).unwrap();
+    let engine = Arc::new(SyncEngine::new());
+
+    let table = Table::new(url);
+    let snapshot = Arc::new(table.snapshot(engine.as_ref(), None)?);
+
+    // The predicate is pushed down to the parquet reader but can't skip the file's only row
+    // group, so the deletion vector must still be applied to every row.
+    let predicate = Arc::new(column_expr!("value").gt(Expr::literal(2)));
+    let scan = snapshot
+        .clone()
+        .scan_builder()
+        .with_predicate(predicate)
+        .build()?;
+    let total_rows = count_total_scan_rows(scan.execute(engine.clone())?)?;
+    assert_eq!(total_rows, 8);
+
+    // The predicate skips the file's only row group
+    let predicate = Arc::new(column_expr!("value").gt(Expr::literal(100)));
+    let scan = snapshot.scan_builder().with_predicate(predicate).build()?;
+    let total_rows = count_total_scan_rows(scan.execute(engine)?)?;
+    assert_eq!(total_rows, 0);
+    Ok(())
+}
+
 #[test]
 fn non_dv_table() -> Result<(), Box<dyn std::error::Error>> {
     let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"))?;
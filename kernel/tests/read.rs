@@ -1032,6 +1032,76 @@ fn with_predicate_and_removes() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn read_table_data_with_row_filtering(
+    path: &str,
+    predicate: Pred,
+    expected: Vec<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::fs::canonicalize(PathBuf::from(path))?;
+    let url = url::Url::from_directory_path(path).unwrap();
+    let engine = Arc::new(delta_kernel::engine::sync::SyncEngine::new());
+    let snapshot = Table::new(url).snapshot(engine.as_ref(), None)?;
+    let scan = snapshot
+        .into_scan_builder()
+        .with_predicate(Arc::new(predicate))
+        .with_row_filtering(true)
+        .build()?;
+    let mut expected = expected.into_iter().map(String::from).collect_vec();
+    sort_lines!(expected);
+    read_with_execute(engine, &scan, &expected)
+}
+
+#[test]
+fn with_row_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    // The row with a NULL letter doesn't satisfy the predicate, nor its negation
+    let expected = vec![
+        "+--------+--------+---------+",
+        "| letter | number | a_float |",
+        "+--------+--------+---------+",
+        "| a      | 1      | 1.1     |",
+        "| a      | 4      | 4.4     |",
+        "+--------+--------+---------+",
+    ];
+    read_table_data_with_row_filtering(
+        "./tests/data/basic_partitioned",
+        column_expr!("letter").eq(Expr::literal("a")),
+        expected,
+    )?;
+    let expected = vec![
+        "+--------+--------+---------+",
+        "| letter | number | a_float |",
+        "+--------+--------+---------+",
+        "| b      | 2      | 2.2     |",
+        "| c      | 3      | 3.3     |",
+        "| e      | 5      | 5.5     |",
+        "+--------+--------+---------+",
+    ];
+    read_table_data_with_row_filtering(
+        "./tests/data/basic_partitioned",
+        Pred::not(column_expr!("letter").eq(Expr::literal("a"))),
+        expected,
+    )?;
+
+    // The filter is combined with the deletion vector
+    let expected = vec![
+        "+-------+",
+        "| value |",
+        "+-------+",
+        "| 4     |",
+        "| 5     |",
+        "| 6     |",
+        "| 7     |",
+        "| 8     |",
+        "+-------+",
+    ];
+    read_table_data_with_row_filtering(
+        "./tests/data/table-with-dv-small/",
+        Pred::gt(column_expr!("value"), Expr::literal(3)),
+        expected,
+    )?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn predicate_on_non_nullable_partition_column() -> Result<(), Box<dyn std::error::Error>> {
     // Test for https://github.com/delta-io/delta-kernel-rs/issues/698
@@ -21,6 +21,7 @@ use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::DefaultEngine;
 use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
+use delta_kernel::transaction::{CommitResult, PostCommitHook};
 use delta_kernel::Error as KernelError;
 use delta_kernel::{DeltaResult, Table};
 
@@ -836,3 +837,188 @@ async fn test_write_txn_actions() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn test_write_version_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table, engine, store, table_name) in setup_tables(schema.clone(), &[]).await? {
+        let engine = Arc::new(engine);
+        let read_checksum = |version: u64| {
+            let store = store.clone();
+            async move {
+                let crc = store
+                    .get(&Path::from(format!(
+                        "/{table_name}/_delta_log/{version:020}.crc"
+                    )))
+                    .await?;
+                let crc: serde_json::Value = serde_json::from_slice(&crc.bytes().await?)?;
+                Ok::<_, Box<dyn std::error::Error>>(crc)
+            }
+        };
+
+        // commit 1 has no previous checksum, so its checksum is computed from the table state
+        // and commit 2's checksum is computed incrementally from it. The checksum files are
+        // written by the post-commit hooks.
+        for (version, app_version) in [(1, 1), (2, 2)] {
+            let mut txn = table
+                .new_transaction(engine.as_ref())?
+                .with_commit_info(new_commit_info()?)
+                .with_transaction_id("app_id".to_string(), app_version);
+            let data = RecordBatch::try_new(
+                Arc::new(schema.as_ref().try_into()?),
+                vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+            )?;
+            let write_metadata = engine
+                .write_parquet(
+                    &ArrowEngineData::new(data),
+                    &txn.get_write_context(),
+                    HashMap::new(),
+                    true,
+                )
+                .await?;
+            txn.add_write_metadata(write_metadata);
+            let CommitResult::Committed {
+                version: committed_version,
+                post_commit_hooks,
+            } = txn.commit(engine.as_ref())?
+            else {
+                panic!("commit should succeed");
+            };
+            assert_eq!(committed_version, version);
+            // only the checksum hook is returned before the (default) checkpoint interval
+            let [PostCommitHook::VersionChecksum(hook)] = post_commit_hooks.as_slice() else {
+                panic!("unexpected post-commit hooks: {post_commit_hooks:?}");
+            };
+            assert_eq!(hook.version(), version);
+            hook.invoke(engine.as_ref())?;
+
+            let table_size: u64 = {
+                use futures::stream::TryStreamExt;
+                let files: Vec<_> = store
+                    .list(Some(&Path::from(format!("/{table_name}/"))))
+                    .try_collect()
+                    .await?;
+                files
+                    .iter()
+                    .filter(|f| f.location.extension() == Some("parquet"))
+                    .map(|f| f.size)
+                    .sum()
+            };
+
+            let mut crc = read_checksum(version).await?;
+            let last_updated = crc["setTransactions"][0]
+                .as_object_mut()
+                .unwrap()
+                .remove("lastUpdated");
+            assert!(last_updated.is_some());
+            assert_eq!(crc["tableSizeBytes"], json!(table_size));
+            assert_eq!(crc["numFiles"], json!(version));
+            assert_eq!(crc["numMetadata"], json!(1));
+            assert_eq!(crc["numProtocol"], json!(1));
+            assert_eq!(
+                crc["setTransactions"],
+                json!([{ "appId": "app_id", "version": app_version }])
+            );
+            assert_eq!(crc["domainMetadata"], json!([]));
+            assert_eq!(crc["metadata"]["id"], json!("test_id"));
+            assert_eq!(crc["metadata"]["configuration"], json!({}));
+            assert!(crc["protocol"]["minReaderVersion"].is_number());
+            assert!(crc.get("inCommitTimestampOpt").is_none());
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_post_commit_hooks() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let (store, engine, table_location) = setup("test_table", true);
+    let table = create_table(store.clone(), table_location, schema.clone(), &[], true).await?;
+
+    // commit 1 sets a checkpoint interval of 2, and a log retention too long to represent in
+    // i64 milliseconds (which must not fail the commit)
+    let metadata = json!({
+        "metaData": {
+            "id": "test_id",
+            "format": {
+                "provider": "parquet",
+                "options": {}
+            },
+            "schemaString": serde_json::to_string(&schema)?,
+            "partitionColumns": [],
+            "configuration": {
+                "delta.checkpointInterval": "2",
+                "delta.logRetentionDuration": "interval 10000000000000000 seconds"
+            },
+            "createdTime": 1677811175819u64
+        }
+    });
+    store
+        .put(
+            &Path::from("/test_table/_delta_log/00000000000000000001.json"),
+            to_vec(&metadata)?.into(),
+        )
+        .await?;
+
+    let commit = |table: &Table| -> DeltaResult<_> {
+        let txn = table
+            .new_transaction(&engine)?
+            .with_commit_info(new_commit_info()?);
+        match txn.commit(&engine)? {
+            CommitResult::Committed {
+                version,
+                post_commit_hooks,
+            } => Ok((version, post_commit_hooks)),
+            CommitResult::Conflict(_, version) => panic!("unexpected conflict at {version}"),
+        }
+    };
+
+    // commit 2 is a checkpoint version, so the checkpoint and log cleanup hooks are returned too
+    let (version, hooks) = commit(&table)?;
+    assert_eq!(version, 2);
+    let [PostCommitHook::VersionChecksum(checksum), PostCommitHook::Checkpoint(checkpoint), PostCommitHook::LogCleanup(log_cleanup)] =
+        hooks.as_slice()
+    else {
+        panic!("unexpected post-commit hooks: {hooks:?}");
+    };
+    assert_eq!(
+        (
+            checksum.version(),
+            checkpoint.version(),
+            log_cleanup.version()
+        ),
+        (2, 2, 2)
+    );
+    let writer = checkpoint.checkpoint_writer(&engine)?;
+    assert_eq!(
+        writer.checkpoint_path()?,
+        table
+            .location()
+            .join("_delta_log/00000000000000000002.checkpoint.parquet")?
+    );
+    // there is no checkpoint yet (and the log is younger than the retention period anyway)
+    assert!(log_cleanup.expired_log_files(&engine)?.is_empty());
+
+    // commit 3 is not
+    let (version, hooks) = commit(&table)?;
+    assert_eq!(version, 3);
+    assert!(matches!(
+        hooks.as_slice(),
+        [PostCommitHook::VersionChecksum(_)]
+    ));
+    Ok(())
+}